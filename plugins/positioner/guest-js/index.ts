@@ -2,26 +2,47 @@
 // SPDX-License-Identifier: MIT
 
 import { invoke } from "@tauri-apps/api/tauri";
+import { UnlistenFn } from "@tauri-apps/api/event";
+import { appWindow } from "@tauri-apps/api/window";
 
 /**
  * Well known window positions.
+ *
+ * The values are the names the plugin uses in its payloads. The tray-relative positions
+ * require the `system-tray` feature.
  */
 export enum Position {
-  TopLeft = 0,
-  TopRight,
-  BottomLeft,
-  BottomRight,
-  TopCenter,
-  BottomCenter,
-  LeftCenter,
-  RightCenter,
-  Center,
-  TrayLeft,
-  TrayBottomLeft,
-  TrayRight,
-  TrayBottomRight,
-  TrayCenter,
-  TrayBottomCenter,
+  TopLeft = "top-left",
+  TopRight = "top-right",
+  BottomLeft = "bottom-left",
+  BottomRight = "bottom-right",
+  TopCenter = "top-center",
+  BottomCenter = "bottom-center",
+  LeftCenter = "left-center",
+  RightCenter = "right-center",
+  Center = "center",
+  TrayLeft = "tray-left",
+  TrayBottomLeft = "tray-bottom-left",
+  TrayRight = "tray-right",
+  TrayBottomRight = "tray-bottom-right",
+  TrayCenter = "tray-center",
+  TrayBottomCenter = "tray-bottom-center",
+  TrayTopLeft = "tray-top-left",
+  TrayTopCenter = "tray-top-center",
+  TrayTopRight = "tray-top-right",
+  TrayAdjacent = "tray-adjacent",
+  Pointer = "pointer",
+  PointerCenter = "pointer-center",
+}
+
+/**
+ * How an animated move is played.
+ */
+export interface AnimationOptions {
+  /** The duration of the animation in milliseconds. */
+  duration: number;
+  /** The easing curve, `"linear"` by default. */
+  easing?: "linear" | "easeInOut" | "easeOut";
 }
 
 /**
@@ -29,9 +50,200 @@ export enum Position {
  * All positions are relative to the **current** screen.
  *
  * @param to The {@link Position} to move to.
+ * @param offset An optional offset in physical pixels, applied after the position is computed.
+ * Positive values move the window right and down.
+ * @param trayId The id of the tray icon tray-relative positions refer to. Defaults to the
+ * icon that was clicked most recently.
+ * @param animation Glides the window to the position instead of moving it at once.
  */
-export async function moveWindow(to: Position): Promise<void> {
+export async function moveWindow(
+  to: Position,
+  offset?: { x: number; y: number },
+  trayId?: string,
+  animation?: AnimationOptions,
+): Promise<void> {
   await invoke("plugin:positioner|move_window", {
     position: to,
+    offset,
+    trayId,
+    duration: animation?.duration,
+    easing: animation?.easing,
+  });
+}
+
+/**
+ * The payload of the event emitted after the plugin moved the window to a {@link Position}.
+ */
+export interface MovedEvent {
+  position: Position;
+  physicalPosition: { x: number; y: number };
+  /** The name of the monitor the window was positioned on, or `null`. */
+  monitor: string | null;
+}
+
+/**
+ * Calls `cb` every time the plugin moves the current window to a {@link Position}, e.g.
+ * to point an arrow at the tray icon.
+ *
+ * @returns A function to stop listening.
+ */
+export async function onMoved(
+  cb: (event: MovedEvent) => void,
+): Promise<UnlistenFn> {
+  return await appWindow.listen<MovedEvent>("positioner://moved", (event) => {
+    cb(event.payload);
+  });
+}
+
+/**
+ * Moves the `Window` to a fraction of the free space on its current monitor using
+ * `WindowExt.move_window_relative()`.
+ *
+ * `{ x: 0, y: 0 }` puts the window in the top-left corner and `{ x: 1, y: 1 }` in the
+ * bottom-right corner. Values outside `0` to `1` are clamped.
+ */
+export async function moveWindowRelative(to: {
+  x: number;
+  y: number;
+}): Promise<void> {
+  await invoke("plugin:positioner|move_window_relative", to);
+}
+
+/**
+ * Moves the `Window` to the given {@link Position} within the bounds of another window
+ * using `WindowExt.move_window_relative_to_window()`, e.g. `Position.TopRight` aligns the
+ * top-right corners of both windows.
+ *
+ * @param anchor The label of the window to position against.
+ * @param to The monitor-relative {@link Position} to move to.
+ */
+export async function moveWindowRelativeToWindow(
+  anchor: string,
+  to: Position,
+): Promise<void> {
+  await invoke("plugin:positioner|move_window_relative_to_window", {
+    anchor,
+    position: to,
   });
 }
+
+/**
+ * Moves the `Window` to the given {@link Position} on a specific monitor using
+ * `WindowExt.move_window_with_monitor()`. Fails with a list of the available monitors if
+ * none matches.
+ *
+ * @param to The {@link Position} to move to.
+ * @param monitor The index of the monitor in `availableMonitors()`, or its name.
+ */
+export async function moveWindowToMonitor(
+  to: Position,
+  monitor: number | string,
+): Promise<void> {
+  await invoke("plugin:positioner|move_window_to_monitor", {
+    position: to,
+    monitor,
+  });
+}
+
+/**
+ * Returns the {@link Position} the `Window` currently occupies on its monitor, or `null`
+ * if it isn't within `tolerance` physical pixels of any.
+ *
+ * @param tolerance The allowed distance from a position on each axis, in physical pixels.
+ */
+export async function currentPositionKind(
+  tolerance = 0,
+): Promise<Position | null> {
+  return await invoke("plugin:positioner|current_position_kind", { tolerance });
+}
+
+/**
+ * Details about a monitor, as reported by the positioner plugin.
+ */
+export interface MonitorDetails {
+  name: string | null;
+  position: { x: number; y: number };
+  size: { width: number; height: number };
+  scaleFactor: number;
+  isPrimary: boolean;
+  /** The refresh rate in Hz, or `null` if the platform doesn't report it. */
+  refreshRate: number | null;
+}
+
+/**
+ * Returns the details of every monitor, in the same order as `availableMonitors()`.
+ */
+export async function getMonitors(): Promise<MonitorDetails[]> {
+  return await invoke("plugin:positioner|get_monitors");
+}
+
+/**
+ * Returns the last known bounds of the tray icon with the given id, or of the most
+ * recently updated one, in physical pixels.
+ *
+ * The bounds are captured by `on_tray_event`, so this is `null` until the first tray
+ * event arrives, and always `null` unless the `system-tray` feature is enabled.
+ *
+ * @param trayId The id of the tray icon, defaults to the most recently updated one.
+ */
+export async function getTrayRect(trayId?: string): Promise<{
+  position: { x: number; y: number };
+  size: { width: number; height: number };
+} | null> {
+  return await invoke("plugin:positioner|get_tray_rect", { trayId });
+}
+
+/**
+ * Where a window is and which {@link Position} it is docked closest to.
+ */
+export interface WindowPlacement {
+  label: string;
+  position: { x: number; y: number };
+  size: { width: number; height: number };
+  /** The index of the window's monitor in `availableMonitors()`, or `null`. */
+  monitorIndex: number | null;
+  /** The monitor-relative position nearest to the window, or `null`. */
+  anchor: Position | null;
+}
+
+/**
+ * Returns the geometry of every window of the app, sorted by label.
+ */
+export async function getAllWindowGeometries(): Promise<WindowPlacement[]> {
+  return await invoke("plugin:positioner|get_all_window_geometries");
+}
+
+/**
+ * Returns the name of the zone under the given point, e.g. the cursor while the window is
+ * dragged, or `null` if the point isn't inside any zone.
+ *
+ * @param point The point in physical screen coordinates.
+ */
+export async function previewZoneAt(point: {
+  x: number;
+  y: number;
+}): Promise<string | null> {
+  return await invoke("plugin:positioner|preview_zone_at", { point });
+}
+
+/**
+ * Moves and resizes the window to fill the zone under the given point, e.g. when a drag
+ * ends. Returns the name of the zone, or `null` if the point isn't inside any zone.
+ *
+ * @param point The point in physical screen coordinates.
+ */
+export async function dropIntoZoneAt(point: {
+  x: number;
+  y: number;
+}): Promise<string | null> {
+  return await invoke("plugin:positioner|drop_into_zone_at", { point });
+}
+
+/**
+ * Returns everything the plugin knows about the monitors, the tray icon and the app's
+ * windows, to be attached to bug reports. It contains screen geometry, monitor names and
+ * window labels, but nothing the windows display.
+ */
+export async function diagnostics(): Promise<Record<string, unknown>> {
+  return await invoke("plugin:positioner|diagnostics");
+}
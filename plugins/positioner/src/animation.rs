@@ -0,0 +1,289 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{ext::WindowExtInternal, plugin_state, Error, Position, Rect, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    thread::{sleep, spawn},
+    time::{Duration, Instant},
+};
+use tauri::{PhysicalPosition, Runtime, Window};
+
+/// The time between two animation frames, about 60 frames per second.
+const FRAME: Duration = Duration::from_millis(16);
+
+/// The easing curve of an animated move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseInOut,
+    EaseOut,
+}
+
+impl Easing {
+    /// Maps the linear progress `t` (`0.0..=1.0`) onto the curve.
+    pub fn apply(self, t: f64) -> f64 {
+        match self {
+            Self::Linear => t,
+            Self::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Self::EaseOut => 1.0 - (1.0 - t).powi(2),
+        }
+    }
+}
+
+/// How an animated move is played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AnimationConfig {
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl AnimationConfig {
+    pub fn new(duration: Duration, easing: Easing) -> Self {
+        Self { duration, easing }
+    }
+}
+
+/// How a flicked window glides and snaps, see [`WindowExt::flick`](crate::WindowExt::flick).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlickConfig {
+    /// How fast the window slows down, in physical pixels per second squared.
+    pub deceleration: f64,
+    /// The speed, in physical pixels per second, below which the window stops gliding and
+    /// snaps to the nearest [`Position`](crate::Position).
+    pub snap_threshold: f64,
+    /// How the final snap is played.
+    pub snap: AnimationConfig,
+}
+
+impl Default for FlickConfig {
+    fn default() -> Self {
+        Self {
+            deceleration: 4000.0,
+            snap_threshold: 50.0,
+            snap: AnimationConfig::new(Duration::from_millis(200), Easing::EaseOut),
+        }
+    }
+}
+
+/// The generation of the latest animation of every window, keyed by label.
+///
+/// Starting an animation bumps the generation, which makes the previous one stop.
+#[derive(Default)]
+pub(crate) struct Animations(Mutex<HashMap<String, u64>>);
+
+impl Animations {
+    fn next(&self, label: &str) -> u64 {
+        let mut generations = self.0.lock().unwrap();
+        let generation = generations.entry(label.to_string()).or_default();
+        *generation += 1;
+        *generation
+    }
+
+    fn is_current(&self, label: &str, generation: u64) -> bool {
+        self.0.lock().unwrap().get(label) == Some(&generation)
+    }
+}
+
+/// Glides the window from its current position to `target` on a background thread, then
+/// calls `on_done` unless the animation was interrupted.
+///
+/// Any animation already running for the window is interrupted.
+pub(crate) fn animate_to<R: Runtime>(
+    window: &Window<R>,
+    target: PhysicalPosition<i32>,
+    config: AnimationConfig,
+    on_done: impl FnOnce() + Send + 'static,
+) -> Result<()> {
+    let generation = plugin_state::<R, Animations>(window).next(window.label());
+    let start = window.outer_position()?;
+
+    if config.duration.is_zero() {
+        window.set_position(tauri::Position::Physical(target))?;
+        on_done();
+        return Ok(());
+    }
+
+    let window = window.clone();
+    spawn(move || {
+        if glide(&window, generation, start, target, config) {
+            on_done();
+        }
+    });
+
+    Ok(())
+}
+
+/// Moves the window through the given waypoints one after the other on a background
+/// thread, optionally starting over after the last one.
+///
+/// Any animation already running for the window is interrupted.
+pub(crate) fn animate_path<R: Runtime>(
+    window: &Window<R>,
+    waypoints: Vec<(Position, Duration)>,
+    looping: bool,
+) -> Result<()> {
+    let generation = plugin_state::<R, Animations>(window).next(window.label());
+    if waypoints.is_empty() {
+        return Ok(());
+    }
+
+    let window = window.clone();
+    spawn(move || loop {
+        for (position, duration) in &waypoints {
+            let segment = (|| {
+                let monitor = window.current_monitor()?.ok_or(Error::NoMonitor)?;
+                let target = window.position_on(*position, &monitor)?;
+                window.record_scale_factor(&monitor);
+                Ok::<_, Error>((window.outer_position()?, target))
+            })();
+            let (start, target) = match segment {
+                Ok(segment) => segment,
+                Err(_) => return,
+            };
+
+            let config = AnimationConfig::new(*duration, Easing::EaseInOut);
+            if !glide(&window, generation, start, target, config) {
+                return;
+            }
+        }
+        if !looping {
+            return;
+        }
+    });
+
+    Ok(())
+}
+
+/// Moves the window from `start` to `target` on the current thread.
+///
+/// Returns `false` if the animation was interrupted or the window couldn't be moved.
+fn glide<R: Runtime>(
+    window: &Window<R>,
+    generation: u64,
+    start: PhysicalPosition<i32>,
+    target: PhysicalPosition<i32>,
+    config: AnimationConfig,
+) -> bool {
+    let started = Instant::now();
+    loop {
+        if !plugin_state::<R, Animations>(window).is_current(window.label(), generation) {
+            return false;
+        }
+
+        let t = if config.duration.is_zero() {
+            1.0
+        } else {
+            (started.elapsed().as_secs_f64() / config.duration.as_secs_f64()).min(1.0)
+        };
+        let progress = config.easing.apply(t);
+        let position = PhysicalPosition {
+            x: start.x + ((target.x - start.x) as f64 * progress).round() as i32,
+            y: start.y + ((target.y - start.y) as f64 * progress).round() as i32,
+        };
+
+        if window
+            .set_position(tauri::Position::Physical(position))
+            .is_err()
+        {
+            return false;
+        }
+        if t >= 1.0 {
+            return true;
+        }
+        sleep(FRAME);
+    }
+}
+
+/// Lets the window glide from its current position with the given velocity, slowing down
+/// until it snaps to the nearest monitor-relative [`Position`](crate::Position), on a
+/// background thread.
+///
+/// The window doesn't bounce: it stops moving along an axis when it reaches the edge of its
+/// monitor. Any animation already running for the window is interrupted.
+pub(crate) fn flick<R: Runtime>(
+    window: &Window<R>,
+    velocity: (f32, f32),
+    config: FlickConfig,
+) -> Result<()> {
+    let generation = plugin_state::<R, Animations>(window).next(window.label());
+    let monitor = window.current_monitor()?.ok_or(Error::NoMonitor)?;
+    let start = Rect::of_window(window)?;
+
+    let window = window.clone();
+    spawn(move || {
+        let bounds = Rect::of_monitor(&monitor);
+        let (mut x, mut y) = (start.left() as f64, start.top() as f64);
+        let (mut vx, mut vy) = (velocity.0 as f64, velocity.1 as f64);
+        let mut last = Instant::now();
+
+        loop {
+            sleep(FRAME);
+            if !plugin_state::<R, Animations>(&window).is_current(window.label(), generation) {
+                return;
+            }
+
+            let dt = last.elapsed().as_secs_f64();
+            last = Instant::now();
+            let speed = vx.hypot(vy);
+            if speed < config.snap_threshold {
+                break;
+            }
+
+            // slow down along the direction of travel
+            let slowed = (speed - config.deceleration * dt).max(0.0) / speed;
+            vx *= slowed;
+            vy *= slowed;
+            x += vx * dt;
+            y += vy * dt;
+
+            let moved = Rect::new(
+                x.round() as i32,
+                y.round() as i32,
+                start.size.width,
+                start.size.height,
+            )
+            .clamp_within(&bounds);
+            if moved.left() != x.round() as i32 {
+                vx = 0.0;
+            }
+            if moved.top() != y.round() as i32 {
+                vy = 0.0;
+            }
+            x = moved.left() as f64;
+            y = moved.top() as f64;
+
+            if window
+                .set_position(tauri::Position::Physical(moved.position))
+                .is_err()
+            {
+                return;
+            }
+        }
+
+        let current = PhysicalPosition {
+            x: x as i32,
+            y: y as i32,
+        };
+        if let Ok((_, offset)) = window.anchor_nearest_to(&monitor, current) {
+            let target = PhysicalPosition {
+                x: current.x - offset.x,
+                y: current.y - offset.y,
+            };
+            let _ = window.place_animated(target, &monitor, config.snap, || ());
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{ext::WindowExtInternal, plugin_state, Error, Position, Result};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tauri::{PhysicalSize, Runtime, Window, WindowEvent};
+
+struct AspectLock {
+    position: Position,
+    ratio: (u32, u32),
+    /// The inner size the window had after the last resize we handled.
+    last: PhysicalSize<u32>,
+    /// The size of the last correction, whose own resize event must not be corrected again.
+    correcting: Option<PhysicalSize<u32>>,
+}
+
+type Locked = Arc<Mutex<Option<AspectLock>>>;
+
+/// The aspect ratio and anchor every locked window is kept at, keyed by label.
+#[derive(Default)]
+pub(crate) struct AspectLocks(Mutex<HashMap<String, Locked>>);
+
+pub(crate) fn lock<R: Runtime>(
+    window: &Window<R>,
+    position: Position,
+    ratio_w: u32,
+    ratio_h: u32,
+) -> Result<()> {
+    let locks = plugin_state::<R, AspectLocks>(window);
+    let mut locked = locks.0.lock().unwrap();
+
+    let lock = match locked.get(window.label()) {
+        Some(existing) => existing.clone(),
+        None => {
+            let lock: Locked = Arc::default();
+            locked.insert(window.label().to_string(), lock.clone());
+
+            let window_ = window.clone();
+            let lock_ = lock.clone();
+            window.on_window_event(move |event| match event {
+                WindowEvent::Resized(size) => {
+                    let _ = keep_aspect(&window_, &lock_, *size);
+                }
+                WindowEvent::Destroyed => {
+                    plugin_state::<R, AspectLocks>(&window_)
+                        .0
+                        .lock()
+                        .unwrap()
+                        .remove(window_.label());
+                }
+                _ => {}
+            });
+            lock
+        }
+    };
+    drop(locked);
+
+    let size = window.inner_size()?;
+    lock.lock().unwrap().replace(AspectLock {
+        position,
+        ratio: (ratio_w.max(1), ratio_h.max(1)),
+        // a zero size makes whichever side differs more from the ratio drive the correction
+        last: PhysicalSize::default(),
+        correcting: None,
+    });
+    keep_aspect(window, &lock, size)
+}
+
+pub(crate) fn unlock<R: Runtime>(window: &Window<R>) {
+    if let Some(lock) = plugin_state::<R, AspectLocks>(window)
+        .0
+        .lock()
+        .unwrap()
+        .get(window.label())
+    {
+        lock.lock().unwrap().take();
+    }
+}
+
+/// Resizes the window back to the locked aspect ratio if `size` breaks it, keeping the side
+/// the user changed the most, and re-applies the anchor.
+fn keep_aspect<R: Runtime>(
+    window: &Window<R>,
+    lock: &Locked,
+    size: PhysicalSize<u32>,
+) -> Result<()> {
+    let mut guard = lock.lock().unwrap();
+    let lock = match guard.as_mut() {
+        Some(lock) => lock,
+        None => return Ok(()),
+    };
+
+    // the resize event of our own correction
+    if lock.correcting.take() == Some(size) {
+        lock.last = size;
+        return Ok(());
+    }
+
+    let (ratio_w, ratio_h) = (lock.ratio.0 as u64, lock.ratio.1 as u64);
+    let width_changed = size.width.abs_diff(lock.last.width) as u64 * ratio_h;
+    let height_changed = size.height.abs_diff(lock.last.height) as u64 * ratio_w;
+    let target = if width_changed >= height_changed {
+        PhysicalSize {
+            width: size.width,
+            height: ((size.width as u64 * ratio_h + ratio_w / 2) / ratio_w) as u32,
+        }
+    } else {
+        PhysicalSize {
+            width: ((size.height as u64 * ratio_w + ratio_h / 2) / ratio_h) as u32,
+            height: size.height,
+        }
+    };
+    lock.last = target;
+    let position = lock.position;
+    if target != size {
+        lock.correcting = Some(target);
+    }
+    drop(guard);
+
+    // the outer size doesn't reflect the correction yet, so predict it from the frame
+    let outer = window.outer_size()?;
+    let frame = PhysicalSize {
+        width: outer.width.saturating_sub(size.width),
+        height: outer.height.saturating_sub(size.height),
+    };
+    if target != size {
+        window.set_size(tauri::Size::Physical(target))?;
+    }
+
+    let monitor = window.current_monitor()?.ok_or(Error::NoMonitor)?;
+    let anchored = window.position_on_sized(
+        position,
+        &monitor,
+        PhysicalSize {
+            width: target.width + frame.width,
+            height: target.height + frame.height,
+        },
+    )?;
+    window.place(anchored, &monitor)
+}
@@ -0,0 +1,136 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    ext::WindowExtInternal, AnimationConfig, Diagnostics, Easing, Error, MonitorDetails,
+    MonitorStrategy, Position, Rect, Result, WindowExt, WindowPlacement,
+};
+use serde::Deserialize;
+use std::time::Duration;
+use tauri::{command, AppHandle, Manager, PhysicalPosition, Runtime, Window};
+
+#[command]
+pub async fn move_window<R: Runtime>(
+    window: Window<R>,
+    position: Position,
+    offset: Option<PhysicalPosition<i32>>,
+    tray_id: Option<String>,
+    duration: Option<u64>,
+    easing: Option<Easing>,
+) -> Result<()> {
+    let offset = offset.unwrap_or(PhysicalPosition { x: 0, y: 0 });
+    let animation = duration.map(|duration| {
+        AnimationConfig::new(Duration::from_millis(duration), easing.unwrap_or_default())
+    });
+    window.move_window_with(position, offset, tray_id.as_deref(), animation)?;
+    Ok(())
+}
+
+#[command]
+pub async fn move_window_relative<R: Runtime>(window: Window<R>, x: f64, y: f64) -> Result<()> {
+    window.move_window_relative(x, y)
+}
+
+#[command]
+pub async fn move_window_relative_to_window<R: Runtime>(
+    window: Window<R>,
+    anchor: String,
+    position: Position,
+) -> Result<()> {
+    let anchor_window = window
+        .get_window(&anchor)
+        .ok_or(Error::WindowNotFound(anchor))?;
+    window.move_window_relative_to_window(&anchor_window, position)
+}
+
+/// A monitor picked from the frontend, by its index in `availableMonitors()` or its name.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum MonitorId {
+    Index(usize),
+    Name(String),
+}
+
+#[command]
+pub async fn move_window_to_monitor<R: Runtime>(
+    window: Window<R>,
+    position: Position,
+    monitor: MonitorId,
+) -> Result<()> {
+    let strategy = match monitor {
+        MonitorId::Index(index) => MonitorStrategy::Index(index),
+        MonitorId::Name(name) => MonitorStrategy::Name(name),
+    };
+    // excluded monitors are resolved too, so moving onto one fails with
+    // `Error::MonitorExcluded` instead of `Error::MonitorNotFound`
+    let monitor = match strategy.resolve_including_excluded(&window)? {
+        Some(monitor) => monitor,
+        None => {
+            let available = window
+                .available_monitors()?
+                .iter()
+                .enumerate()
+                .map(|(index, m)| match m.name() {
+                    Some(name) => format!("#{index} \"{name}\""),
+                    None => format!("#{index}"),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(Error::MonitorNotFound(format!(
+                "{} (available: {available})",
+                strategy.describe()
+            )));
+        }
+    };
+    window.move_window_with_monitor(position, &monitor)?;
+    Ok(())
+}
+
+#[command]
+pub async fn current_position_kind<R: Runtime>(
+    window: Window<R>,
+    tolerance: u32,
+) -> Result<Option<Position>> {
+    window.current_position_kind(tolerance)
+}
+
+#[command]
+pub async fn get_monitors<R: Runtime>(window: Window<R>) -> Result<Vec<MonitorDetails>> {
+    crate::get_monitors(&window)
+}
+
+#[command]
+pub async fn get_tray_rect<R: Runtime>(
+    app: AppHandle<R>,
+    tray_id: Option<String>,
+) -> Result<Option<Rect>> {
+    Ok(crate::tray_rect(&app, tray_id.as_deref()))
+}
+
+#[command]
+pub async fn get_all_window_geometries<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<WindowPlacement>> {
+    crate::get_all_window_geometries(&app)
+}
+
+#[command]
+pub async fn diagnostics<R: Runtime>(window: Window<R>) -> Result<Diagnostics> {
+    crate::diagnostics(&window)
+}
+
+#[command]
+pub async fn preview_zone_at<R: Runtime>(
+    window: Window<R>,
+    point: PhysicalPosition<i32>,
+) -> Result<Option<String>> {
+    window.preview_zone_at(point)
+}
+
+#[command]
+pub async fn drop_into_zone_at<R: Runtime>(
+    window: Window<R>,
+    point: PhysicalPosition<i32>,
+) -> Result<Option<String>> {
+    window.drop_into_zone_at(point)
+}
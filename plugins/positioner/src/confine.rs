@@ -0,0 +1,123 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{plugin_state, Rect, Result};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+use tauri::{Monitor, PhysicalPosition, Runtime, Window, WindowEvent};
+
+struct Confinement {
+    bounds: Rect,
+    /// The position of the last correction, whose own move event must not be corrected again.
+    correcting: Option<PhysicalPosition<i32>>,
+}
+
+type Confined = Arc<Mutex<Option<Confinement>>>;
+
+/// The monitor bounds every confined window is kept in, keyed by label.
+#[derive(Default)]
+pub(crate) struct Confinements(Mutex<HashMap<String, Confined>>);
+
+impl Confinements {
+    /// The bounds of every currently confined window, keyed by label.
+    pub(crate) fn bounds(&self) -> BTreeMap<String, Rect> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(label, confinement)| {
+                let confinement = confinement.lock().unwrap();
+                confinement
+                    .as_ref()
+                    .map(|confinement| (label.clone(), confinement.bounds))
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn confine<R: Runtime>(window: &Window<R>, monitor: &Monitor) -> Result<()> {
+    let bounds = Rect::of_monitor(monitor);
+    let confinements = plugin_state::<R, Confinements>(window);
+    let mut confined = confinements.0.lock().unwrap();
+
+    let confinement = match confined.get(window.label()) {
+        Some(existing) => existing.clone(),
+        None => {
+            let confinement: Confined = Arc::default();
+            confined.insert(window.label().to_string(), confinement.clone());
+
+            let window_ = window.clone();
+            let confinement_ = confinement.clone();
+            window.on_window_event(move |event| match event {
+                WindowEvent::Moved(position) => {
+                    let _ = keep_inside(&window_, &confinement_, *position);
+                }
+                WindowEvent::Destroyed => {
+                    plugin_state::<R, Confinements>(&window_)
+                        .0
+                        .lock()
+                        .unwrap()
+                        .remove(window_.label());
+                }
+                _ => {}
+            });
+            confinement
+        }
+    };
+    drop(confined);
+
+    confinement.lock().unwrap().replace(Confinement {
+        bounds,
+        correcting: None,
+    });
+    keep_inside(window, &confinement, window.outer_position()?)
+}
+
+pub(crate) fn release<R: Runtime>(window: &Window<R>) {
+    if let Some(confinement) = plugin_state::<R, Confinements>(window)
+        .0
+        .lock()
+        .unwrap()
+        .get(window.label())
+    {
+        confinement.lock().unwrap().take();
+    }
+}
+
+/// Moves the window back inside its confinement if `position` leaves it.
+fn keep_inside<R: Runtime>(
+    window: &Window<R>,
+    confinement: &Confined,
+    position: PhysicalPosition<i32>,
+) -> Result<()> {
+    let size = window.outer_size()?;
+    let mut guard = confinement.lock().unwrap();
+    let confinement = match guard.as_mut() {
+        Some(confinement) => confinement,
+        None => return Ok(()),
+    };
+
+    // the move event of our own correction
+    if confinement.correcting.take() == Some(position) {
+        return Ok(());
+    }
+
+    let rect = Rect::new(
+        position.x,
+        position.y,
+        size.width as i32,
+        size.height as i32,
+    );
+    let target = rect.clamp_within(&confinement.bounds).position;
+    if target == position {
+        return Ok(());
+    }
+    confinement.correcting = Some(target);
+    drop(guard);
+
+    window
+        .set_position(tauri::Position::Physical(target))
+        .map_err(Into::into)
+}
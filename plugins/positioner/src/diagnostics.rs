@@ -0,0 +1,45 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    confine::Confinements, get_all_window_geometries, get_monitors, plugin_state,
+    tracking::Responsive, Config, MonitorDetails, Position, Rect, Result, SizeThreshold,
+    WindowPlacement,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::{Runtime, Window};
+
+/// Everything the plugin knows about the screen and the app's windows, for bug reports.
+///
+/// It contains screen geometry, monitor names and window labels, but nothing the windows
+/// display.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    pub monitors: Vec<MonitorDetails>,
+    /// The last known rectangle of the tray icon, always `None` without the `system-tray`
+    /// feature.
+    pub tray_rect: Option<Rect>,
+    pub windows: Vec<WindowPlacement>,
+    /// The breakpoints of every window tracked with
+    /// [`WindowExt::track_window_responsive`](crate::WindowExt::track_window_responsive).
+    pub responsive: BTreeMap<String, Vec<(SizeThreshold, Position)>>,
+    /// The bounds of every window confined with
+    /// [`WindowExt::confine_to_monitor`](crate::WindowExt::confine_to_monitor).
+    pub confined: BTreeMap<String, Rect>,
+    /// The options set on the plugin [`Builder`](crate::Builder).
+    pub config: serde_json::Value,
+}
+
+/// Collects the [`Diagnostics`], e.g. for the user to paste into a bug report.
+pub fn diagnostics<R: Runtime>(window: &Window<R>) -> Result<Diagnostics> {
+    Ok(Diagnostics {
+        monitors: get_monitors(window)?,
+        tray_rect: crate::tray_rect(window, None),
+        windows: get_all_window_geometries(window)?,
+        responsive: plugin_state::<R, Responsive>(window).breakpoints(),
+        confined: plugin_state::<R, Confinements>(window).bounds(),
+        config: serde_json::to_value(&*plugin_state::<R, Config>(window))?,
+    })
+}
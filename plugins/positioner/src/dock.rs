@@ -0,0 +1,141 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{ext::WindowExtInternal, plugin_state, Error, Position, Rect, Result};
+use serde::Deserialize;
+use std::sync::Mutex;
+use tauri::{Manager, PhysicalPosition, PhysicalSize, Runtime, Window};
+
+/// A side of a window another window can be docked to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Side {
+    /// Where a window of the given size goes to sit `gap` pixels away from this side of
+    /// `anchor`, aligned with its top or left edge.
+    pub fn dock(self, anchor: Rect, size: PhysicalSize<i32>, gap: i32) -> PhysicalPosition<i32> {
+        match self {
+            Side::Left => PhysicalPosition {
+                x: anchor.left() - gap - size.width,
+                y: anchor.top(),
+            },
+            Side::Right => PhysicalPosition {
+                x: anchor.right() + gap,
+                y: anchor.top(),
+            },
+            Side::Top => PhysicalPosition {
+                x: anchor.left(),
+                y: anchor.top() - gap - size.height,
+            },
+            Side::Bottom => PhysicalPosition {
+                x: anchor.left(),
+                y: anchor.bottom() + gap,
+            },
+        }
+    }
+
+    /// The side across from this one.
+    pub fn opposite(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+            Side::Top => Side::Bottom,
+            Side::Bottom => Side::Top,
+        }
+    }
+}
+
+/// The labels of the app's windows, most recently focused first.
+#[derive(Default)]
+pub(crate) struct FocusHistory(Mutex<Vec<String>>);
+
+impl FocusHistory {
+    pub(crate) fn focused(&self, label: &str) {
+        let mut history = self.0.lock().unwrap();
+        history.retain(|l| l != label);
+        history.insert(0, label.to_string());
+    }
+
+    pub(crate) fn destroyed(&self, label: &str) {
+        self.0.lock().unwrap().retain(|l| l != label);
+    }
+}
+
+pub(crate) fn move_near_last_focused<R: Runtime>(
+    window: &Window<R>,
+    side: Side,
+    gap: i32,
+) -> Result<()> {
+    let anchor = plugin_state::<R, FocusHistory>(window)
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|label| *label != window.label())
+        .find_map(|label| window.get_window(label));
+
+    let anchor = match anchor {
+        Some(anchor) => anchor,
+        None => {
+            let monitor = window.primary_monitor()?.ok_or(Error::NoMonitor)?;
+            let position = window.position_on(Position::Center, &monitor)?;
+            return window.place(position, &monitor);
+        }
+    };
+
+    let monitor = anchor.current_monitor()?.ok_or(Error::NoMonitor)?;
+    let size = window.outer_size()?;
+    let position = side.dock(
+        Rect::of_window(&anchor)?,
+        PhysicalSize {
+            width: size.width as i32,
+            height: size.height as i32,
+        },
+        gap,
+    );
+    window.place(position, &monitor)
+}
+
+pub(crate) fn move_beside_rect<R: Runtime>(
+    window: &Window<R>,
+    anchor: Rect,
+    side: Side,
+    gap: i32,
+) -> Result<()> {
+    let monitor = window
+        .available_monitors()?
+        .into_iter()
+        .filter_map(|monitor| {
+            let overlap = Rect::of_monitor(&monitor).intersection(&anchor)?.area();
+            Some((monitor, overlap))
+        })
+        .max_by_key(|(_, overlap)| *overlap)
+        .map(|(monitor, _)| monitor)
+        .ok_or(Error::NoMonitor)?;
+    let bounds = Rect::of_monitor(&monitor);
+
+    let size = window.outer_size()?;
+    let size = PhysicalSize {
+        width: size.width as i32,
+        height: size.height as i32,
+    };
+    let beside = |side: Side| Rect {
+        position: side.dock(anchor, size, gap),
+        size,
+    };
+
+    let mut rect = beside(side);
+    if !bounds.contains_rect(&rect) {
+        let flipped = beside(side.opposite());
+        if bounds.contains_rect(&flipped) {
+            rect = flipped;
+        }
+    }
+    window.place(rect.clamp_within(&bounds).position, &monitor)
+}
@@ -0,0 +1,72 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use serde::{Serialize, Serializer};
+
+/// The error types.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// Some Tauri API failed
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+    /// The window isn't on any monitor, e.g. in headless or remote-desktop sessions.
+    #[error("no monitor detected")]
+    NoMonitor,
+    /// The requested monitor isn't connected.
+    #[error("monitor {0} not found")]
+    MonitorNotFound(String),
+    /// The monitor was excluded with
+    /// [`Builder::with_excluded_monitors`](crate::Builder::with_excluded_monitors).
+    #[error("monitor \"{0}\" is excluded")]
+    MonitorExcluded(String),
+    /// The monitor doesn't report a name, so nothing can be looked up for it.
+    #[error("the monitor has no name")]
+    UnnamedMonitor,
+    /// No zones were registered for the monitor.
+    #[error("no zones registered for monitor \"{0}\"")]
+    NoZones(String),
+    /// JSON error.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The layout document was written by a newer version of the plugin.
+    #[error("unsupported layout version {0}")]
+    UnsupportedLayoutVersion(u32),
+    /// The name doesn't match any [`Position`](crate::Position).
+    #[error(
+        "unknown position \"{0}\", expected one of: {}",
+        crate::ext::position_names()
+    )]
+    UnknownPosition(String),
+    /// A tray-relative [`Position`](crate::Position) was requested from a build without the
+    /// `system-tray` feature.
+    #[error("tray-relative positions require the `system-tray` feature")]
+    TrayFeatureDisabled,
+    /// A tray-relative [`Position`](crate::Position) was requested before any tray event
+    /// reported where the tray icon is, see `on_tray_event`.
+    #[error("the tray position isn't known yet")]
+    TrayPositionUnknown,
+    /// The operation only supports monitor-relative positions.
+    #[error("\"{0}\" isn't a monitor-relative position")]
+    NotMonitorRelative(String),
+    /// The mouse cursor position can't be queried on this platform.
+    #[error("the cursor position isn't available")]
+    CursorPositionUnknown,
+    /// The app has no window with the given label.
+    #[error("window \"{0}\" not found")]
+    WindowNotFound(String),
+    /// The monitor has no zone with the given name.
+    #[error("zone \"{0}\" not found")]
+    ZoneNotFound(String),
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
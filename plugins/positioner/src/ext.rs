@@ -3,13 +3,31 @@
 
 #[cfg(feature = "system-tray")]
 use crate::Tray;
-use serde_repr::Deserialize_repr;
-#[cfg(feature = "system-tray")]
-use tauri::Manager;
-use tauri::{PhysicalPosition, PhysicalSize, Result, Runtime, Window};
+use crate::{
+    animation::{self, AnimationConfig, Easing},
+    aspect, confine, dock,
+    monitor::{when_ready, MonitorStrategy},
+    overlay, platform, plugin_state, present,
+    rect::set_outer_rect,
+    tracking::{self, SizeThreshold},
+    zones, Config, Error, Margins, Rect, Result, Side, WindowGeometry,
+};
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{collections::HashMap, fmt, str::FromStr, sync::Mutex, time::Duration};
+use tauri::{Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime, Window, WindowBuilder};
 
 /// Well known window positions.
-#[derive(Debug, Deserialize_repr)]
+///
+/// Positions are serialized as their kebab-case name, see [`Position::name`], and can be
+/// deserialized from either their name or their numeric discriminant. Discriminants are
+/// stable and don't depend on the enabled features: the tray-relative positions always take
+/// `9` to `18`, so sending one of them, by name or number, to a build without the
+/// `system-tray` feature fails with [`Error::TrayFeatureDisabled`] instead of being mistaken
+/// for another position. New positions are appended with new discriminants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u16)]
 pub enum Position {
     TopLeft = 0,
@@ -33,173 +51,2306 @@ pub enum Position {
     TrayCenter,
     #[cfg(feature = "system-tray")]
     TrayBottomCenter,
+    /// Below the tray icon, aligned with its left edge, for a tray at the top of the screen.
+    #[cfg(feature = "system-tray")]
+    TrayTopLeft = 15,
+    /// Below the tray icon and centered on it, for a tray at the top of the screen.
+    #[cfg(feature = "system-tray")]
+    TrayTopCenter = 16,
+    /// Below the tray icon, starting at its right edge, for a tray at the top of the screen.
+    #[cfg(feature = "system-tray")]
+    TrayTopRight = 17,
+    /// Centered on the tray icon, below it if the icon is in the top half of its monitor and
+    /// above it otherwise.
+    #[cfg(feature = "system-tray")]
+    TrayAdjacent = 18,
+    /// The window's top-left corner at the mouse cursor, kept inside the monitor under it.
+    Pointer = 19,
+    /// Centered on the mouse cursor, kept inside the monitor under it.
+    PointerCenter = 20,
+}
+
+/// The discriminants reserved for the tray-relative positions.
+const TRAY_DISCRIMINANTS: std::ops::RangeInclusive<u16> = 9..=18;
+
+/// The highest discriminant in use.
+const LAST_DISCRIMINANT: u16 = 20;
+
+/// The names of the tray-relative positions, whether or not the `system-tray` feature is
+/// enabled.
+const TRAY_NAMES: [&str; 10] = [
+    "tray-left",
+    "tray-bottom-left",
+    "tray-right",
+    "tray-bottom-right",
+    "tray-center",
+    "tray-bottom-center",
+    "tray-top-left",
+    "tray-top-center",
+    "tray-top-right",
+    "tray-adjacent",
+];
+
+/// The names of every position available in this build, for error messages.
+pub(crate) fn position_names() -> String {
+    (0..=LAST_DISCRIMINANT)
+        .filter_map(|d| Position::try_from(d).ok())
+        .map(|p| p.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl TryFrom<u16> for Position {
+    type Error = Error;
+
+    fn try_from(discriminant: u16) -> Result<Self> {
+        use Position::*;
+
+        let position = match discriminant {
+            0 => TopLeft,
+            1 => TopRight,
+            2 => BottomLeft,
+            3 => BottomRight,
+            4 => TopCenter,
+            5 => BottomCenter,
+            6 => LeftCenter,
+            7 => RightCenter,
+            8 => Center,
+            #[cfg(feature = "system-tray")]
+            9 => TrayLeft,
+            #[cfg(feature = "system-tray")]
+            10 => TrayBottomLeft,
+            #[cfg(feature = "system-tray")]
+            11 => TrayRight,
+            #[cfg(feature = "system-tray")]
+            12 => TrayBottomRight,
+            #[cfg(feature = "system-tray")]
+            13 => TrayCenter,
+            #[cfg(feature = "system-tray")]
+            14 => TrayBottomCenter,
+            #[cfg(feature = "system-tray")]
+            15 => TrayTopLeft,
+            #[cfg(feature = "system-tray")]
+            16 => TrayTopCenter,
+            #[cfg(feature = "system-tray")]
+            17 => TrayTopRight,
+            #[cfg(feature = "system-tray")]
+            18 => TrayAdjacent,
+            19 => Pointer,
+            20 => PointerCenter,
+            d if TRAY_DISCRIMINANTS.contains(&d) => return Err(Error::TrayFeatureDisabled),
+            d => return Err(Error::UnknownPosition(d.to_string())),
+        };
+        Ok(position)
+    }
+}
+
+impl FromStr for Position {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match Position::from_name(name) {
+            Some(position) => Ok(position),
+            None if TRAY_NAMES.contains(&name) => Err(Error::TrayFeatureDisabled),
+            None => Err(Error::UnknownPosition(name.to_string())),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Position {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PositionVisitor;
+
+        impl<'de> Visitor<'de> for PositionVisitor {
+            type Value = Position;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a position name like \"top-right\" or its discriminant")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<Position, E> {
+                u16::try_from(value)
+                    .map_err(|_| Error::UnknownPosition(value.to_string()))
+                    .and_then(Position::try_from)
+                    .map_err(E::custom)
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<Position, E> {
+                match u64::try_from(value) {
+                    Ok(value) => self.visit_u64(value),
+                    Err(_) => Err(E::custom(Error::UnknownPosition(value.to_string()))),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> std::result::Result<Position, E> {
+                name.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(PositionVisitor)
+    }
+}
+
+impl Serialize for Position {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl Position {
+    /// The positions that are relative to a monitor, as opposed to the tray icon.
+    pub const MONITOR_RELATIVE: [Position; 9] = [
+        Position::TopLeft,
+        Position::TopRight,
+        Position::BottomLeft,
+        Position::BottomRight,
+        Position::TopCenter,
+        Position::BottomCenter,
+        Position::LeftCenter,
+        Position::RightCenter,
+        Position::Center,
+    ];
+
+    /// Whether the position is relative to the tray icon.
+    pub fn is_tray_relative(&self) -> bool {
+        TRAY_DISCRIMINANTS.contains(&(*self as u16))
+    }
+
+    /// Whether the position is relative to the mouse cursor.
+    pub fn is_pointer_relative(&self) -> bool {
+        matches!(self, Position::Pointer | Position::PointerCenter)
+    }
+
+    /// The kebab-case name of the position, e.g. `"top-right"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Position::TopLeft => "top-left",
+            Position::TopRight => "top-right",
+            Position::BottomLeft => "bottom-left",
+            Position::BottomRight => "bottom-right",
+            Position::TopCenter => "top-center",
+            Position::BottomCenter => "bottom-center",
+            Position::LeftCenter => "left-center",
+            Position::RightCenter => "right-center",
+            Position::Center => "center",
+            #[cfg(feature = "system-tray")]
+            Position::TrayLeft => "tray-left",
+            #[cfg(feature = "system-tray")]
+            Position::TrayBottomLeft => "tray-bottom-left",
+            #[cfg(feature = "system-tray")]
+            Position::TrayRight => "tray-right",
+            #[cfg(feature = "system-tray")]
+            Position::TrayBottomRight => "tray-bottom-right",
+            #[cfg(feature = "system-tray")]
+            Position::TrayCenter => "tray-center",
+            #[cfg(feature = "system-tray")]
+            Position::TrayBottomCenter => "tray-bottom-center",
+            #[cfg(feature = "system-tray")]
+            Position::TrayTopLeft => "tray-top-left",
+            #[cfg(feature = "system-tray")]
+            Position::TrayTopCenter => "tray-top-center",
+            #[cfg(feature = "system-tray")]
+            Position::TrayTopRight => "tray-top-right",
+            #[cfg(feature = "system-tray")]
+            Position::TrayAdjacent => "tray-adjacent",
+            Position::Pointer => "pointer",
+            Position::PointerCenter => "pointer-center",
+        }
+    }
+
+    /// Parses the kebab-case name returned by [`Position::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        let position = match name {
+            "top-left" => Position::TopLeft,
+            "top-right" => Position::TopRight,
+            "bottom-left" => Position::BottomLeft,
+            "bottom-right" => Position::BottomRight,
+            "top-center" => Position::TopCenter,
+            "bottom-center" => Position::BottomCenter,
+            "left-center" => Position::LeftCenter,
+            "right-center" => Position::RightCenter,
+            "center" => Position::Center,
+            #[cfg(feature = "system-tray")]
+            "tray-left" => Position::TrayLeft,
+            #[cfg(feature = "system-tray")]
+            "tray-bottom-left" => Position::TrayBottomLeft,
+            #[cfg(feature = "system-tray")]
+            "tray-right" => Position::TrayRight,
+            #[cfg(feature = "system-tray")]
+            "tray-bottom-right" => Position::TrayBottomRight,
+            #[cfg(feature = "system-tray")]
+            "tray-center" => Position::TrayCenter,
+            #[cfg(feature = "system-tray")]
+            "tray-bottom-center" => Position::TrayBottomCenter,
+            #[cfg(feature = "system-tray")]
+            "tray-top-left" => Position::TrayTopLeft,
+            #[cfg(feature = "system-tray")]
+            "tray-top-center" => Position::TrayTopCenter,
+            #[cfg(feature = "system-tray")]
+            "tray-top-right" => Position::TrayTopRight,
+            #[cfg(feature = "system-tray")]
+            "tray-adjacent" => Position::TrayAdjacent,
+            "pointer" => Position::Pointer,
+            "pointer-center" => Position::PointerCenter,
+            _ => return None,
+        };
+        Some(position)
+    }
+}
+
+/// An offset for edge and corner [`Position`]s, measured from the edges the window touches
+/// towards the inside of the monitor.
+///
+/// Unlike the raw offset of [`WindowExt::move_window_with_offset`], whose positive values
+/// always point right and down, the direction depends on the position: for
+/// [`Position::BottomRight`], `EdgeRelativeOffset::new(16, 16)` keeps the window 16 pixels
+/// away from the right and the bottom edge, and so does it for [`Position::TopLeft`] with
+/// the left and top edge. Negative values push the window past the edges.
+///
+/// On an axis the position is centered on, and for tray-relative positions, the value is
+/// applied as a raw offset instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EdgeRelativeOffset {
+    /// The distance from the left or right edge, in physical pixels.
+    pub horizontal: i32,
+    /// The distance from the top or bottom edge, in physical pixels.
+    pub vertical: i32,
+}
+
+impl EdgeRelativeOffset {
+    pub fn new(horizontal: i32, vertical: i32) -> Self {
+        Self {
+            horizontal,
+            vertical,
+        }
+    }
+
+    /// The raw offset this resolves to for the given [`Position`].
+    pub fn to_physical(&self, position: Position) -> PhysicalPosition<i32> {
+        use Position::*;
+
+        let (dx, dy) = match position {
+            TopRight | RightCenter => (-1, 1),
+            BottomLeft | BottomCenter => (1, -1),
+            BottomRight => (-1, -1),
+            _ => (1, 1),
+        };
+        PhysicalPosition {
+            x: dx * self.horizontal,
+            y: dy * self.vertical,
+        }
+    }
+}
+
+/// Where the origin of a coordinate space is and which way its y-axis points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OriginConvention {
+    /// The origin is the top-left corner of the primary monitor and y grows downwards, like
+    /// everywhere in Tauri.
+    #[default]
+    TopLeft,
+    /// The origin is the bottom-left corner of the primary monitor and y grows upwards, like
+    /// in native macOS APIs.
+    ///
+    /// A window's top-left corner at `y` in Tauri's coordinates has its bottom-left corner
+    /// at `primary_height - (y + window_height)` in this convention.
+    BottomLeft,
 }
 
 /// A [`Window`] extension that provides extra methods related to positioning.
 pub trait WindowExt {
     /// Moves the [`Window`] to the given [`Position`]
     ///
-    /// All positions are relative to the **current** screen.
-    fn move_window(&self, position: Position) -> Result<()>;
+    /// All positions are relative to the **current** screen. The window is never resized,
+    /// see [`WindowExt::reposition_only`]. Once moved, [`MOVED_EVENT`] is emitted to the
+    /// window. Returns where the window's top-left corner was moved to, in physical pixels.
+    ///
+    /// Fails with [`Error::NoMonitor`] if the window isn't on any monitor, and with
+    /// [`Error::TrayPositionUnknown`] for tray-relative positions requested before the first
+    /// tray event, instead of panicking.
+    fn move_window(&self, position: Position) -> Result<PhysicalPosition<i32>>;
+    /// Moves the [`Window`] to the given [`Position`] on its current monitor and guarantees
+    /// that its size is left untouched.
+    ///
+    /// This is the path to use for "move, never resize", as opposed to the helpers that
+    /// resize windows, like [`WindowExt::move_window_to_zone`]. It currently behaves like
+    /// [`WindowExt::move_window`], but debug builds assert that the outer size didn't change
+    /// to catch regressions.
+    fn reposition_only(&self, position: Position) -> Result<PhysicalPosition<i32>>;
+    /// Moves the [`Window`] to the given [`Position`] on the given [`Monitor`].
+    ///
+    /// If the monitor has a different scale factor than the one the window is on, the system
+    /// rescales the window once it arrives, so the position is computed for the size the
+    /// window will have there, assuming it keeps its logical size.
+    ///
+    /// Fails with [`Error::TrayPositionUnknown`] for tray-relative positions requested before
+    /// the first tray event.
+    fn move_window_with_monitor(
+        &self,
+        position: Position,
+        monitor: &Monitor,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// Like [`WindowExt::move_window_with_monitor`], but also moves the window to monitors
+    /// excluded with [`Builder::with_excluded_monitors`](crate::Builder::with_excluded_monitors).
+    fn force_move_window_with_monitor(
+        &self,
+        position: Position,
+        monitor: &Monitor,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// Moves the [`Window`] to the given [`Position`] within the bounding rectangle of the
+    /// monitors with the given indices, following the ordering of `available_monitors()`,
+    /// e.g. to treat several monitors forming a video wall as one surface.
+    ///
+    /// Unlike the other methods, the window may straddle the bezels between monitors, which
+    /// is intended for walls. Only monitor-relative positions are supported.
+    fn move_window_on_wall(&self, position: Position, monitor_indices: &[usize]) -> Result<()>;
+    /// Like [`WindowExt::move_window`], but computes the position against the work area of
+    /// the current monitor, the part that isn't covered by the Windows taskbar, the macOS
+    /// Dock and menu bar or X11 panels.
+    ///
+    /// Falls back to the full monitor bounds where the work area can't be determined, e.g.
+    /// on Wayland. Fails with [`Error::NotMonitorRelative`] for tray-relative positions.
+    fn move_window_in_work_area(&self, position: Position) -> Result<()>;
+    /// Moves the [`Window`] to a fraction of the free space on its current monitor.
+    ///
+    /// `(0.0, 0.0)` puts the window's top-left corner on the monitor's top-left corner and
+    /// `(1.0, 1.0)` its bottom-right corner on the monitor's bottom-right corner, so
+    /// `(0.5, 0.5)` centers it. Values outside `0.0..=1.0` are clamped.
+    fn move_window_relative(&self, x_frac: f64, y_frac: f64) -> Result<()>;
+    /// Like [`WindowExt::move_window`], but glides the window to the position over
+    /// `duration` along the `easing` curve, at about 60 frames per second.
+    ///
+    /// Returns the position the window glides to right away; the animation runs on a
+    /// background thread and [`MOVED_EVENT`] is only emitted once the window arrived.
+    /// Calling this again, or any other animated move, interrupts it.
+    fn move_window_animated(
+        &self,
+        position: Position,
+        duration: Duration,
+        easing: Easing,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// Moves the [`Window`] to the given [`Position`] within the outer bounds of `anchor`
+    /// instead of a monitor, e.g. [`Position::TopRight`] aligns the top-right corners of
+    /// both windows.
+    ///
+    /// The window follows the anchor to whatever monitor it is on. Fails with
+    /// [`Error::NotMonitorRelative`] for tray-relative positions. See
+    /// [`WindowExt::move_window_beside_rect`] to dock the window outside another rectangle.
+    fn move_window_relative_to_window(&self, anchor: &Self, position: Position) -> Result<()>;
+    /// Moves the [`Window`] to the given [`Position`] and then by `offset`.
+    ///
+    /// The offset is a raw delta: positive values move the window right and down, whatever
+    /// the position. See [`WindowExt::move_window_with_edge_offset`] to keep a distance from
+    /// the monitor edges instead.
+    ///
+    /// Works for both monitor-relative and tray-relative positions; [`WindowExt::move_window`]
+    /// is the same with a zero offset.
+    fn move_window_with_offset(
+        &self,
+        position: Position,
+        offset: PhysicalPosition<i32>,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// Moves the [`Window`] to the given [`Position`], kept `offset` away from the edges it
+    /// touches, e.g. 16 pixels from the right and bottom edge for [`Position::BottomRight`].
+    fn move_window_with_edge_offset(
+        &self,
+        position: Position,
+        offset: EdgeRelativeOffset,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// Computes where the [`Window`] would be moved to by [`WindowExt::move_window`] without
+    /// moving it, e.g. to prepare its content before showing it at a tray-relative position.
+    ///
+    /// See [`calculate_position`] for the same computation without a window.
+    fn position_for(&self, position: Position) -> Result<PhysicalPosition<i32>>;
+    /// Computes where the [`Window`] would be moved to by [`WindowExt::move_window`] without
+    /// moving it, in the coordinate space given by `origin`.
+    ///
+    /// With [`OriginConvention::BottomLeft`] the returned point is the window's bottom-left
+    /// corner, measured upwards from the bottom of the primary monitor. All values are in
+    /// physical pixels.
+    fn preview_position(
+        &self,
+        position: Position,
+        origin: OriginConvention,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// Like [`WindowExt::move_window`], but waits briefly for the window to report its
+    /// monitor instead of failing right away.
+    ///
+    /// This is useful right after the window was created, when the windowing system
+    /// may not have placed it on a monitor yet.
+    fn move_window_when_ready(&self, position: Position) -> Result<PhysicalPosition<i32>>;
+    /// Positions a splash or loading window, typically before the main window exists.
+    ///
+    /// The monitor is picked with the given [`MonitorStrategy`], retrying briefly while
+    /// monitors are still being detected, just like [`WindowExt::move_window_when_ready`].
+    /// Call it from the `setup` hook, while the splash window is still hidden:
+    ///
+    /// ```ignore
+    /// tauri::Builder::default()
+    ///     .setup(|app| {
+    ///         let splash = app.get_window("splash").unwrap();
+    ///         splash.position_splash(MonitorStrategy::Primary, Position::Center)?;
+    ///         splash.show()?;
+    ///         Ok(())
+    ///     })
+    /// ```
+    fn position_splash(
+        &self,
+        strategy: MonitorStrategy,
+        position: Position,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// Keeps the [`Window`] at a [`Position`] that depends on its size, like media queries
+    /// for window placement.
+    ///
+    /// Whenever the window is resized, the first breakpoint whose [`SizeThreshold`] the
+    /// window's logical inner size satisfies is applied on the current monitor, so list the
+    /// largest thresholds first. If no breakpoint matches the window is left alone.
+    /// Resize events are debounced, and calling this again replaces the breakpoints.
+    /// Pass an empty list to stop tracking.
+    fn track_window_responsive(&self, breakpoints: Vec<(SizeThreshold, Position)>) -> Result<()>;
+    /// Moves and resizes the [`Window`] to fill the zone with the given name.
+    ///
+    /// The zone is looked up among the zones registered with [`register_zones`](crate::register_zones)
+    /// for the window's current monitor.
+    fn move_window_to_zone(&self, zone_name: &str) -> Result<()>;
+    /// The name of the zone under `point`, e.g. the cursor while the user drags the
+    /// [`Window`], so the frontend can highlight it.
+    ///
+    /// `point` is in physical screen coordinates and zones are looked up on the monitor
+    /// containing it. Returns `None` if the point isn't inside any zone.
+    fn preview_zone_at(&self, point: PhysicalPosition<i32>) -> Result<Option<String>>;
+    /// Moves and resizes the [`Window`] to fill the zone under `point`, e.g. when the user
+    /// releases it at the end of a drag.
+    ///
+    /// Returns the name of the zone, or `None` if the point isn't inside any zone, in which
+    /// case the window is left alone.
+    fn drop_into_zone_at(&self, point: PhysicalPosition<i32>) -> Result<Option<String>>;
+    /// Gives `target` the outer position and size of this [`Window`], e.g. so a new window
+    /// starts exactly where another one is.
+    ///
+    /// If this window is partly off its monitor, the copied rectangle is first moved onto
+    /// it. If `target` is on a different monitor, the rectangle is then also moved onto
+    /// `target`'s monitor. Returns the geometry applied to `target`.
+    fn copy_geometry_to(&self, target: &Self) -> Result<WindowGeometry>;
+    /// The largest outer size the [`Window`] can have at the given [`Position`] on `monitor`
+    /// while staying `margin` away from the edges of its work area, e.g. to make a panel "as
+    /// big as possible in the top-right corner with 12px margins" without running under the
+    /// taskbar or Dock.
+    ///
+    /// Falls back to the full monitor bounds where the work area can't be determined, see
+    /// [`WindowExt::move_window_in_work_area`]. On an axis the position is centered on, the
+    /// window stays centered, so the larger of the two margins counts on both sides.
+    /// Tray-relative positions are treated like [`Position::TopLeft`]. Returns a zero size
+    /// along an axis whose margins exceed the available space.
+    fn max_size_for(
+        &self,
+        position: Position,
+        margin: Margins,
+        monitor: &Monitor,
+    ) -> PhysicalSize<i32>;
+    /// Sets the part of the [`Window`]'s outer size that isn't visible, like the shadow and
+    /// resize borders of custom-drawn client-side decorations.
+    ///
+    /// Monitor-relative positions then align the visible part of the window with the monitor
+    /// edges instead of its outer bounds. The extents are applied on top of whatever the
+    /// platform already accounts for in the outer size of natively decorated windows. Pass
+    /// [`Margins::default`] to clear them.
+    fn set_frame_extents(&self, extents: Margins);
+    /// Whether the outer rectangles of this window and `other` overlap.
+    fn windows_overlap(&self, other: &Self) -> Result<bool>;
+    /// How much this window and `other` overlap, as a fraction of the smaller window's area.
+    ///
+    /// Returns `0.0` if they don't overlap and `1.0` if the smaller window is completely
+    /// covered by the larger one.
+    fn windows_overlap_ratio(&self, other: &Self) -> Result<f64>;
+    /// Moves the [`Window`] the shortest distance that makes it stop overlapping the given
+    /// rectangle, e.g. a toolbar or on-screen display the window must not cover.
+    ///
+    /// Directions that keep the window on its current monitor are preferred. If none does,
+    /// the window is pushed the shortest way and then kept on the monitor, which may leave
+    /// it overlapping the rectangle. Directions are considered in the order left, right, up,
+    /// down, and ties between equally short ones are broken with the configured
+    /// [`TieBreak`](crate::TieBreak).
+    ///
+    /// Returns the position the window was moved to, or `None` if it already cleared the
+    /// rectangle and was left untouched.
+    fn move_out_of(
+        &self,
+        rect_pos: PhysicalPosition<i32>,
+        rect_size: PhysicalSize<i32>,
+    ) -> Result<Option<PhysicalPosition<i32>>>;
+    /// Moves and resizes the [`Window`] to exactly cover a region of `host`'s content, e.g.
+    /// the viewport of a video player an annotation overlay is drawn on.
+    ///
+    /// The region is given as `(x, y, width, height)` in `host`'s logical content
+    /// coordinates, relative to the top-left corner of its inner area, and is converted to
+    /// physical screen coordinates with `host`'s scale factor. This is a one-shot move, see
+    /// [`WindowExt::follow_region`] to keep covering the region while `host` moves.
+    fn cover_region(&self, host: &Self, region_logical: (f64, f64, f64, f64)) -> Result<()>;
+    /// Like [`WindowExt::cover_region`], but covers the region again whenever `host` is
+    /// moved, resized or changes its scale factor.
+    ///
+    /// Calling this again for the same `host` replaces the region. Following stops when
+    /// either window is destroyed or [`WindowExt::unfollow_region`] is called.
+    fn follow_region(&self, host: &Self, region_logical: (f64, f64, f64, f64)) -> Result<()>;
+    /// Stops following the region of `host` set with [`WindowExt::follow_region`].
+    fn unfollow_region(&self, host: &Self);
+    /// Centers the [`Window`] on its current monitor, shows it and fades it in, the usual
+    /// way to present a dialog.
+    ///
+    /// The opacity is raised from `0` to `1` in `opacity_steps` steps, `interval` apart, on a
+    /// background thread. Fading is supported on Windows and Linux, where it requires a
+    /// compositing window manager. Elsewhere, or with zero steps, the window is just centered
+    /// and shown.
+    fn present_centered(&self, opacity_steps: u32, interval: Duration) -> Result<()>;
+    /// Fades the [`Window`] out and hides it, reusing the steps and interval of the last
+    /// [`WindowExt::present_centered`] call.
+    ///
+    /// A fade that is still running is interrupted. Where fading isn't supported, the window
+    /// is hidden right away.
+    fn dismiss(&self) -> Result<()>;
+    /// Sends the [`Window`] gliding with the given velocity, e.g. after a flick gesture on a
+    /// touch screen, and snaps it to the nearest monitor-relative [`Position`] once it slows
+    /// down.
+    ///
+    /// The velocity is in physical pixels per second. The window stays on its current
+    /// monitor throughout. Deceleration, the snap threshold and the snap animation are set
+    /// with [`Builder::with_flick`](crate::Builder::with_flick). Returns right away; the
+    /// motion runs on a background thread and is interrupted by any other animated move.
+    fn flick(&self, velocity: (f32, f32)) -> Result<()>;
+    /// Moves the [`Window`] to the given [`Position`] on its current monitor and keeps its
+    /// inner size at the aspect ratio `ratio_w:ratio_h` while the user resizes it, e.g. for a
+    /// media player.
+    ///
+    /// After every resize the side the user changed the most is kept, the other one is
+    /// adjusted to the ratio and the window is moved back to `position`, so the anchored
+    /// corner or edge stays put. Calling this again replaces the position and ratio.
+    fn move_and_lock_aspect(&self, position: Position, ratio_w: u32, ratio_h: u32) -> Result<()>;
+    /// Stops keeping the aspect ratio set with [`WindowExt::move_and_lock_aspect`].
+    fn unlock_aspect(&self);
+    /// Moves the [`Window`] through a sequence of waypoints, e.g. to guide attention around
+    /// the screen during an onboarding tour.
+    ///
+    /// Each waypoint is reached in its given duration, easing in and out, starting from the
+    /// previous one. A waypoint repeating the previous one holds the window still for its
+    /// duration. Every waypoint is resolved against the window's current monitor when its
+    /// segment starts. With `looping`, the path starts over after the last waypoint until it
+    /// is interrupted.
+    ///
+    /// Returns right away; the path runs on a background thread. Calling this again, or any
+    /// other animated move, interrupts it.
+    fn animate_path(&self, waypoints: Vec<(Position, Duration)>, looping: bool) -> Result<()>;
+    /// Keeps the [`Window`] inside the bounds of `monitor`, e.g. for kiosk windows that must
+    /// not be dragged off-screen or onto another monitor.
+    ///
+    /// The window is moved inside the monitor right away, and whenever it is moved partly
+    /// outside afterwards it snaps back to the closest position inside. Calling this again
+    /// replaces the monitor. Windows larger than the monitor are aligned with its top-left
+    /// corner.
+    fn confine_to_monitor(&self, monitor: &Monitor) -> Result<()>;
+    /// Stops keeping the [`Window`] inside the monitor set with
+    /// [`WindowExt::confine_to_monitor`].
+    fn release_confinement(&self);
+    /// Moves the [`Window`] the least amount that keeps at least `min_visible` of its top
+    /// edge, where the title bar usually is, on a monitor, so the user can still grab it.
+    ///
+    /// This is gentler than requiring the whole window to be visible and matches what most
+    /// window managers do. The monitor that needs the smallest move is used, following the
+    /// ordering of `available_monitors()` and the configured [`TieBreak`](crate::TieBreak)
+    /// when several need the same.
+    ///
+    /// Returns the position the window was moved to, or `None` if it was already grabbable
+    /// and was left untouched.
+    fn ensure_grabbable(
+        &self,
+        min_visible: PhysicalSize<i32>,
+    ) -> Result<Option<PhysicalPosition<i32>>>;
+    /// Docks the [`Window`] `gap` pixels away from the given side of the app's most recently
+    /// focused other window, e.g. to open a new window next to the one the user was working
+    /// in.
+    ///
+    /// Focus is tracked by the plugin, so this requires the plugin to be attached. If no other
+    /// window was focused yet, or all of them were closed, the window is centered on the
+    /// primary monitor instead.
+    fn move_window_near_last_focused(&self, side: Side, gap: i32) -> Result<()>;
+    /// Docks the [`Window`] `gap` pixels away from the given side of an arbitrary screen
+    /// rectangle, e.g. the accessibility focus rectangle the app obtained from the platform.
+    ///
+    /// If the window would not fit on the monitor there, it goes to the opposite side
+    /// instead. Either way it is kept inside the monitor that overlaps the rectangle the most.
+    fn move_window_beside_rect(
+        &self,
+        rect_pos: PhysicalPosition<i32>,
+        rect_size: PhysicalSize<i32>,
+        side: Side,
+        gap: i32,
+    ) -> Result<()>;
+    /// The monitor-relative [`Position`] closest to where the [`Window`] currently is on its
+    /// current monitor.
+    fn nearest_position(&self) -> Result<Position>;
+    /// The [`Position`] the [`Window`] currently occupies on its current monitor, if it is
+    /// within `tolerance_px` pixels of one on both axes, e.g. to toggle between
+    /// [`Position::LeftCenter`] and [`Position::RightCenter`].
+    ///
+    /// Tray-relative positions are only considered once the tray icon's bounds are known.
+    /// Returns `None` if the window isn't at any position.
+    fn current_position_kind(&self, tolerance_px: u32) -> Result<Option<Position>>;
+    /// Splits the [`Window`]'s current position into the monitor it is on, the nearest
+    /// monitor-relative [`Position`] and the offset from that position.
+    ///
+    /// Moving the window with [`WindowExt::move_window_with_offset`] to that position and
+    /// offset on the same monitor puts it exactly where it is now. The monitor is identified
+    /// by its name, or by its index in `available_monitors()` formatted as `#<index>` if it
+    /// has none.
+    fn decompose_position(&self) -> Result<(String, Position, PhysicalPosition<i32>)>;
+    /// The scale factor the plugin used the last time it positioned this [`Window`], or
+    /// `None` if it hasn't positioned it yet.
+    ///
+    /// This is the scale factor of the **target** monitor, which may differ from the one the
+    /// window was on before, e.g. while it migrates between monitors with different DPI.
+    fn last_scale_factor(&self) -> Option<f64>;
+    /// Like [`WindowExt::move_window`], but keeps the window `gap` pixels away from the
+    /// taskbar instead of using the gap set with
+    /// [`Builder::with_tray_gap`](crate::Builder::with_tray_gap).
+    ///
+    /// Only tray-relative positions are affected.
+    #[cfg(feature = "system-tray")]
+    fn move_window_with_tray_gap(&self, position: Position, gap: i32) -> Result<()>;
+    /// Like [`WindowExt::move_window`], but flips tray-relative positions to the other side
+    /// of the tray icon when the window would otherwise leave the monitor the icon is on,
+    /// e.g. below the icon instead of above it for a taskbar at the top of the screen.
+    ///
+    /// Windows that still overflow, like near the right edge of the screen, are shifted back
+    /// inside the monitor. Monitor-relative positions behave as with
+    /// [`WindowExt::move_window`].
+    #[cfg(feature = "system-tray")]
+    fn move_window_smart(&self, position: Position) -> Result<()>;
+    /// Like [`WindowExt::move_window`], but tray-relative positions use the tray icon with
+    /// the given id instead of the one that was updated most recently, for apps with more
+    /// than one tray icon.
+    ///
+    /// Fails with [`Error::TrayPositionUnknown`] for tray-relative positions if no tray
+    /// event has been received for that icon yet.
+    #[cfg(feature = "system-tray")]
+    fn move_window_to_tray(
+        &self,
+        tray_id: &str,
+        position: Position,
+    ) -> Result<PhysicalPosition<i32>>;
+}
+
+/// The event emitted to a window after [`WindowExt::move_window`] or one of its variants
+/// taking a [`Position`] moved it, with a [`Moved`] payload.
+///
+/// Windows moved along a path with [`WindowExt::animate_path`], or kept in place by
+/// tracking or [`WindowExt::move_and_lock_aspect`], don't receive it.
+pub const MOVED_EVENT: &str = "positioner://moved";
+
+/// The payload of [`MOVED_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Moved {
+    /// The position that was requested.
+    pub position: Position,
+    /// Where the window's top-left corner was moved to, in physical pixels.
+    pub physical_position: PhysicalPosition<i32>,
+    /// The name of the monitor the window was positioned on, if it has one.
+    pub monitor: Option<String>,
+}
+
+/// The frame extents of every window that has any, keyed by label.
+#[derive(Default)]
+pub(crate) struct FrameExtents(Mutex<HashMap<String, Margins>>);
+
+impl FrameExtents {
+    fn get(&self, label: &str) -> Margins {
+        self.0
+            .lock()
+            .unwrap()
+            .get(label)
+            .copied()
+            .unwrap_or_default()
+    }
 }
 
+/// The scale factor of the monitor every window was last positioned on, keyed by label.
+#[derive(Default)]
+pub(crate) struct ScaleFactors(Mutex<HashMap<String, f64>>);
+
 impl<R: Runtime> WindowExt for Window<R> {
-    fn move_window(&self, pos: Position) -> Result<()> {
+    fn move_window(&self, pos: Position) -> Result<PhysicalPosition<i32>> {
+        self.move_window_with_offset(pos, PhysicalPosition { x: 0, y: 0 })
+    }
+
+    fn move_window_with_offset(
+        &self,
+        pos: Position,
+        offset: PhysicalPosition<i32>,
+    ) -> Result<PhysicalPosition<i32>> {
+        self.move_window_with(pos, offset, None, None)
+    }
+
+    fn move_window_with_edge_offset(
+        &self,
+        pos: Position,
+        offset: EdgeRelativeOffset,
+    ) -> Result<PhysicalPosition<i32>> {
+        self.move_window_with_offset(pos, offset.to_physical(pos))
+    }
+
+    fn position_for(&self, pos: Position) -> Result<PhysicalPosition<i32>> {
+        let monitor = self.monitor_for(pos, None)?;
+        self.position_on(pos, &monitor)
+    }
+
+    fn preview_position(
+        &self,
+        pos: Position,
+        origin: OriginConvention,
+    ) -> Result<PhysicalPosition<i32>> {
+        let position = self.position_for(pos)?;
+        match origin {
+            OriginConvention::TopLeft => Ok(position),
+            OriginConvention::BottomLeft => {
+                let primary = self.primary_monitor()?.ok_or(Error::NoMonitor)?;
+                let primary_height = primary.size().height as i32;
+                let window_height = self.outer_size()?.height as i32;
+                Ok(PhysicalPosition {
+                    x: position.x,
+                    y: primary_height - (position.y + window_height),
+                })
+            }
+        }
+    }
+
+    fn reposition_only(&self, pos: Position) -> Result<PhysicalPosition<i32>> {
+        #[cfg(debug_assertions)]
+        let size = self.outer_size()?;
+        let position = self.move_window(pos)?;
+        #[cfg(debug_assertions)]
+        debug_assert_eq!(
+            size,
+            self.outer_size()?,
+            "reposition_only resized window \"{}\"",
+            self.label()
+        );
+        Ok(position)
+    }
+
+    fn move_window_when_ready(&self, pos: Position) -> Result<PhysicalPosition<i32>> {
+        let monitor = when_ready(|| Ok(self.current_monitor()?))?.ok_or(Error::NoMonitor)?;
+        self.move_window_with_monitor(pos, &monitor)
+    }
+
+    fn position_splash(
+        &self,
+        strategy: MonitorStrategy,
+        pos: Position,
+    ) -> Result<PhysicalPosition<i32>> {
+        let monitor = when_ready(|| strategy.resolve(self))?
+            .ok_or_else(|| Error::MonitorNotFound(strategy.describe()))?;
+        self.move_window_with_monitor(pos, &monitor)
+    }
+
+    fn track_window_responsive(&self, breakpoints: Vec<(SizeThreshold, Position)>) -> Result<()> {
+        tracking::track_responsive(self, breakpoints)
+    }
+
+    fn move_window_to_zone(&self, zone_name: &str) -> Result<()> {
+        zones::move_to_zone(self, zone_name)
+    }
+
+    fn preview_zone_at(&self, point: PhysicalPosition<i32>) -> Result<Option<String>> {
+        Ok(zones::zone_at(self, point)?.map(|zone| zone.name))
+    }
+
+    fn drop_into_zone_at(&self, point: PhysicalPosition<i32>) -> Result<Option<String>> {
+        match zones::zone_at(self, point)? {
+            Some(zone) => {
+                set_outer_rect(self, zone.rect)?;
+                Ok(Some(zone.name))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn copy_geometry_to(&self, target: &Self) -> Result<WindowGeometry> {
+        let mut rect = Rect::of_window(self)?;
+        let source_monitor = self.current_monitor()?;
+        if let Some(monitor) = &source_monitor {
+            rect = rect.clamp_within(&Rect::of_monitor(monitor));
+        }
+        if let Some(monitor) = target.current_monitor()? {
+            let same = source_monitor.map_or(false, |m| m.position() == monitor.position());
+            if !same {
+                rect = rect.clamp_within(&Rect::of_monitor(&monitor));
+            }
+        }
+
+        set_outer_rect(target, rect)?;
+        Ok(WindowGeometry {
+            position: rect.position,
+            size: PhysicalSize {
+                width: rect.size.width as u32,
+                height: rect.size.height as u32,
+            },
+        })
+    }
+
+    fn max_size_for(&self, pos: Position, margin: Margins, monitor: &Monitor) -> PhysicalSize<i32> {
         use Position::*;
 
-        let screen = self.current_monitor()?.unwrap();
-        let screen_position = screen.position();
-        let screen_size = PhysicalSize::<i32> {
-            width: screen.size().width as i32,
-            height: screen.size().height as i32,
+        let bounds =
+            platform::work_area(self, monitor).unwrap_or_else(|| Rect::of_monitor(monitor));
+        let centered_x = matches!(pos, TopCenter | BottomCenter | Center);
+        let centered_y = matches!(pos, LeftCenter | RightCenter | Center);
+        let width = if centered_x {
+            bounds.size.width - 2 * margin.left.max(margin.right)
+        } else {
+            bounds.size.width - margin.left - margin.right
         };
-        let window_size = PhysicalSize::<i32> {
-            width: self.outer_size()?.width as i32,
-            height: self.outer_size()?.height as i32,
+        let height = if centered_y {
+            bounds.size.height - 2 * margin.top.max(margin.bottom)
+        } else {
+            bounds.size.height - margin.top - margin.bottom
+        };
+
+        PhysicalSize {
+            width: width.max(0),
+            height: height.max(0),
+        }
+    }
+
+    fn set_frame_extents(&self, extents: Margins) {
+        let frame_extents = plugin_state::<R, FrameExtents>(self);
+        let mut frame_extents = frame_extents.0.lock().unwrap();
+        if extents == Margins::default() {
+            frame_extents.remove(self.label());
+        } else {
+            frame_extents.insert(self.label().to_string(), extents);
+        }
+    }
+
+    fn windows_overlap(&self, other: &Self) -> Result<bool> {
+        Ok(Rect::of_window(self)?
+            .intersection(&Rect::of_window(other)?)
+            .is_some())
+    }
+
+    fn windows_overlap_ratio(&self, other: &Self) -> Result<f64> {
+        let this = Rect::of_window(self)?;
+        let other = Rect::of_window(other)?;
+        let smaller = this.area().min(other.area());
+
+        match this.intersection(&other) {
+            Some(overlap) if smaller > 0 => Ok(overlap.area() as f64 / smaller as f64),
+            _ => Ok(0.0),
+        }
+    }
+
+    fn move_out_of(
+        &self,
+        rect_pos: PhysicalPosition<i32>,
+        rect_size: PhysicalSize<i32>,
+    ) -> Result<Option<PhysicalPosition<i32>>> {
+        let window = Rect::of_window(self)?;
+        let obstacle = Rect {
+            position: rect_pos,
+            size: rect_size,
+        };
+        if window.intersection(&obstacle).is_none() {
+            return Ok(None);
+        }
+
+        let bounds = Rect::of_monitor(&self.current_monitor()?.ok_or(Error::NoMonitor)?);
+        let mut candidates = [
+            window.translate(obstacle.left() - window.right(), 0),
+            window.translate(obstacle.right() - window.left(), 0),
+            window.translate(0, obstacle.top() - window.bottom()),
+            window.translate(0, obstacle.bottom() - window.top()),
+        ];
+        let distance = |r: &Rect| (r.left() - window.left()).abs() + (r.top() - window.top()).abs();
+        let tie_break = &plugin_state::<R, Config>(self).tie_break;
+        candidates.sort_by(|a, b| {
+            distance(a)
+                .cmp(&distance(b))
+                .then_with(|| tie_break.compare((a, &bounds), (b, &bounds)))
+        });
+
+        let target = candidates
+            .iter()
+            .find(|candidate| bounds.contains_rect(candidate))
+            .copied()
+            .unwrap_or_else(|| candidates[0].clamp_within(&bounds));
+
+        self.set_position(tauri::Position::Physical(target.position))?;
+        Ok(Some(target.position))
+    }
+
+    fn move_window_with_monitor(
+        &self,
+        pos: Position,
+        monitor: &Monitor,
+    ) -> Result<PhysicalPosition<i32>> {
+        let position = self.position_on_sized(pos, monitor, self.size_on(monitor)?)?;
+        self.place(position, monitor)?;
+        self.emit_moved(pos, position, monitor);
+        Ok(position)
+    }
+
+    fn force_move_window_with_monitor(
+        &self,
+        pos: Position,
+        monitor: &Monitor,
+    ) -> Result<PhysicalPosition<i32>> {
+        let position = self.position_on_sized(pos, monitor, self.size_on(monitor)?)?;
+        self.force_place(position, monitor)?;
+        self.emit_moved(pos, position, monitor);
+        Ok(position)
+    }
+
+    fn move_window_on_wall(&self, pos: Position, monitor_indices: &[usize]) -> Result<()> {
+        let monitors = self.available_monitors()?;
+        let mut wall: Option<(Rect, &Monitor)> = None;
+        for index in monitor_indices {
+            let monitor = monitors
+                .get(*index)
+                .ok_or_else(|| Error::MonitorNotFound(format!("#{index}")))?;
+            let rect = Rect::of_monitor(monitor);
+            wall = Some(match wall {
+                Some((bounds, first)) => (bounds.union(&rect), first),
+                None => (rect, monitor),
+            });
+        }
+        let (bounds, first) = wall.ok_or(Error::NoMonitor)?;
+
+        let position = self.position_within(pos, bounds, self.outer_size()?)?;
+        self.place(position, first)?;
+        self.emit_moved(pos, position, first);
+        Ok(())
+    }
+
+    fn move_window_in_work_area(&self, pos: Position) -> Result<()> {
+        let monitor = self.current_monitor()?.ok_or(Error::NoMonitor)?;
+        let bounds =
+            platform::work_area(self, &monitor).unwrap_or_else(|| Rect::of_monitor(&monitor));
+        let position = self.position_within(pos, bounds, self.outer_size()?)?;
+        self.place(position, &monitor)?;
+        self.emit_moved(pos, position, &monitor);
+        Ok(())
+    }
+
+    fn move_window_animated(
+        &self,
+        pos: Position,
+        duration: Duration,
+        easing: Easing,
+    ) -> Result<PhysicalPosition<i32>> {
+        let animation = AnimationConfig::new(duration, easing);
+        self.move_window_with(pos, PhysicalPosition { x: 0, y: 0 }, None, Some(animation))
+    }
+
+    fn move_window_relative(&self, x_frac: f64, y_frac: f64) -> Result<()> {
+        let monitor = self.current_monitor()?.ok_or(Error::NoMonitor)?;
+        let bounds = Rect::of_monitor(&monitor);
+        let size = self.outer_size()?;
+        let free_width = (bounds.size.width - size.width as i32) as f64;
+        let free_height = (bounds.size.height - size.height as i32) as f64;
+        let position = PhysicalPosition {
+            x: bounds.left() + (free_width * x_frac.clamp(0.0, 1.0)).round() as i32,
+            y: bounds.top() + (free_height * y_frac.clamp(0.0, 1.0)).round() as i32,
+        };
+        self.place(position, &monitor)
+    }
+
+    fn move_window_relative_to_window(&self, anchor: &Self, pos: Position) -> Result<()> {
+        let monitor = anchor.current_monitor()?.ok_or(Error::NoMonitor)?;
+        let position = self.position_within(pos, Rect::of_window(anchor)?, self.outer_size()?)?;
+        self.place(position, &monitor)?;
+        self.emit_moved(pos, position, &monitor);
+        Ok(())
+    }
+
+    #[cfg(feature = "system-tray")]
+    fn move_window_with_tray_gap(&self, pos: Position, gap: i32) -> Result<()> {
+        let monitor = self.monitor_for(pos, None)?;
+        let size = self.size_on(&monitor)?;
+        let position = self.tray_position_on(pos, &monitor, size, gap, false, None)?;
+        self.place(position, &monitor)?;
+        self.emit_moved(pos, position, &monitor);
+        Ok(())
+    }
+
+    #[cfg(feature = "system-tray")]
+    fn move_window_smart(&self, pos: Position) -> Result<()> {
+        let monitor = self.monitor_for(pos, None)?;
+        let tray_gap = plugin_state::<R, Config>(self).tray_gap;
+        // the tray icon may be on a monitor with another scale factor than the window
+        let size = self.size_on(&monitor)?;
+        let position = self.tray_position_on(pos, &monitor, size, tray_gap, true, None)?;
+        self.place(position, &monitor)?;
+        self.emit_moved(pos, position, &monitor);
+        Ok(())
+    }
+
+    #[cfg(feature = "system-tray")]
+    fn move_window_to_tray(&self, tray_id: &str, pos: Position) -> Result<PhysicalPosition<i32>> {
+        self.move_window_with(pos, PhysicalPosition { x: 0, y: 0 }, Some(tray_id), None)
+    }
+
+    fn cover_region(&self, host: &Self, region_logical: (f64, f64, f64, f64)) -> Result<()> {
+        overlay::cover(self, host, region_logical)
+    }
+
+    fn follow_region(&self, host: &Self, region_logical: (f64, f64, f64, f64)) -> Result<()> {
+        overlay::follow(self, host, region_logical)
+    }
+
+    fn unfollow_region(&self, host: &Self) {
+        overlay::unfollow(self, host)
+    }
+
+    fn ensure_grabbable(
+        &self,
+        min_visible: PhysicalSize<i32>,
+    ) -> Result<Option<PhysicalPosition<i32>>> {
+        let window = Rect::of_window(self)?;
+        let distance = |r: &Rect| (r.left() - window.left()).abs() + (r.top() - window.top()).abs();
+        let tie_break = &plugin_state::<R, Config>(self).tie_break;
+        let (target, _) = self
+            .available_monitors()?
+            .iter()
+            .map(|monitor| {
+                let bounds = Rect::of_monitor(monitor);
+                (window.keep_top_visible_within(&bounds, min_visible), bounds)
+            })
+            .min_by(|(a, a_bounds), (b, b_bounds)| {
+                distance(a)
+                    .cmp(&distance(b))
+                    .then_with(|| tie_break.compare((a, a_bounds), (b, b_bounds)))
+            })
+            .ok_or(Error::NoMonitor)?;
+
+        if target.position == window.position {
+            return Ok(None);
+        }
+        self.set_position(tauri::Position::Physical(target.position))?;
+        Ok(Some(target.position))
+    }
+
+    fn present_centered(&self, opacity_steps: u32, interval: Duration) -> Result<()> {
+        present::present_centered(self, opacity_steps, interval)
+    }
+
+    fn dismiss(&self) -> Result<()> {
+        present::dismiss(self)
+    }
+
+    fn flick(&self, velocity: (f32, f32)) -> Result<()> {
+        let config = plugin_state::<R, Config>(self).flick;
+        animation::flick(self, velocity, config)
+    }
+
+    fn move_and_lock_aspect(&self, pos: Position, ratio_w: u32, ratio_h: u32) -> Result<()> {
+        aspect::lock(self, pos, ratio_w, ratio_h)
+    }
+
+    fn unlock_aspect(&self) {
+        aspect::unlock(self)
+    }
+
+    fn animate_path(&self, waypoints: Vec<(Position, Duration)>, looping: bool) -> Result<()> {
+        animation::animate_path(self, waypoints, looping)
+    }
+
+    fn confine_to_monitor(&self, monitor: &Monitor) -> Result<()> {
+        confine::confine(self, monitor)
+    }
+
+    fn release_confinement(&self) {
+        confine::release(self)
+    }
+
+    fn move_window_near_last_focused(&self, side: Side, gap: i32) -> Result<()> {
+        dock::move_near_last_focused(self, side, gap)
+    }
+
+    fn move_window_beside_rect(
+        &self,
+        rect_pos: PhysicalPosition<i32>,
+        rect_size: PhysicalSize<i32>,
+        side: Side,
+        gap: i32,
+    ) -> Result<()> {
+        let rect = Rect {
+            position: rect_pos,
+            size: rect_size,
         };
+        dock::move_beside_rect(self, rect, side, gap)
+    }
+
+    fn nearest_position(&self) -> Result<Position> {
+        let monitor = self.current_monitor()?.ok_or(Error::NoMonitor)?;
+        Ok(self.nearest_anchor(&monitor)?.0)
+    }
+
+    fn current_position_kind(&self, tolerance_px: u32) -> Result<Option<Position>> {
+        let monitor = self.current_monitor()?.ok_or(Error::NoMonitor)?;
+        let current = self.outer_position()?;
+        let tolerance = tolerance_px as i32;
+        let matches = |target: PhysicalPosition<i32>| {
+            (current.x - target.x).abs() <= tolerance && (current.y - target.y).abs() <= tolerance
+        };
+
+        let (anchor, offset) = self.nearest_anchor(&monitor)?;
+        if offset.x.abs() <= tolerance && offset.y.abs() <= tolerance {
+            return Ok(Some(anchor));
+        }
+
         #[cfg(feature = "system-tray")]
-        let (tray_position, tray_size) = self
-            .state::<Tray>()
+        if plugin_state::<R, Tray>(self).rect(None).is_some() {
+            for d in TRAY_DISCRIMINANTS {
+                let pos = Position::try_from(d)?;
+                if matches(self.position_on(pos, &monitor)?) {
+                    return Ok(Some(pos));
+                }
+            }
+        }
+        #[cfg(not(feature = "system-tray"))]
+        let _ = matches;
+
+        Ok(None)
+    }
+
+    fn decompose_position(&self) -> Result<(String, Position, PhysicalPosition<i32>)> {
+        let monitor = self.current_monitor()?.ok_or(Error::NoMonitor)?;
+        let (anchor, offset) = self.nearest_anchor(&monitor)?;
+        let id = match monitor.name() {
+            Some(name) => name.clone(),
+            None => {
+                let index = self
+                    .available_monitors()?
+                    .iter()
+                    .position(|m| m.position() == monitor.position())
+                    .ok_or(Error::NoMonitor)?;
+                format!("#{index}")
+            }
+        };
+        Ok((id, anchor, offset))
+    }
+
+    fn last_scale_factor(&self) -> Option<f64> {
+        plugin_state::<R, ScaleFactors>(self)
             .0
             .lock()
             .unwrap()
-            .map(|(pos, size)| {
-                (
-                    Some((pos.x as i32, pos.y as i32)),
-                    Some((size.width as i32, size.height as i32)),
-                )
+            .get(self.label())
+            .copied()
+    }
+}
+
+/// Moves every window of the app whose [`WindowExt::nearest_position`] is
+/// [`Position::Center`] back to the exact center of its current monitor, e.g. to tidy up
+/// after the screen resolution changed.
+///
+/// Unlike window tracking this is a one-shot action. Returns the labels of the windows that
+/// were moved, sorted. Windows that are already exactly centered are left out.
+pub fn recenter_centered<R: Runtime, M: Manager<R>>(manager: &M) -> Result<Vec<String>> {
+    let mut windows: Vec<_> = manager.windows().into_iter().collect();
+    windows.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut recentered = Vec::new();
+    for (label, window) in windows {
+        let monitor = match window.current_monitor()? {
+            Some(monitor) => monitor,
+            None => continue,
+        };
+        let (anchor, offset) = window.nearest_anchor(&monitor)?;
+        if anchor == Position::Center && (offset.x, offset.y) != (0, 0) {
+            window.move_window_with_monitor(Position::Center, &monitor)?;
+            recentered.push(label);
+        }
+    }
+    Ok(recentered)
+}
+
+/// Builds a window and shows it at `pos` without it flashing at its default position first.
+///
+/// The window is built hidden, moved while still hidden and only shown afterwards, so the
+/// builder doesn't need [`WindowBuilder::visible`] to be set: it is always overridden with
+/// `visible(false)`. Waits briefly for the window to report its monitor, just like
+/// [`WindowExt::move_window_when_ready`].
+///
+/// ```ignore
+/// let builder = WindowBuilder::new(app, "palette", WindowUrl::App("palette.html".into()));
+/// create_positioned(builder, Position::TopRight)?;
+/// ```
+pub fn create_positioned<R: Runtime>(
+    builder: WindowBuilder<'_, R>,
+    pos: Position,
+) -> Result<Window<R>> {
+    let window = builder.visible(false).build()?;
+    window.move_window_when_ready(pos)?;
+    window.show()?;
+    Ok(window)
+}
+
+/// Returns the label of the app's window on `monitor` that is closest to where it would be
+/// placed at `pos`, e.g. to focus "the top-right window".
+///
+/// The distance is measured between the centers of each window and of the rectangle it
+/// would occupy at `pos`. Returns `None` if none of the app's windows is on `monitor`.
+pub fn window_nearest_to<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    pos: Position,
+    monitor: &Monitor,
+) -> Result<Option<String>> {
+    let mut nearest: Option<(String, i64)> = None;
+    for (label, window) in manager.windows() {
+        match window.current_monitor()? {
+            Some(current) if current.position() == monitor.position() => {}
+            _ => continue,
+        }
+
+        let current = window.outer_position()?;
+        let target = window.position_on(pos, monitor)?;
+        // both rectangles have the window's size, so comparing their corners compares centers
+        let distance =
+            ((current.x - target.x) as i64).pow(2) + ((current.y - target.y) as i64).pow(2);
+        if nearest
+            .as_ref()
+            .map_or(true, |(nearest_label, nearest_distance)| {
+                (distance, &label) < (*nearest_distance, nearest_label)
             })
-            .unwrap_or_default();
+        {
+            nearest = Some((label, distance));
+        }
+    }
+    Ok(nearest.map(|(label, _)| label))
+}
 
-        let physical_pos = match pos {
-            TopLeft => *screen_position,
-            TopRight => PhysicalPosition {
-                x: screen_position.x + (screen_size.width - window_size.width),
-                y: screen_position.y,
-            },
-            BottomLeft => PhysicalPosition {
-                x: screen_position.x,
-                y: screen_size.height - (window_size.height - screen_position.y),
-            },
-            BottomRight => PhysicalPosition {
-                x: screen_position.x + (screen_size.width - window_size.width),
-                y: screen_size.height - (window_size.height - screen_position.y),
-            },
-            TopCenter => PhysicalPosition {
-                x: screen_position.x + ((screen_size.width / 2) - (window_size.width / 2)),
-                y: screen_position.y,
-            },
-            BottomCenter => PhysicalPosition {
-                x: screen_position.x + ((screen_size.width / 2) - (window_size.width / 2)),
-                y: screen_size.height - (window_size.height - screen_position.y),
-            },
-            LeftCenter => PhysicalPosition {
-                x: screen_position.x,
-                y: screen_position.y + (screen_size.height / 2) - (window_size.height / 2),
-            },
-            RightCenter => PhysicalPosition {
-                x: screen_position.x + (screen_size.width - window_size.width),
-                y: screen_position.y + (screen_size.height / 2) - (window_size.height / 2),
+pub(crate) trait WindowExtInternal {
+    /// Computes where the window would be placed at `pos` on `monitor` without moving it.
+    fn position_on(&self, pos: Position, monitor: &Monitor) -> Result<PhysicalPosition<i32>>;
+    /// Like [`WindowExtInternal::position_on`], but for a window of the given outer size
+    /// instead of the current one.
+    fn position_on_sized(
+        &self,
+        pos: Position,
+        monitor: &Monitor,
+        window_size: PhysicalSize<u32>,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// The outer size the window will have on `monitor`: its physical size scales by the
+    /// ratio of the two scale factors as long as it keeps its logical size.
+    fn size_on(&self, monitor: &Monitor) -> Result<PhysicalSize<u32>>;
+    /// Like [`WindowExtInternal::position_on_sized`], but within arbitrary bounds instead of
+    /// a monitor.
+    ///
+    /// Fails with [`Error::NotMonitorRelative`] for tray-relative positions.
+    fn position_within(
+        &self,
+        pos: Position,
+        bounds: Rect,
+        window_size: PhysicalSize<u32>,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// Finds the monitor-relative [`Position`] closest to the window's current position on
+    /// `monitor`, along with the offset from that position to where the window actually is.
+    fn nearest_anchor(&self, monitor: &Monitor) -> Result<(Position, PhysicalPosition<i32>)>;
+    /// Like [`WindowExtInternal::nearest_anchor`], but for the window at `current` instead of
+    /// where it currently is.
+    fn anchor_nearest_to(
+        &self,
+        monitor: &Monitor,
+        current: PhysicalPosition<i32>,
+    ) -> Result<(Position, PhysicalPosition<i32>)>;
+    /// Moves the window to a position computed for `monitor`, recording the monitor's scale
+    /// factor for [`WindowExt::last_scale_factor`].
+    ///
+    /// Fails with [`Error::MonitorExcluded`] if `monitor` is excluded.
+    fn place(&self, position: PhysicalPosition<i32>, monitor: &Monitor) -> Result<()>;
+    /// Like [`WindowExtInternal::place`], but also places the window on excluded monitors.
+    fn force_place(&self, position: PhysicalPosition<i32>, monitor: &Monitor) -> Result<()>;
+    /// Like [`WindowExtInternal::place`], but glides the window there on a background thread,
+    /// calling `on_done` once it arrived unless the animation was interrupted.
+    fn place_animated(
+        &self,
+        position: PhysicalPosition<i32>,
+        monitor: &Monitor,
+        config: AnimationConfig,
+        on_done: impl FnOnce() + Send + 'static,
+    ) -> Result<()>;
+    /// Records the scale factor of the monitor the window is being positioned on.
+    fn record_scale_factor(&self, monitor: &Monitor);
+    /// Emits [`MOVED_EVENT`] to the window after it was moved to `pos`.
+    fn emit_moved(&self, pos: Position, position: PhysicalPosition<i32>, monitor: &Monitor);
+    /// Like [`WindowExt::move_window_with_offset`], but for the tray icon with the given id,
+    /// or the most recently updated one if `None`, and animated if `animation` is set, in
+    /// which case [`MOVED_EVENT`] is emitted once the window arrived.
+    fn move_window_with(
+        &self,
+        pos: Position,
+        offset: PhysicalPosition<i32>,
+        tray_id: Option<&str>,
+        animation: Option<AnimationConfig>,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// The bounds of the tray icon with the given id, or of the most recently updated one if
+    /// `None`, as of the last tray event.
+    ///
+    /// Fails with [`Error::TrayPositionUnknown`] if no tray event has been received for it,
+    /// unless [`Builder::fallback_to_cursor`](crate::Builder::fallback_to_cursor) is set, in
+    /// which case the icon is assumed to be a point at the mouse cursor.
+    #[cfg(feature = "system-tray")]
+    fn tray_rect(&self, tray_id: Option<&str>) -> Result<Rect>;
+    /// The monitor the tray icon is on, or `None` if the tray position isn't known yet or
+    /// lies outside every monitor.
+    #[cfg(feature = "system-tray")]
+    fn tray_monitor(&self, tray_id: Option<&str>) -> Result<Option<Monitor>>;
+    /// The monitor under the mouse cursor, or `None` if the cursor position can't be queried
+    /// or lies outside every monitor.
+    fn monitor_at_cursor(&self) -> Result<Option<Monitor>>;
+    /// Computes where the window goes for a pointer-relative `pos`, kept inside the monitor
+    /// under the cursor, falling back to `monitor` if that isn't known.
+    ///
+    /// Fails with [`Error::CursorPositionUnknown`] if the cursor position can't be queried.
+    fn pointer_position_on(
+        &self,
+        pos: Position,
+        monitor: &Monitor,
+        window_size: PhysicalSize<u32>,
+    ) -> Result<PhysicalPosition<i32>>;
+    /// The monitor [`WindowExt::move_window`] positions the window on for `pos`: the one the
+    /// tray icon is on for tray-relative positions and the one under the cursor for
+    /// pointer-relative positions, if known, and the current one otherwise.
+    fn monitor_for(&self, pos: Position, tray_id: Option<&str>) -> Result<Monitor>;
+    /// Like [`WindowExtInternal::position_on_sized`], but with the given tray gap instead of
+    /// the configured one.
+    ///
+    /// The position is computed for and kept inside the monitor the tray icon is on, falling
+    /// back to `monitor` if that isn't known. With `flip`, a window that doesn't fit
+    /// vertically is moved to the other side of the icon first, see [`flip_to_fit`].
+    #[cfg(feature = "system-tray")]
+    fn tray_position_on(
+        &self,
+        pos: Position,
+        monitor: &Monitor,
+        window_size: PhysicalSize<u32>,
+        tray_gap: i32,
+        flip: bool,
+        tray_id: Option<&str>,
+    ) -> Result<PhysicalPosition<i32>>;
+}
+
+impl<R: Runtime> WindowExtInternal for Window<R> {
+    fn place(&self, position: PhysicalPosition<i32>, monitor: &Monitor) -> Result<()> {
+        if plugin_state::<R, Config>(self).is_excluded(monitor) {
+            return Err(Error::MonitorExcluded(
+                monitor.name().cloned().unwrap_or_default(),
+            ));
+        }
+        self.force_place(position, monitor)
+    }
+
+    fn force_place(&self, position: PhysicalPosition<i32>, monitor: &Monitor) -> Result<()> {
+        self.record_scale_factor(monitor);
+        self.set_position(tauri::Position::Physical(position))?;
+        #[cfg(feature = "store")]
+        crate::mirror::record(self, monitor, position);
+        Ok(())
+    }
+
+    fn place_animated(
+        &self,
+        position: PhysicalPosition<i32>,
+        monitor: &Monitor,
+        config: AnimationConfig,
+        on_done: impl FnOnce() + Send + 'static,
+    ) -> Result<()> {
+        if plugin_state::<R, Config>(self).is_excluded(monitor) {
+            return Err(Error::MonitorExcluded(
+                monitor.name().cloned().unwrap_or_default(),
+            ));
+        }
+        self.record_scale_factor(monitor);
+        animation::animate_to(self, position, config, on_done)?;
+        #[cfg(feature = "store")]
+        crate::mirror::record(self, monitor, position);
+        Ok(())
+    }
+
+    fn record_scale_factor(&self, monitor: &Monitor) {
+        plugin_state::<R, ScaleFactors>(self)
+            .0
+            .lock()
+            .unwrap()
+            .insert(self.label().to_string(), monitor.scale_factor());
+    }
+
+    fn emit_moved(&self, pos: Position, position: PhysicalPosition<i32>, monitor: &Monitor) {
+        let _ = self.emit(
+            MOVED_EVENT,
+            Moved {
+                position: pos,
+                physical_position: position,
+                monitor: monitor.name().cloned(),
             },
-            Center => PhysicalPosition {
-                x: screen_position.x + ((screen_size.width / 2) - (window_size.width / 2)),
-                y: screen_position.y + (screen_size.height / 2) - (window_size.height / 2),
+        );
+    }
+
+    fn nearest_anchor(&self, monitor: &Monitor) -> Result<(Position, PhysicalPosition<i32>)> {
+        self.anchor_nearest_to(monitor, self.outer_position()?)
+    }
+
+    fn anchor_nearest_to(
+        &self,
+        monitor: &Monitor,
+        current: PhysicalPosition<i32>,
+    ) -> Result<(Position, PhysicalPosition<i32>)> {
+        let mut nearest = (Position::TopLeft, PhysicalPosition { x: 0, y: 0 }, i64::MAX);
+        for anchor in Position::MONITOR_RELATIVE {
+            let target = self.position_on(anchor, monitor)?;
+            let offset = PhysicalPosition {
+                x: current.x - target.x,
+                y: current.y - target.y,
+            };
+            let distance = (offset.x as i64).pow(2) + (offset.y as i64).pow(2);
+            if distance < nearest.2 {
+                nearest = (anchor, offset, distance);
+            }
+        }
+        Ok((nearest.0, nearest.1))
+    }
+
+    fn size_on(&self, monitor: &Monitor) -> Result<PhysicalSize<u32>> {
+        Ok(scaled_size(
+            self.outer_size()?,
+            self.scale_factor()?,
+            monitor.scale_factor(),
+        ))
+    }
+
+    fn position_on(&self, pos: Position, screen: &Monitor) -> Result<PhysicalPosition<i32>> {
+        self.position_on_sized(pos, screen, self.outer_size()?)
+    }
+
+    fn position_on_sized(
+        &self,
+        pos: Position,
+        screen: &Monitor,
+        outer_size: PhysicalSize<u32>,
+    ) -> Result<PhysicalPosition<i32>> {
+        if pos.is_pointer_relative() {
+            return self.pointer_position_on(pos, screen, outer_size);
+        }
+        #[cfg(feature = "system-tray")]
+        if pos.is_tray_relative() {
+            let tray_gap = plugin_state::<R, Config>(self).tray_gap;
+            return self.tray_position_on(pos, screen, outer_size, tray_gap, false, None);
+        }
+
+        self.position_within(pos, Rect::of_monitor(screen), outer_size)
+    }
+
+    fn position_within(
+        &self,
+        pos: Position,
+        bounds: Rect,
+        outer_size: PhysicalSize<u32>,
+    ) -> Result<PhysicalPosition<i32>> {
+        // align the visible part of the window rather than its custom frame
+        let extents = plugin_state::<R, FrameExtents>(self).get(self.label());
+        let window_size = PhysicalSize::<i32> {
+            width: outer_size.width as i32 - extents.left - extents.right,
+            height: outer_size.height as i32 - extents.top - extents.bottom,
+        };
+        let physical_pos = anchor_point(pos, bounds, window_size)
+            .ok_or_else(|| Error::NotMonitorRelative(pos.name().into()))?;
+        // only windows larger than the bounds can overflow them here
+        let physical_pos = plugin_state::<R, Config>(self).clamp(
+            Rect {
+                position: physical_pos,
+                size: window_size,
             },
-            #[cfg(feature = "system-tray")]
-            TrayLeft => {
-                if let (Some((tray_x, tray_y)), Some((_, _tray_height))) =
-                    (tray_position, tray_size)
-                {
-                    let y = tray_y - window_size.height;
-                    // Choose y value based on the target OS
-                    #[cfg(target_os = "windows")]
-                    let y = if y < 0 { tray_y + _tray_height } else { y };
+            &bounds,
+        );
 
-                    #[cfg(target_os = "macos")]
-                    let y = if y < 0 { tray_y } else { y };
+        Ok(PhysicalPosition {
+            x: physical_pos.x - extents.left,
+            y: physical_pos.y - extents.top,
+        })
+    }
 
-                    PhysicalPosition { x: tray_x, y }
-                } else {
-                    panic!("Tray position not set");
-                }
+    fn move_window_with(
+        &self,
+        pos: Position,
+        offset: PhysicalPosition<i32>,
+        tray_id: Option<&str>,
+        animation: Option<AnimationConfig>,
+    ) -> Result<PhysicalPosition<i32>> {
+        let monitor = self.monitor_for(pos, tray_id)?;
+        #[cfg(feature = "system-tray")]
+        let position = {
+            let tray_gap = plugin_state::<R, Config>(self).tray_gap;
+            self.tray_position_on(
+                pos,
+                &monitor,
+                self.size_on(&monitor)?,
+                tray_gap,
+                false,
+                tray_id,
+            )?
+        };
+        #[cfg(not(feature = "system-tray"))]
+        let position = match tray_id {
+            Some(_) => return Err(Error::TrayFeatureDisabled),
+            None => self.position_on_sized(pos, &monitor, self.size_on(&monitor)?)?,
+        };
+
+        let position = PhysicalPosition {
+            x: position.x + offset.x,
+            y: position.y + offset.y,
+        };
+        match animation {
+            Some(config) => {
+                let (window, monitor_) = (self.clone(), monitor.clone());
+                self.place_animated(position, &monitor, config, move || {
+                    window.emit_moved(pos, position, &monitor_)
+                })?
             }
-            #[cfg(feature = "system-tray")]
-            TrayBottomLeft => {
-                if let Some((tray_x, tray_y)) = tray_position {
-                    PhysicalPosition {
-                        x: tray_x,
-                        y: tray_y,
-                    }
-                } else {
-                    panic!("Tray position not set");
-                }
+            None => {
+                self.place(position, &monitor)?;
+                self.emit_moved(pos, position, &monitor);
             }
-            #[cfg(feature = "system-tray")]
-            TrayRight => {
-                if let (Some((tray_x, tray_y)), Some((tray_width, _tray_height))) =
-                    (tray_position, tray_size)
-                {
-                    let y = tray_y - window_size.height;
-                    // Choose y value based on the target OS
-                    #[cfg(target_os = "windows")]
-                    let y = if y < 0 { tray_y + _tray_height } else { y };
-
-                    #[cfg(target_os = "macos")]
-                    let y = if y < 0 { tray_y } else { y };
-
-                    PhysicalPosition {
-                        x: tray_x + tray_width,
-                        y,
-                    }
-                } else {
-                    panic!("Tray position not set");
-                }
+        }
+        Ok(position)
+    }
+
+    fn monitor_for(&self, pos: Position, _tray_id: Option<&str>) -> Result<Monitor> {
+        if pos.is_pointer_relative() {
+            if let Some(monitor) = self.monitor_at_cursor()? {
+                return Ok(monitor);
             }
-            #[cfg(feature = "system-tray")]
-            TrayBottomRight => {
-                if let (Some((tray_x, tray_y)), Some((tray_width, _))) = (tray_position, tray_size)
-                {
-                    PhysicalPosition {
-                        x: tray_x + tray_width,
-                        y: tray_y,
-                    }
-                } else {
-                    panic!("Tray position not set");
-                }
+        }
+        #[cfg(feature = "system-tray")]
+        if pos.is_tray_relative() {
+            if let Some(monitor) = self.tray_monitor(_tray_id)? {
+                return Ok(monitor);
             }
-            #[cfg(feature = "system-tray")]
-            TrayCenter => {
-                if let (Some((tray_x, tray_y)), Some((tray_width, _tray_height))) =
-                    (tray_position, tray_size)
-                {
-                    let x = tray_x + tray_width / 2 - window_size.width / 2;
-                    let y = tray_y - window_size.height;
-                    // Choose y value based on the target OS
-                    #[cfg(target_os = "windows")]
-                    let y = if y < 0 { tray_y + _tray_height } else { y };
+        }
+        self.current_monitor()?.ok_or(Error::NoMonitor)
+    }
 
-                    #[cfg(target_os = "macos")]
-                    let y = if y < 0 { tray_y } else { y };
+    #[cfg(feature = "system-tray")]
+    fn tray_rect(&self, tray_id: Option<&str>) -> Result<Rect> {
+        if let Some(rect) = plugin_state::<R, Tray>(self).rect(tray_id) {
+            return Ok(rect);
+        }
+        if !plugin_state::<R, Config>(self).fallback_to_cursor {
+            return Err(Error::TrayPositionUnknown);
+        }
+        let cursor = platform::cursor_position(self).ok_or(Error::CursorPositionUnknown)?;
+        Ok(Rect {
+            position: cursor,
+            size: PhysicalSize::default(),
+        })
+    }
+
+    fn monitor_at_cursor(&self) -> Result<Option<Monitor>> {
+        let cursor = match platform::cursor_position(self) {
+            Some(cursor) => cursor,
+            None => return Ok(None),
+        };
+        Ok(self
+            .available_monitors()?
+            .into_iter()
+            .find(|monitor| Rect::of_monitor(monitor).contains(cursor)))
+    }
+
+    fn pointer_position_on(
+        &self,
+        pos: Position,
+        screen: &Monitor,
+        outer_size: PhysicalSize<u32>,
+    ) -> Result<PhysicalPosition<i32>> {
+        let cursor = platform::cursor_position(self).ok_or(Error::CursorPositionUnknown)?;
+        let bounds = match self.monitor_at_cursor()? {
+            Some(monitor) => Rect::of_monitor(&monitor),
+            None => Rect::of_monitor(screen),
+        };
+        let size = PhysicalSize {
+            width: outer_size.width as i32,
+            height: outer_size.height as i32,
+        };
+        let position = match pos {
+            Position::PointerCenter => PhysicalPosition {
+                x: cursor.x - size.width / 2,
+                y: cursor.y - size.height / 2,
+            },
+            _ => cursor,
+        };
+        Ok(plugin_state::<R, Config>(self).clamp(Rect { position, size }, &bounds))
+    }
+
+    #[cfg(feature = "system-tray")]
+    fn tray_monitor(&self, tray_id: Option<&str>) -> Result<Option<Monitor>> {
+        let tray = match self.tray_rect(tray_id) {
+            Ok(tray) => tray,
+            Err(Error::TrayPositionUnknown) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let center = PhysicalPosition {
+            x: tray.left() + tray.size.width / 2,
+            y: tray.top() + tray.size.height / 2,
+        };
+        Ok(self
+            .available_monitors()?
+            .into_iter()
+            .find(|monitor| Rect::of_monitor(monitor).contains(center)))
+    }
+
+    #[cfg(feature = "system-tray")]
+    fn tray_position_on(
+        &self,
+        pos: Position,
+        screen: &Monitor,
+        outer_size: PhysicalSize<u32>,
+        tray_gap: i32,
+        flip: bool,
+        tray_id: Option<&str>,
+    ) -> Result<PhysicalPosition<i32>> {
+        if !pos.is_tray_relative() {
+            return self.position_on_sized(pos, screen, outer_size);
+        }
+
+        let window_size = PhysicalSize::<i32> {
+            width: outer_size.width as i32,
+            height: outer_size.height as i32,
+        };
+        let tray = self.tray_rect(tray_id)?;
+        let bounds = match self.tray_monitor(tray_id)? {
+            Some(tray_monitor) => Rect::of_monitor(&tray_monitor),
+            None => Rect::of_monitor(screen),
+        };
+
+        let physical_pos = tray_point(pos, tray, window_size, bounds);
+
+        let physical_pos = if flip {
+            let tie_break = &plugin_state::<R, Config>(self).tie_break;
+            flip_to_fit(physical_pos, window_size, tray, bounds, tie_break)
+        } else {
+            physical_pos
+        };
+        let position = clear_taskbar(physical_pos, tray, bounds, tray_gap);
+        let window = Rect {
+            position,
+            size: window_size,
+        };
+        Ok(plugin_state::<R, Config>(self).clamp(window, &bounds))
+    }
+}
+
+/// The physical size a window of physical `size` at scale factor `from` has at scale factor
+/// `to`, as long as it keeps its logical size.
+pub(crate) fn scaled_size(size: PhysicalSize<u32>, from: f64, to: f64) -> PhysicalSize<u32> {
+    let ratio = to / from;
+    PhysicalSize {
+        width: (size.width as f64 * ratio).round() as u32,
+        height: (size.height as f64 * ratio).round() as u32,
+    }
+}
+
+/// Where a window of the given size goes for a monitor-relative `pos` within `bounds`, or
+/// `None` for tray- and pointer-relative positions.
+pub(crate) fn anchor_point(
+    pos: Position,
+    bounds: Rect,
+    window_size: PhysicalSize<i32>,
+) -> Option<PhysicalPosition<i32>> {
+    use Position::*;
+
+    let screen_position = &bounds.position;
+    let screen_size = bounds.size;
+    Some(match pos {
+        TopLeft => *screen_position,
+        TopRight => PhysicalPosition {
+            x: screen_position.x + (screen_size.width - window_size.width),
+            y: screen_position.y,
+        },
+        BottomLeft => PhysicalPosition {
+            x: screen_position.x,
+            y: screen_position.y + screen_size.height - window_size.height,
+        },
+        BottomRight => PhysicalPosition {
+            x: screen_position.x + (screen_size.width - window_size.width),
+            y: screen_position.y + screen_size.height - window_size.height,
+        },
+        TopCenter => PhysicalPosition {
+            x: screen_position.x + ((screen_size.width / 2) - (window_size.width / 2)),
+            y: screen_position.y,
+        },
+        BottomCenter => PhysicalPosition {
+            x: screen_position.x + ((screen_size.width / 2) - (window_size.width / 2)),
+            y: screen_position.y + screen_size.height - window_size.height,
+        },
+        LeftCenter => PhysicalPosition {
+            x: screen_position.x,
+            y: screen_position.y + (screen_size.height / 2) - (window_size.height / 2),
+        },
+        RightCenter => PhysicalPosition {
+            x: screen_position.x + (screen_size.width - window_size.width),
+            y: screen_position.y + (screen_size.height / 2) - (window_size.height / 2),
+        },
+        Center => PhysicalPosition {
+            x: screen_position.x + ((screen_size.width / 2) - (window_size.width / 2)),
+            y: screen_position.y + (screen_size.height / 2) - (window_size.height / 2),
+        },
+        _ => return None,
+    })
+}
+
+/// Computes where a window of the given outer size goes for `pos` on the monitor at
+/// `monitor_pos` with `monitor_size`, without any window, e.g. to lay out a popup's arrow
+/// before showing it.
+///
+/// Tray-relative positions need the tray icon's bounds, as reported by the last tray
+/// event, and fail with [`Error::TrayPositionUnknown`] without them. Like
+/// [`WindowExt::move_window`], they are kept inside the monitor. Frame extents and the tray
+/// gap aren't taken into account, see [`WindowExt::position_for`] to include them.
+///
+/// Pointer-relative positions depend on the cursor and fail with
+/// [`Error::NotMonitorRelative`].
+pub fn calculate_position(
+    pos: Position,
+    monitor_pos: PhysicalPosition<i32>,
+    monitor_size: PhysicalSize<i32>,
+    window_size: PhysicalSize<i32>,
+    tray_rect: Option<(PhysicalPosition<i32>, PhysicalSize<i32>)>,
+) -> Result<PhysicalPosition<i32>> {
+    let bounds = Rect {
+        position: monitor_pos,
+        size: monitor_size,
+    };
+
+    #[cfg(feature = "system-tray")]
+    if pos.is_tray_relative() {
+        let (position, size) = tray_rect.ok_or(Error::TrayPositionUnknown)?;
+        let tray = Rect { position, size };
+        let window = Rect {
+            position: tray_point(pos, tray, window_size, bounds),
+            size: window_size,
+        };
+        return Ok(window.clamp_within(&bounds).position);
+    }
+    #[cfg(not(feature = "system-tray"))]
+    let _ = tray_rect;
+
+    anchor_point(pos, bounds, window_size)
+        .ok_or_else(|| Error::NotMonitorRelative(pos.name().into()))
+}
+
+/// Where a window of the given size goes for a tray-relative `pos`, before it is moved away
+/// from the taskbar or kept inside `bounds`, the monitor the tray icon is on.
+#[cfg(feature = "system-tray")]
+fn tray_point(
+    pos: Position,
+    tray: Rect,
+    window_size: PhysicalSize<i32>,
+    bounds: Rect,
+) -> PhysicalPosition<i32> {
+    use Position::*;
+
+    // above the tray icon, unless that leaves the top of the screen
+    let above = || {
+        let y = tray.top() - window_size.height;
+        // Choose y value based on the target OS
+        #[cfg(target_os = "windows")]
+        let y = if y < bounds.top() { tray.bottom() } else { y };
 
-                    PhysicalPosition { x, y }
+        #[cfg(target_os = "macos")]
+        let y = if y < bounds.top() { tray.top() } else { y };
+
+        y
+    };
+
+    match pos {
+        TrayLeft => PhysicalPosition {
+            x: tray.left(),
+            y: above(),
+        },
+        TrayBottomLeft => tray.position,
+        TrayRight => PhysicalPosition {
+            x: tray.right(),
+            y: above(),
+        },
+        TrayBottomRight => PhysicalPosition {
+            x: tray.right(),
+            y: tray.top(),
+        },
+        TrayCenter => PhysicalPosition {
+            x: tray.left() + tray.size.width / 2 - window_size.width / 2,
+            y: above(),
+        },
+        TrayBottomCenter => PhysicalPosition {
+            x: tray.left() + (tray.size.width / 2) - (window_size.width / 2),
+            y: tray.top(),
+        },
+        TrayTopLeft => PhysicalPosition {
+            x: tray.left(),
+            y: tray.bottom(),
+        },
+        TrayTopCenter => PhysicalPosition {
+            x: tray.left() + (tray.size.width / 2) - (window_size.width / 2),
+            y: tray.bottom(),
+        },
+        TrayTopRight => PhysicalPosition {
+            x: tray.right(),
+            y: tray.bottom(),
+        },
+        TrayAdjacent => {
+            let in_top_half =
+                tray.top() + tray.size.height / 2 < bounds.top() + bounds.size.height / 2;
+            PhysicalPosition {
+                x: tray.left() + (tray.size.width / 2) - (window_size.width / 2),
+                y: if in_top_half {
+                    tray.bottom()
                 } else {
-                    panic!("Tray position not set");
-                }
+                    tray.top() - window_size.height
+                },
             }
-            #[cfg(feature = "system-tray")]
-            TrayBottomCenter => {
-                if let (Some((tray_x, tray_y)), Some((tray_width, _))) = (tray_position, tray_size)
-                {
-                    PhysicalPosition {
-                        x: tray_x + (tray_width / 2) - (window_size.width / 2),
-                        y: tray_y,
-                    }
-                } else {
-                    panic!("Tray position not set");
+        }
+        _ => anchor_point(pos, bounds, window_size).unwrap_or(bounds.position),
+    }
+}
+
+/// Moves a window of the given size at a tray-relative `position` to the other side of the
+/// tray icon vertically if it doesn't fit inside `bounds` there.
+///
+/// A window centered above the icon's center goes right below it, any other right above it.
+/// If that doesn't fit either while the window would fit right on the other side of the
+/// icon, it goes there instead. Otherwise, including when both sides fit, `tie_break`
+/// decides, with [`TieBreak::PreferAnchorOrder`](crate::TieBreak::PreferAnchorOrder)
+/// keeping the flipped position. The position is left untouched if the window fits, and the
+/// horizontal position is never changed.
+#[cfg(feature = "system-tray")]
+pub(crate) fn flip_to_fit(
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<i32>,
+    tray: Rect,
+    bounds: Rect,
+    tie_break: &crate::TieBreak,
+) -> PhysicalPosition<i32> {
+    let window = Rect { position, size };
+    if window.top() >= bounds.top() && window.bottom() <= bounds.bottom() {
+        return position;
+    }
+
+    let below = Rect {
+        position: PhysicalPosition {
+            x: position.x,
+            y: tray.bottom(),
+        },
+        size,
+    };
+    let above = Rect {
+        position: PhysicalPosition {
+            x: position.x,
+            y: tray.top() - size.height,
+        },
+        size,
+    };
+    let mut candidates = if window.top() + size.height / 2 < tray.top() + tray.size.height / 2 {
+        [below, above]
+    } else {
+        [above, below]
+    };
+    candidates.sort_by(|a, b| {
+        let fits = |r: &Rect| r.top() >= bounds.top() && r.bottom() <= bounds.bottom();
+        fits(b)
+            .cmp(&fits(a))
+            .then_with(|| tie_break.compare((a, &bounds), (b, &bounds)))
+    });
+    candidates[0].position
+}
+
+/// Moves a tray-relative `position` `gap` pixels away from the taskbar.
+///
+/// The taskbar itself isn't reported, so it is assumed to run along the monitor edge
+/// closest to the tray icon, with the icon centered across its thickness. The space between
+/// the icon and the monitor edge is then also the space between the icon and the taskbar's
+/// inner edge, which makes the taskbar the icon's size plus twice that space thick, and the
+/// gap is measured from that inner edge. A gap of zero leaves the position untouched.
+#[cfg(feature = "system-tray")]
+fn clear_taskbar(
+    position: PhysicalPosition<i32>,
+    tray: Rect,
+    monitor: Rect,
+    gap: i32,
+) -> PhysicalPosition<i32> {
+    if gap == 0 || !monitor.contains(tray.position) {
+        return position;
+    }
+
+    let (clearance, dx, dy) = [
+        (tray.top() - monitor.top(), 0, 1),
+        (monitor.bottom() - tray.bottom(), 0, -1),
+        (tray.left() - monitor.left(), 1, 0),
+        (monitor.right() - tray.right(), -1, 0),
+    ]
+    .into_iter()
+    .min_by_key(|(clearance, ..)| *clearance)
+    .unwrap();
+    let offset = clearance.max(0) + gap;
+
+    PhysicalPosition {
+        x: position.x + dx * offset,
+        y: position.y + dy * offset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(feature = "system-tray"))]
+    fn tray_positions_require_the_feature() {
+        for discriminant in TRAY_DISCRIMINANTS {
+            assert!(matches!(
+                Position::try_from(discriminant),
+                Err(Error::TrayFeatureDisabled)
+            ));
+        }
+        for name in TRAY_NAMES {
+            assert!(matches!(
+                name.parse::<Position>(),
+                Err(Error::TrayFeatureDisabled)
+            ));
+        }
+        assert!(matches!(
+            Position::try_from(LAST_DISCRIMINANT + 1),
+            Err(Error::UnknownPosition(_))
+        ));
+        assert!(matches!(
+            "tray-nowhere".parse::<Position>(),
+            Err(Error::UnknownPosition(_))
+        ));
+    }
+
+    #[test]
+    fn anchor_point_on_secondary_monitors() {
+        let window = PhysicalSize {
+            width: 400,
+            height: 300,
+        };
+        // left of, right of, above and below a 1920x1080 primary, and off to the top-left
+        for (x, y, width, height) in [
+            (-2560, 0, 2560, 1440),
+            (1920, 0, 1280, 1024),
+            (0, -1080, 1920, 1080),
+            (240, 1080, 1440, 900),
+            (-1600, -1200, 1600, 1200),
+        ] {
+            let bounds = Rect::new(x, y, width, height);
+            let at = |pos| anchor_point(pos, bounds, window).unwrap();
+            let (right, bottom) = (x + width - 400, y + height - 300);
+            let (center_x, center_y) = (x + width / 2 - 200, y + height / 2 - 150);
+
+            assert_eq!(at(Position::TopLeft), PhysicalPosition { x, y });
+            assert_eq!(at(Position::TopRight), PhysicalPosition { x: right, y });
+            assert_eq!(at(Position::BottomLeft), PhysicalPosition { x, y: bottom });
+            assert_eq!(
+                at(Position::BottomRight),
+                PhysicalPosition {
+                    x: right,
+                    y: bottom
                 }
-            }
+            );
+            assert_eq!(at(Position::TopCenter), PhysicalPosition { x: center_x, y });
+            assert_eq!(
+                at(Position::BottomCenter),
+                PhysicalPosition {
+                    x: center_x,
+                    y: bottom
+                }
+            );
+            assert_eq!(
+                at(Position::LeftCenter),
+                PhysicalPosition { x, y: center_y }
+            );
+            assert_eq!(
+                at(Position::RightCenter),
+                PhysicalPosition {
+                    x: right,
+                    y: center_y
+                }
+            );
+            assert_eq!(
+                at(Position::Center),
+                PhysicalPosition {
+                    x: center_x,
+                    y: center_y
+                }
+            );
+            assert!(bounds.contains_rect(&Rect {
+                position: at(Position::BottomRight),
+                size: window
+            }));
+        }
+    }
+
+    #[test]
+    fn anchor_point_ignores_relative_positions() {
+        let bounds = Rect::new(-1920, 0, 1920, 1080);
+        let window = PhysicalSize {
+            width: 400,
+            height: 300,
+        };
+        assert_eq!(anchor_point(Position::Pointer, bounds, window), None);
+        assert_eq!(anchor_point(Position::PointerCenter, bounds, window), None);
+    }
+
+    #[test]
+    #[cfg(feature = "system-tray")]
+    fn flip_to_fit_at_monitor_edges() {
+        use crate::TieBreak;
+        use std::sync::Arc;
+
+        let bounds = Rect::new(0, 0, 1920, 1080);
+        let size = PhysicalSize {
+            width: 300,
+            height: 400,
+        };
+        let flip = |x, y, tray| {
+            flip_to_fit(
+                PhysicalPosition { x, y },
+                size,
+                tray,
+                bounds,
+                &TieBreak::default(),
+            )
+        };
+
+        // bottom edge: a window below the icon leaves the monitor and goes above it
+        let tray = Rect::new(1800, 1056, 24, 24);
+        assert_eq!(flip(1700, 656, tray), PhysicalPosition { x: 1700, y: 656 });
+        assert_eq!(flip(1700, 1080, tray), PhysicalPosition { x: 1700, y: 656 });
+
+        // top edge: a window above the icon leaves the monitor and goes below it
+        let tray = Rect::new(1800, 0, 24, 24);
+        assert_eq!(flip(1700, 24, tray), PhysicalPosition { x: 1700, y: 24 });
+        assert_eq!(flip(1700, -400, tray), PhysicalPosition { x: 1700, y: 24 });
+
+        // left and right edges: the horizontal position is never changed
+        let tray = Rect::new(0, 528, 24, 24);
+        assert_eq!(flip(-138, 128, tray), PhysicalPosition { x: -138, y: 128 });
+        let tray = Rect::new(1896, 900, 24, 24);
+        assert_eq!(flip(1758, 924, tray), PhysicalPosition { x: 1758, y: 500 });
+
+        // a window that fits on neither side flips unless the tie break prefers otherwise
+        let tall = PhysicalSize {
+            width: 300,
+            height: 700,
+        };
+        let tray = Rect::new(0, 600, 24, 24);
+        let below = PhysicalPosition { x: 0, y: 624 };
+        let above = PhysicalPosition { x: 0, y: -100 };
+        assert_eq!(
+            flip_to_fit(below, tall, tray, bounds, &TieBreak::default()),
+            above
+        );
+        let prefer_low = TieBreak::Custom(Arc::new(|a: &Rect, b: &Rect| b.top().cmp(&a.top())));
+        assert_eq!(flip_to_fit(below, tall, tray, bounds, &prefer_low), below);
+    }
+
+    #[test]
+    fn calculate_position_for_monitor_relative_positions() {
+        let monitor = PhysicalPosition { x: -1920, y: -200 };
+        let monitor_size = PhysicalSize {
+            width: 1920,
+            height: 1080,
+        };
+        let window = PhysicalSize {
+            width: 300,
+            height: 400,
+        };
+        let at = |pos| calculate_position(pos, monitor, monitor_size, window, None);
+
+        assert_eq!(at(Position::TopLeft).unwrap(), monitor);
+        assert_eq!(
+            at(Position::BottomRight).unwrap(),
+            PhysicalPosition { x: -300, y: 480 }
+        );
+        assert_eq!(
+            at(Position::Center).unwrap(),
+            PhysicalPosition { x: -1110, y: 140 }
+        );
+        assert!(matches!(
+            at(Position::Pointer),
+            Err(Error::NotMonitorRelative(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "system-tray")]
+    fn calculate_position_for_tray_relative_positions() {
+        let monitor = PhysicalPosition { x: 1920, y: -200 };
+        let monitor_size = PhysicalSize {
+            width: 1920,
+            height: 1080,
+        };
+        let window = PhysicalSize {
+            width: 300,
+            height: 400,
+        };
+        let at = |pos, tray: Option<Rect>| {
+            let tray = tray.map(|tray| (tray.position, tray.size));
+            calculate_position(pos, monitor, monitor_size, window, tray)
+        };
+
+        assert!(matches!(
+            at(Position::TrayCenter, None),
+            Err(Error::TrayPositionUnknown)
+        ));
+        let tray = Rect::new(3500, 856, 24, 24);
+        assert_eq!(
+            at(Position::TrayCenter, Some(tray)).unwrap(),
+            PhysicalPosition { x: 3362, y: 456 }
+        );
+        // kept inside the monitor near its right edge
+        let tray = Rect::new(3820, 856, 24, 24);
+        assert_eq!(
+            at(Position::TrayRight, Some(tray)).unwrap(),
+            PhysicalPosition { x: 3540, y: 456 }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "system-tray")]
+    fn tray_point_around_the_icon() {
+        let bounds = Rect::new(1920, -200, 1920, 1080);
+        let window = PhysicalSize {
+            width: 300,
+            height: 400,
         };
+        let tray = Rect::new(3700, 856, 24, 24);
+        let at = |pos| tray_point(pos, tray, window, bounds);
 
-        self.set_position(tauri::Position::Physical(physical_pos))
+        assert_eq!(at(Position::TrayLeft), PhysicalPosition { x: 3700, y: 456 });
+        assert_eq!(
+            at(Position::TrayRight),
+            PhysicalPosition { x: 3724, y: 456 }
+        );
+        assert_eq!(
+            at(Position::TrayCenter),
+            PhysicalPosition { x: 3562, y: 456 }
+        );
+        assert_eq!(
+            at(Position::TrayBottomLeft),
+            PhysicalPosition { x: 3700, y: 856 }
+        );
+        assert_eq!(
+            at(Position::TrayBottomRight),
+            PhysicalPosition { x: 3724, y: 856 }
+        );
+        assert_eq!(
+            at(Position::TrayBottomCenter),
+            PhysicalPosition { x: 3562, y: 856 }
+        );
+        assert_eq!(
+            at(Position::TrayTopLeft),
+            PhysicalPosition { x: 3700, y: 880 }
+        );
+        assert_eq!(
+            at(Position::TrayTopCenter),
+            PhysicalPosition { x: 3562, y: 880 }
+        );
+        assert_eq!(
+            at(Position::TrayTopRight),
+            PhysicalPosition { x: 3724, y: 880 }
+        );
+        // in the bottom half of the monitor, so above the icon
+        assert_eq!(
+            at(Position::TrayAdjacent),
+            PhysicalPosition { x: 3562, y: 456 }
+        );
+        // monitor-relative positions fall back to the monitor anchors
+        assert_eq!(at(Position::TopLeft), bounds.position);
+    }
+
+    #[test]
+    #[cfg(feature = "system-tray")]
+    fn tray_point_near_the_top_of_the_monitor() {
+        let bounds = Rect::new(1920, -200, 1920, 1080);
+        let window = PhysicalSize {
+            width: 300,
+            height: 400,
+        };
+
+        // above a negative y, but still inside the monitor
+        let tray = Rect::new(3700, 300, 24, 24);
+        assert_eq!(
+            tray_point(Position::TrayLeft, tray, window, bounds),
+            PhysicalPosition { x: 3700, y: -100 }
+        );
+
+        let tray = Rect::new(3700, -200, 24, 24);
+        let y = tray_point(Position::TrayLeft, tray, window, bounds).y;
+        #[cfg(target_os = "windows")]
+        assert_eq!(y, tray.bottom());
+        #[cfg(target_os = "macos")]
+        assert_eq!(y, tray.top());
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        assert_eq!(y, -600);
+    }
+
+    #[test]
+    fn anchor_point_across_scale_factors() {
+        // a 1x monitor with a 2x one to its right, whose top edge is higher
+        let low = (Rect::new(0, 0, 1920, 1080), 1.0);
+        let high = (Rect::new(1920, -360, 3840, 2160), 2.0);
+        let size_at_1x = PhysicalSize {
+            width: 800,
+            height: 600,
+        };
+
+        for ((_, from), (to_bounds, to)) in [(low, high), (high, low)] {
+            // the size the window has on the monitor it leaves
+            let size = scaled_size(size_at_1x, 1.0, from);
+            let size = scaled_size(size, from, to);
+            assert_eq!(size, scaled_size(size_at_1x, 1.0, to));
+            let size = PhysicalSize {
+                width: size.width as i32,
+                height: size.height as i32,
+            };
+
+            let top_right = anchor_point(Position::TopRight, to_bounds, size).unwrap();
+            assert_eq!(top_right.x + size.width, to_bounds.right());
+            assert_eq!(top_right.y, to_bounds.top());
+
+            let bottom_center = anchor_point(Position::BottomCenter, to_bounds, size).unwrap();
+            assert_eq!(
+                bottom_center.x - to_bounds.left(),
+                to_bounds.right() - (bottom_center.x + size.width)
+            );
+            assert_eq!(bottom_center.y + size.height, to_bounds.bottom());
+        }
+
+        let size = PhysicalSize {
+            width: 1600,
+            height: 1200,
+        };
+        assert_eq!(
+            anchor_point(Position::TopRight, high.0, size),
+            Some(PhysicalPosition { x: 4160, y: -360 })
+        );
+        assert_eq!(
+            anchor_point(Position::BottomCenter, high.0, size),
+            Some(PhysicalPosition { x: 3040, y: 600 })
+        );
     }
 }
@@ -0,0 +1,291 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{ext::WindowExtInternal, Error, Position, Rect, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tauri::{LogicalSize, Manager, PhysicalPosition, PhysicalSize, Runtime, Window};
+
+/// The version of the document written by [`export_layout`].
+pub const LAYOUT_VERSION: u32 = 1;
+
+/// The outer position and size of a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+}
+
+impl WindowGeometry {
+    /// Reads the current outer geometry of the given [`Window`].
+    pub fn of<R: Runtime>(window: &Window<R>) -> Result<Self> {
+        Ok(Self {
+            position: window.outer_position()?,
+            size: window.outer_size()?,
+        })
+    }
+}
+
+/// Where a window is and which [`Position`] it is docked closest to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowPlacement {
+    pub label: String,
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    /// The index of the window's monitor in `available_monitors()`, or `None` if it isn't
+    /// on any.
+    pub monitor_index: Option<usize>,
+    /// The monitor-relative [`Position`] nearest to the window, see
+    /// [`WindowExt::nearest_position`](crate::WindowExt::nearest_position).
+    pub anchor: Option<Position>,
+}
+
+/// Returns the geometry of every window of the app, sorted by label.
+///
+/// The monitors are only queried once for all windows, which makes this cheaper than
+/// asking for each window separately.
+pub fn get_all_window_geometries<R: Runtime, M: Manager<R>>(
+    manager: &M,
+) -> Result<Vec<WindowPlacement>> {
+    let mut windows: Vec<_> = manager.windows().into_iter().collect();
+    windows.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let monitors = match windows.first() {
+        Some((_, window)) => window.available_monitors()?,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut placements = Vec::new();
+    for (label, window) in windows {
+        let geometry = WindowGeometry::of(&window)?;
+        let center = PhysicalPosition {
+            x: geometry.position.x + geometry.size.width as i32 / 2,
+            y: geometry.position.y + geometry.size.height as i32 / 2,
+        };
+        let monitor_index = monitors
+            .iter()
+            .position(|m| Rect::of_monitor(m).contains(center));
+        let anchor = match monitor_index {
+            Some(index) => Some(window.nearest_anchor(&monitors[index])?.0),
+            None => None,
+        };
+
+        placements.push(WindowPlacement {
+            label,
+            position: geometry.position,
+            size: geometry.size,
+            monitor_index,
+            anchor,
+        });
+    }
+    Ok(placements)
+}
+
+/// A snapshot of the geometry of a set of windows, keyed by window label.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Layout {
+    windows: BTreeMap<String, WindowGeometry>,
+}
+
+impl Layout {
+    /// Captures the geometry of every window of the app.
+    pub fn capture<R: Runtime, M: Manager<R>>(manager: &M) -> Result<Self> {
+        let mut windows = BTreeMap::new();
+        for (label, window) in manager.windows() {
+            windows.insert(label, WindowGeometry::of(&window)?);
+        }
+        Ok(Self { windows })
+    }
+
+    /// Captures the geometry of the windows with the given labels.
+    ///
+    /// Labels that don't belong to an open window are skipped.
+    pub fn capture_windows<R: Runtime, M: Manager<R>>(
+        manager: &M,
+        labels: &[&str],
+    ) -> Result<Self> {
+        let mut windows = BTreeMap::new();
+        for label in labels {
+            if let Some(window) = manager.get_window(label) {
+                windows.insert(label.to_string(), WindowGeometry::of(&window)?);
+            }
+        }
+        Ok(Self { windows })
+    }
+
+    /// Returns the captured geometry of the window with the given label.
+    pub fn get(&self, label: &str) -> Option<&WindowGeometry> {
+        self.windows.get(label)
+    }
+
+    /// Records the geometry of a window, replacing any previous entry.
+    pub fn insert(&mut self, label: impl Into<String>, geometry: WindowGeometry) {
+        self.windows.insert(label.into(), geometry);
+    }
+
+    /// Iterates over the captured windows in label order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &WindowGeometry)> {
+        self.windows.iter()
+    }
+
+    /// Returns how far each window moved between `self` and `other`.
+    ///
+    /// The delta is `other - self`, so applying the negated delta to a window in `other`
+    /// moves it back to where it was in `self`. Only windows present in both layouts are
+    /// reported, in label order, including windows that didn't move.
+    pub fn diff(&self, other: &Layout) -> Vec<(String, PhysicalPosition<i32>)> {
+        self.windows
+            .iter()
+            .filter_map(|(label, before)| {
+                other.windows.get(label).map(|after| {
+                    (
+                        label.clone(),
+                        PhysicalPosition {
+                            x: after.position.x - before.position.x,
+                            y: after.position.y - before.position.y,
+                        },
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// What [`import_layout`] does with a window whose monitor isn't connected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingMonitorPolicy {
+    /// Apply the anchor and offset on the primary monitor.
+    #[default]
+    Primary,
+    /// Apply the anchor and offset on the monitor the window is currently on.
+    Current,
+    /// Leave the window where it is.
+    Skip,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LayoutDocument {
+    version: u32,
+    windows: Vec<PortableWindow>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PortableWindow {
+    label: String,
+    monitor: Option<String>,
+    anchor: String,
+    offset: PhysicalPosition<i32>,
+    size: LogicalSize<f64>,
+}
+
+/// Exports the arrangement of the windows with the given labels as a JSON document.
+///
+/// Windows are described relative to their monitor rather than by absolute coordinates,
+/// so the layout can be applied on a machine whose monitors are arranged differently:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "windows": [
+///     {
+///       "label": "main",
+///       "monitor": "DELL U2720Q",
+///       "anchor": "top-right",
+///       "offset": { "x": -16, "y": 16 },
+///       "size": { "width": 800.0, "height": 600.0 }
+///     }
+///   ]
+/// }
+/// ```
+///
+/// - `monitor` is the name of the monitor the window is on, or `null` if it has no name.
+/// - `anchor` is the name of the closest monitor-relative [`Position`], see [`Position::name`].
+/// - `offset` is the distance in physical pixels from that anchor to the window.
+/// - `size` is the logical inner size of the window.
+///
+/// `version` is bumped whenever the meaning of an existing field changes. New fields may be
+/// added without a version bump and are ignored by older readers.
+///
+/// Labels that don't belong to an open window are skipped.
+pub fn export_layout<R: Runtime, M: Manager<R>>(manager: &M, labels: &[&str]) -> Result<String> {
+    let mut windows = Vec::new();
+    for label in labels {
+        let window = match manager.get_window(label) {
+            Some(window) => window,
+            None => continue,
+        };
+        let monitor = window.current_monitor()?.ok_or(Error::NoMonitor)?;
+        let (anchor, offset) = window.nearest_anchor(&monitor)?;
+        windows.push(PortableWindow {
+            label: label.to_string(),
+            monitor: monitor.name().cloned(),
+            anchor: anchor.name().into(),
+            offset,
+            size: window.inner_size()?.to_logical(monitor.scale_factor()),
+        });
+    }
+
+    serde_json::to_string(&LayoutDocument {
+        version: LAYOUT_VERSION,
+        windows,
+    })
+    .map_err(Into::into)
+}
+
+/// Applies a layout created by [`export_layout`].
+///
+/// Monitors are matched by name. Windows whose monitor isn't connected are handled according
+/// to `policy`, and windows that aren't open are skipped.
+pub fn import_layout<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    json: &str,
+    policy: MissingMonitorPolicy,
+) -> Result<()> {
+    let document: LayoutDocument = serde_json::from_str(json)?;
+    if document.version > LAYOUT_VERSION {
+        return Err(Error::UnsupportedLayoutVersion(document.version));
+    }
+
+    for entry in document.windows {
+        let window = match manager.get_window(&entry.label) {
+            Some(window) => window,
+            None => continue,
+        };
+        let anchor = Position::from_name(&entry.anchor)
+            .ok_or_else(|| Error::UnknownPosition(entry.anchor.clone()))?;
+
+        let monitor = window
+            .available_monitors()?
+            .into_iter()
+            .find(|m| entry.monitor.is_some() && m.name() == entry.monitor.as_ref());
+        let monitor = match (monitor, policy) {
+            (Some(monitor), _) => Some(monitor),
+            (None, MissingMonitorPolicy::Primary) => window.primary_monitor()?,
+            (None, MissingMonitorPolicy::Current) => window.current_monitor()?,
+            (None, MissingMonitorPolicy::Skip) => None,
+        };
+        let monitor = match monitor {
+            Some(monitor) => monitor,
+            None => continue,
+        };
+
+        let inner_size: PhysicalSize<u32> = entry.size.to_physical(monitor.scale_factor());
+        // the new size may not be applied yet, so compute the outer size from the decorations
+        let (outer, inner) = (window.outer_size()?, window.inner_size()?);
+        let target_size = PhysicalSize {
+            width: inner_size.width + outer.width.saturating_sub(inner.width),
+            height: inner_size.height + outer.height.saturating_sub(inner.height),
+        };
+        window.set_size(tauri::Size::Physical(inner_size))?;
+        let target = window.position_on_sized(anchor, &monitor, target_size)?;
+        window.place(
+            PhysicalPosition {
+                x: target.x + entry.offset.x,
+                y: target.y + entry.offset.y,
+            },
+            &monitor,
+        )?;
+    }
+
+    Ok(())
+}
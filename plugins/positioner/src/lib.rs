@@ -8,64 +8,434 @@
 //! - **system-tray**: Enables system-tray-relative positions.
 //!   
 //!   Note: This requires attaching the Tauri plugin, *even* when using the trait extension only.
+//! - **store**: Enables mirroring window placements into a store of the store plugin, see
+//!   [`Builder::with_store_mirror`].
 
+mod animation;
+mod aspect;
+mod cmd;
+mod confine;
+mod diagnostics;
+mod dock;
+mod error;
 mod ext;
+mod layout;
+#[cfg(feature = "store")]
+mod mirror;
+mod monitor;
+mod overlay;
+mod placement;
+mod platform;
+mod present;
+mod rect;
+mod tiling;
+mod toast;
+mod tracking;
+mod zones;
 
+pub use animation::{AnimationConfig, Easing, FlickConfig};
+pub use diagnostics::{diagnostics, Diagnostics};
+use dock::FocusHistory;
+pub use dock::Side;
+pub use error::{Error, Result};
 pub use ext::*;
+pub use layout::{
+    export_layout, get_all_window_geometries, import_layout, Layout, MissingMonitorPolicy,
+    WindowGeometry, WindowPlacement, LAYOUT_VERSION,
+};
+pub use monitor::{get_monitors, MonitorDetails, MonitorStrategy};
+pub use placement::TieBreak;
+pub use rect::{Margins, Rect};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
 use tauri::{
     plugin::{self, TauriPlugin},
-    Result, Runtime,
+    Manager, Monitor, PhysicalPosition, RunEvent, Runtime, State, Window, WindowEvent,
 };
+pub use tiling::tile_rects;
+pub use toast::ToastStack;
+pub use tracking::SizeThreshold;
+pub use zones::{register_zones, Zone};
+
+#[cfg(feature = "system-tray")]
+use tauri::{AppHandle, SystemTrayEvent};
+
+/// Returns the state of type `T`, registering its default value on first use so that the
+/// [`WindowExt`] methods also work when the plugin isn't attached.
+pub(crate) fn plugin_state<R: Runtime, T: Default + Send + Sync + 'static>(
+    manager: &impl Manager<R>,
+) -> State<'_, T> {
+    manager.manage(T::default());
+    manager.state::<T>()
+}
 
+/// The bounds of the app's tray icons as reported by [`on_tray_event`], keyed by tray id.
+///
+/// Tray-relative positions use the icon that was updated most recently unless a tray id
+/// is given, see [`WindowExt::move_window_to_tray`].
 #[cfg(feature = "system-tray")]
-use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, SystemTrayEvent};
+#[derive(Default)]
+pub struct Tray(std::sync::Mutex<Vec<(String, Rect)>>);
 
 #[cfg(feature = "system-tray")]
-struct Tray(std::sync::Mutex<Option<(PhysicalPosition<f64>, PhysicalSize<f64>)>>);
+impl Tray {
+    /// Forgets the bounds of the tray icon with the given id, e.g. after it was removed.
+    pub fn forget(&self, id: &str) {
+        self.0.lock().unwrap().retain(|(tray_id, _)| tray_id != id);
+    }
+
+    fn update(&self, id: &str, rect: Rect) {
+        let mut trays = self.0.lock().unwrap();
+        trays.retain(|(tray_id, _)| tray_id != id);
+        trays.insert(0, (id.to_string(), rect));
+    }
+
+    /// The bounds of the tray icon with the given id, or of the most recently updated one.
+    ///
+    /// This is `None` until [`on_tray_event`] saw an event for the icon.
+    pub fn rect(&self, id: Option<&str>) -> Option<Rect> {
+        let trays = self.0.lock().unwrap();
+        match id {
+            Some(id) => trays.iter().find(|(tray_id, _)| tray_id == id),
+            None => trays.first(),
+        }
+        .map(|(_, rect)| *rect)
+    }
+}
+
+/// The last known bounds of the tray icon with the given id, or of the most recently
+/// updated one, e.g. to point a popup's arrow at the icon.
+///
+/// The bounds are captured by [`on_tray_event`], so this is always `None` without the
+/// `system-tray` feature and until the first tray event arrives.
+pub fn tray_rect<R: Runtime>(manager: &impl Manager<R>, tray_id: Option<&str>) -> Option<Rect> {
+    #[cfg(feature = "system-tray")]
+    return plugin_state::<R, Tray>(manager).rect(tray_id);
+    #[cfg(not(feature = "system-tray"))]
+    {
+        let _ = (manager, tray_id);
+        None
+    }
+}
 
 #[cfg(feature = "system-tray")]
 pub fn on_tray_event<R: Runtime>(app: &AppHandle<R>, event: &SystemTrayEvent) {
     match event {
-        SystemTrayEvent::LeftClick { position, size, .. } => {
-            app.state::<Tray>()
-                .0
-                .lock()
-                .unwrap()
-                .replace((*position, *size));
+        SystemTrayEvent::LeftClick {
+            tray_id,
+            position,
+            size,
+            ..
         }
-        SystemTrayEvent::RightClick { position, size, .. } => {
-            app.state::<Tray>()
-                .0
-                .lock()
-                .unwrap()
-                .replace((*position, *size));
+        | SystemTrayEvent::RightClick {
+            tray_id,
+            position,
+            size,
+            ..
         }
-        SystemTrayEvent::DoubleClick { position, size, .. } => {
-            app.state::<Tray>()
-                .0
-                .lock()
-                .unwrap()
-                .replace((*position, *size));
+        | SystemTrayEvent::DoubleClick {
+            tray_id,
+            position,
+            size,
+            ..
+        } => {
+            let rect = Rect::new(
+                position.x as i32,
+                position.y as i32,
+                size.width as i32,
+                size.height as i32,
+            );
+            plugin_state::<R, Tray>(app).update(tray_id, rect);
         }
         _ => (),
     }
 }
 
-#[tauri::command]
-async fn move_window<R: Runtime>(window: tauri::Window<R>, position: Position) -> Result<()> {
-    window.move_window(position)
+/// The plugin configuration, set through the [`Builder`].
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Config {
+    pub(crate) animated_tracking: Option<AnimationConfig>,
+    pub(crate) flick: FlickConfig,
+    /// The position every window is moved to when it is created, keyed by label.
+    pub(crate) anchors: BTreeMap<String, Position>,
+    /// The position windows without an anchor are moved to when they are created.
+    pub(crate) default_position: Option<Position>,
+    /// The labels of the windows the default position applies to, or `None` for all.
+    pub(crate) default_position_windows: Option<BTreeSet<String>>,
+    pub(crate) defer_to_window_state: bool,
+    /// The names of the monitors windows must not be positioned on.
+    pub(crate) excluded_monitors: BTreeSet<String>,
+    pub(crate) tie_break: TieBreak,
+    /// Whether computed positions may leave the monitor.
+    pub(crate) unclamped: bool,
+    #[cfg(feature = "system-tray")]
+    pub(crate) tray_gap: i32,
+    #[cfg(feature = "system-tray")]
+    pub(crate) fallback_to_cursor: bool,
+    /// The store path and key window placements are mirrored to.
+    #[cfg(feature = "store")]
+    pub(crate) store_mirror: Option<(std::path::PathBuf, String)>,
 }
 
-/// The Tauri plugin that exposes [`WindowExt::move_window`] to the webview.
-pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    let plugin =
-        plugin::Builder::new("positioner").invoke_handler(tauri::generate_handler![move_window]);
+impl Config {
+    /// The position the window is moved to when it is created, if any.
+    fn initial_position<R: Runtime>(&self, window: &Window<R>) -> Option<Position> {
+        if let Some(anchor) = self.anchors.get(window.label()) {
+            return Some(*anchor);
+        }
+
+        let position = self.default_position?;
+        if let Some(labels) = &self.default_position_windows {
+            if !labels.contains(window.label()) {
+                return None;
+            }
+        }
+        // leave windows alone that were given explicit coordinates in the app config
+        let placed_explicitly = window
+            .config()
+            .tauri
+            .windows
+            .iter()
+            .any(|w| w.label == window.label() && (w.x.is_some() || w.y.is_some()));
+        (!placed_explicitly).then_some(position)
+    }
+
+    /// Where the window ends up after shifting it, without resizing it, to lie inside
+    /// `bounds`, unless clamping was turned off with [`Builder::with_clamping`].
+    ///
+    /// A window larger than `bounds` is aligned with its top-left corner.
+    pub(crate) fn clamp(&self, window: Rect, bounds: &Rect) -> PhysicalPosition<i32> {
+        if self.unclamped {
+            window.position
+        } else {
+            window.clamp_within(bounds).position
+        }
+    }
+
+    /// Whether windows must not be positioned on the given monitor.
+    pub(crate) fn is_excluded(&self, monitor: &Monitor) -> bool {
+        monitor
+            .name()
+            .map_or(false, |name| self.excluded_monitors.contains(name))
+    }
+}
+
+/// Builds the positioner plugin.
+#[derive(Default)]
+pub struct Builder {
+    config: Config,
+}
 
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Animates the moves triggered by window tracking instead of snapping windows to their
+    /// new position, e.g. when a tracked window is resized past a breakpoint.
+    ///
+    /// A move that is triggered while the previous one is still animating interrupts it and
+    /// glides on from wherever the window currently is. Off by default.
+    pub fn with_animated_tracking(mut self, config: AnimationConfig) -> Self {
+        self.config.animated_tracking = Some(config);
+        self
+    }
+
+    /// Sets how windows glide and snap after [`WindowExt::flick`]. Defaults to
+    /// [`FlickConfig::default`].
+    pub fn with_flick(mut self, config: FlickConfig) -> Self {
+        self.config.flick = config;
+        self
+    }
+
+    /// Moves the window with the given label to `position` when it is created.
+    ///
+    /// Takes precedence over [`Builder::with_default_position`].
+    pub fn with_anchor(mut self, label: impl Into<String>, position: Position) -> Self {
+        self.config.anchors.insert(label.into(), position);
+        self
+    }
+
+    /// Moves every window to `position` when it is created, before its content is shown,
+    /// instead of leaving it where the OS puts it.
+    ///
+    /// Windows with an anchor set with [`Builder::with_anchor`] go to their anchor instead,
+    /// and windows given explicit `x`/`y` coordinates in the app config are left alone. See
+    /// [`Builder::for_windows`] to limit this to some windows.
+    pub fn with_default_position(mut self, position: Position) -> Self {
+        self.config.default_position = Some(position);
+        self
+    }
+
+    /// Whether windows are kept fully inside the monitor they are positioned on, e.g. a tray
+    /// popup for an icon at the very edge of the taskbar, by shifting them without resizing.
+    ///
+    /// Windows larger than the monitor are aligned with its top-left corner. Turn this off to
+    /// deliberately place windows partly off-screen. On by default.
+    pub fn with_clamping(mut self, clamp: bool) -> Self {
+        self.config.unclamped = !clamp;
+        self
+    }
+
+    /// Limits [`Builder::with_default_position`] to the windows with the given labels.
+    pub fn for_windows<I, L>(mut self, labels: I) -> Self
+    where
+        I: IntoIterator<Item = L>,
+        L: Into<String>,
+    {
+        self.config.default_position_windows = Some(labels.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets which placement wins when several candidates fit equally well. Defaults to
+    /// [`TieBreak::PreferAnchorOrder`].
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.config.tie_break = tie_break;
+        self
+    }
+
+    /// Keeps windows off the monitors with the given names, e.g. a display that is being
+    /// captured or streamed.
+    ///
+    /// Excluded monitors are removed from the candidates before a [`MonitorStrategy`] picks
+    /// one, so they are never selected automatically, and [`MonitorStrategy::Index`] counts
+    /// the remaining monitors only. Moving a window to an excluded monitor explicitly fails
+    /// with [`Error::MonitorExcluded`], unless it is done with
+    /// [`WindowExt::force_move_window_with_monitor`].
+    pub fn with_excluded_monitors(mut self, names: Vec<String>) -> Self {
+        self.config.excluded_monitors = names.into_iter().collect();
+        self
+    }
+
+    /// Leaves persisting window geometry to the window-state plugin and only applies the
+    /// anchors set with [`Builder::with_anchor`] on top of the restored geometry.
+    ///
+    /// Both plugins act when a window is created, so without this the one registered last
+    /// clobbers the other's position. With it, window-state restores the saved position and
+    /// size first, then the positioner re-applies the anchor on the monitor the window was
+    /// restored to, regardless of the order the plugins were registered in. The anchor is
+    /// therefore applied shortly after the window is created rather than before it is first
+    /// shown. Off by default.
+    pub fn defer_persistence_to_window_state(mut self, defer: bool) -> Self {
+        self.config.defer_to_window_state = defer;
+        self
+    }
+
+    /// Mirrors the placement of every window the plugin moves into the store plugin's store
+    /// at `store_path`, under `key`, so the frontend can bind to it reactively.
+    ///
+    /// The key holds an object with an entry per window label:
+    ///
+    /// ```json
+    /// {
+    ///   "main": {
+    ///     "anchor": "top-right",
+    ///     "position": { "x": 1120, "y": 0 },
+    ///     "size": { "width": 800, "height": 600 },
+    ///     "monitor": "DELL U2720Q"
+    ///   }
+    /// }
+    /// ```
+    ///
+    /// `anchor` is the nearest monitor-relative [`Position`], `position` and `size` are the
+    /// window's outer geometry in physical pixels and `monitor` is the name of its monitor,
+    /// if it has one. Entries are updated after every successful move, including the ones
+    /// triggered by tracking, and emit the store's usual change event. Nothing is written to
+    /// disk until the store is saved. Requires the store plugin to be registered.
+    #[cfg(feature = "store")]
+    pub fn with_store_mirror(
+        mut self,
+        store_path: impl Into<std::path::PathBuf>,
+        key: impl Into<String>,
+    ) -> Self {
+        self.config.store_mirror = Some((store_path.into(), key.into()));
+        self
+    }
+
+    /// Keeps windows moved to a tray-relative [`Position`] `gap` pixels away from the
+    /// taskbar, so they hover next to it instead of touching the tray icon.
+    ///
+    /// The gap is measured from the taskbar's edge, which is estimated from where the tray
+    /// icon sits on its monitor. Defaults to `0`, which places windows right at the icon.
+    #[cfg(feature = "system-tray")]
+    pub fn with_tray_gap(mut self, gap: i32) -> Self {
+        self.config.tray_gap = gap;
+        self
+    }
+
+    /// Positions windows moved to a tray-relative [`Position`] relative to the mouse cursor
+    /// while the tray icon's bounds are unknown, instead of failing with
+    /// [`Error::TrayPositionUnknown`].
+    ///
+    /// Useful where tray events don't report the icon's bounds, e.g. with some
+    /// StatusNotifier hosts on Linux, since the cursor is usually on the icon right after a
+    /// click. Defaults to `false`.
     #[cfg(feature = "system-tray")]
-    let plugin = plugin.setup(|app_handle| {
-        app_handle.manage(Tray(std::sync::Mutex::new(None)));
-        Ok(())
-    });
+    pub fn fallback_to_cursor(mut self, fallback: bool) -> Self {
+        self.config.fallback_to_cursor = fallback;
+        self
+    }
+
+    pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
+        let config = self.config;
+        plugin::Builder::new("positioner")
+            .invoke_handler(tauri::generate_handler![
+                cmd::move_window,
+                cmd::move_window_relative,
+                cmd::move_window_relative_to_window,
+                cmd::move_window_to_monitor,
+                cmd::current_position_kind,
+                cmd::get_monitors,
+                cmd::get_tray_rect,
+                cmd::get_all_window_geometries,
+                cmd::diagnostics,
+                cmd::preview_zone_at,
+                cmd::drop_into_zone_at
+            ])
+            .setup(move |app_handle| {
+                app_handle.manage(config);
+                #[cfg(feature = "system-tray")]
+                app_handle.manage(Tray::default());
+                Ok(())
+            })
+            .on_webview_ready(|window| {
+                let config = plugin_state::<R, Config>(&window);
+                let anchor = match config.initial_position(&window) {
+                    Some(anchor) => anchor,
+                    None => return,
+                };
+
+                let monitor = match window.current_monitor() {
+                    Ok(Some(monitor)) if !config.defer_to_window_state => monitor,
+                    // window-state restores windows synchronously in its own hook, while calls
+                    // made from another thread are queued behind all the hooks, so moving from
+                    // there lands after the restore and waits for the monitor without
+                    // blocking the main thread
+                    _ => {
+                        std::thread::spawn(move || {
+                            let _ = window.move_window_when_ready(anchor);
+                        });
+                        return;
+                    }
+                };
+                let _ = window.move_window_with_monitor(anchor, &monitor);
+            })
+            .on_event(|app_handle, event| {
+                if let RunEvent::WindowEvent { label, event, .. } = event {
+                    let history = plugin_state::<R, FocusHistory>(app_handle);
+                    match event {
+                        WindowEvent::Focused(true) => history.focused(label),
+                        WindowEvent::Destroyed => history.destroyed(label),
+                        _ => {}
+                    }
+                }
+            })
+            .build()
+    }
+}
 
-    plugin.build()
+/// The Tauri plugin that exposes [`WindowExt::move_window`] to the webview.
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    Builder::default().build()
 }
@@ -0,0 +1,48 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{ext::WindowExtInternal, plugin_state, Config};
+use serde_json::{json, Map, Value};
+use tauri::{Manager, Monitor, PhysicalPosition, Runtime, Window};
+use tauri_plugin_store::{with_store, StoreCollection};
+
+/// Records where the window was moved to in the store set with
+/// [`Builder::with_store_mirror`](crate::Builder::with_store_mirror), if any.
+///
+/// Mirroring is best effort and never fails the move itself.
+pub(crate) fn record<R: Runtime>(
+    window: &Window<R>,
+    monitor: &Monitor,
+    position: PhysicalPosition<i32>,
+) {
+    let (path, key) = match &plugin_state::<R, Config>(window).store_mirror {
+        Some(mirror) => mirror.clone(),
+        None => return,
+    };
+    let stores = match window.try_state::<StoreCollection<R>>() {
+        Some(stores) => stores,
+        None => return,
+    };
+    let (anchor, size) = match (
+        window.anchor_nearest_to(monitor, position),
+        window.outer_size(),
+    ) {
+        (Ok((anchor, _)), Ok(size)) => (anchor, size),
+        _ => return,
+    };
+
+    let placement = json!({
+        "anchor": anchor,
+        "position": position,
+        "size": size,
+        "monitor": monitor.name(),
+    });
+    let _ = with_store(window.app_handle(), stores, path, |store| {
+        let mut placements = match store.get(&key) {
+            Some(Value::Object(placements)) => placements.clone(),
+            _ => Map::new(),
+        };
+        placements.insert(window.label().to_string(), placement);
+        store.insert(key, Value::Object(placements))
+    });
+}
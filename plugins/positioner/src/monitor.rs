@@ -0,0 +1,152 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{platform, plugin_state, Config, Result};
+use serde::Serialize;
+use std::{fmt, thread::sleep, time::Duration};
+use tauri::{Monitor, PhysicalPosition, PhysicalSize, Runtime, Window};
+
+/// How many times to query the monitors before giving up while they are still settling.
+const READY_ATTEMPTS: u32 = 10;
+/// How long to wait between two attempts.
+const READY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Everything the plugin knows about a monitor.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorDetails {
+    pub name: Option<String>,
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+    /// The refresh rate in Hz.
+    ///
+    /// Currently reported on Windows and Linux only, and `None` wherever the platform
+    /// doesn't know it.
+    pub refresh_rate: Option<u32>,
+}
+
+impl MonitorDetails {
+    fn new<R: Runtime>(window: &Window<R>, monitor: &Monitor, primary: Option<&Monitor>) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            position: *monitor.position(),
+            size: *monitor.size(),
+            scale_factor: monitor.scale_factor(),
+            is_primary: primary.map_or(false, |p| p.position() == monitor.position()),
+            refresh_rate: platform::refresh_rate(window, monitor),
+        }
+    }
+}
+
+/// Returns the details of every monitor, following the ordering of `available_monitors()`.
+pub fn get_monitors<R: Runtime>(window: &Window<R>) -> Result<Vec<MonitorDetails>> {
+    let primary = window.primary_monitor()?;
+    Ok(window
+        .available_monitors()?
+        .iter()
+        .map(|monitor| MonitorDetails::new(window, monitor, primary.as_ref()))
+        .collect())
+}
+
+type MonitorPredicate = Box<dyn Fn(&MonitorDetails) -> bool + Send + Sync>;
+
+/// Selects the monitor a window should be positioned on.
+pub enum MonitorStrategy {
+    /// The monitor the window is currently on.
+    Current,
+    /// The primary monitor of the system.
+    Primary,
+    /// The monitor at the given index, following the ordering of `available_monitors()`.
+    Index(usize),
+    /// The monitor with the given name.
+    Name(String),
+    /// The first monitor the predicate accepts, e.g. to pick a monitor by refresh rate.
+    Predicate(MonitorPredicate),
+}
+
+impl fmt::Debug for MonitorStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Current => f.write_str("Current"),
+            Self::Primary => f.write_str("Primary"),
+            Self::Index(index) => f.debug_tuple("Index").field(index).finish(),
+            Self::Name(name) => f.debug_tuple("Name").field(name).finish(),
+            Self::Predicate(_) => f.write_str("Predicate(..)"),
+        }
+    }
+}
+
+impl MonitorStrategy {
+    /// Resolves the strategy against the monitors `window` can see.
+    ///
+    /// Monitors excluded with [`Builder::with_excluded_monitors`](crate::Builder::with_excluded_monitors)
+    /// are never selected. Returns `None` if no matching monitor is currently connected.
+    pub fn resolve<R: Runtime>(&self, window: &Window<R>) -> Result<Option<Monitor>> {
+        let config = plugin_state::<R, Config>(window);
+        let monitor = self.select(window, |m| !config.is_excluded(m))?;
+        Ok(monitor.filter(|m| !config.is_excluded(m)))
+    }
+
+    /// Like [`MonitorStrategy::resolve`], but may select excluded monitors, so explicit
+    /// moves onto them fail with [`Error::MonitorExcluded`](crate::Error::MonitorExcluded)
+    /// rather than as if the monitor wasn't connected.
+    pub(crate) fn resolve_including_excluded<R: Runtime>(
+        &self,
+        window: &Window<R>,
+    ) -> Result<Option<Monitor>> {
+        self.select(window, |_| true)
+    }
+
+    /// Selects the monitor among those `is_candidate` accepts, except for indices, which
+    /// always follow `available_monitors()` whether or not earlier monitors are candidates.
+    fn select<R: Runtime>(
+        &self,
+        window: &Window<R>,
+        is_candidate: impl Fn(&Monitor) -> bool,
+    ) -> Result<Option<Monitor>> {
+        let monitors = window.available_monitors()?;
+        let monitor = match self {
+            Self::Current => window.current_monitor()?,
+            Self::Primary => window.primary_monitor()?,
+            Self::Index(index) => monitors.into_iter().nth(*index),
+            Self::Name(name) => monitors
+                .into_iter()
+                .find(|m| is_candidate(m) && m.name() == Some(name)),
+            Self::Predicate(predicate) => {
+                let primary = window.primary_monitor()?;
+                monitors.into_iter().find(|m| {
+                    is_candidate(m) && predicate(&MonitorDetails::new(window, m, primary.as_ref()))
+                })
+            }
+        };
+        Ok(monitor)
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Self::Current => "current".into(),
+            Self::Primary => "primary".into(),
+            Self::Index(index) => format!("#{index}"),
+            Self::Name(name) => format!("\"{name}\""),
+            Self::Predicate(_) => "matching the predicate".into(),
+        }
+    }
+}
+
+/// Calls `f` until it yields a value, waiting a little between attempts.
+///
+/// Right after startup the windowing system may not report monitors yet, so this gives
+/// it a brief moment to settle instead of failing on the first try.
+pub(crate) fn when_ready<T>(mut f: impl FnMut() -> Result<Option<T>>) -> Result<Option<T>> {
+    for attempt in 0..READY_ATTEMPTS {
+        if let Some(value) = f()? {
+            return Ok(Some(value));
+        }
+        if attempt + 1 < READY_ATTEMPTS {
+            sleep(READY_INTERVAL);
+        }
+    }
+    Ok(None)
+}
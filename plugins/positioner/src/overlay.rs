@@ -0,0 +1,102 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{plugin_state, rect::set_outer_rect, Rect, Result};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tauri::{Runtime, Window, WindowEvent};
+
+/// A rectangle in logical content coordinates: `(x, y, width, height)`.
+pub(crate) type LogicalRegion = (f64, f64, f64, f64);
+
+type Followed = Arc<Mutex<Option<LogicalRegion>>>;
+
+/// The regions overlays keep covering, keyed by overlay and host label.
+#[derive(Default)]
+pub(crate) struct Overlays(Mutex<HashMap<(String, String), Followed>>);
+
+/// The physical screen rectangle covering `region` of the content area of `host`.
+fn region_rect<R: Runtime>(host: &Window<R>, region: LogicalRegion) -> Result<Rect> {
+    let origin = host.inner_position()?;
+    let scale = host.scale_factor()?;
+    let (x, y, width, height) = region;
+
+    // round the edges rather than the size, so adjacent regions don't leave gaps
+    let left = origin.x + (x * scale).round() as i32;
+    let top = origin.y + (y * scale).round() as i32;
+    let right = origin.x + ((x + width) * scale).round() as i32;
+    let bottom = origin.y + ((y + height) * scale).round() as i32;
+    Ok(Rect::new(left, top, right - left, bottom - top))
+}
+
+pub(crate) fn cover<R: Runtime>(
+    overlay: &Window<R>,
+    host: &Window<R>,
+    region: LogicalRegion,
+) -> Result<()> {
+    set_outer_rect(overlay, region_rect(host, region)?)
+}
+
+pub(crate) fn follow<R: Runtime>(
+    overlay: &Window<R>,
+    host: &Window<R>,
+    region: LogicalRegion,
+) -> Result<()> {
+    let key = (overlay.label().to_string(), host.label().to_string());
+    let overlays = plugin_state::<R, Overlays>(overlay);
+    let mut followed = overlays.0.lock().unwrap();
+
+    if let Some(existing) = followed.get(&key) {
+        existing.lock().unwrap().replace(region);
+    } else {
+        let region: Followed = Arc::new(Mutex::new(Some(region)));
+        followed.insert(key.clone(), region.clone());
+
+        let overlay_ = overlay.clone();
+        let host_ = host.clone();
+        host.on_window_event(move |event| match event {
+            WindowEvent::Moved(_)
+            | WindowEvent::Resized(_)
+            | WindowEvent::ScaleFactorChanged { .. } => {
+                let region = *region.lock().unwrap();
+                if let Some(region) = region {
+                    let _ = cover(&overlay_, &host_, region);
+                }
+            }
+            WindowEvent::Destroyed => forget(&overlay_, &key),
+            _ => {}
+        });
+
+        let overlay_ = overlay.clone();
+        let key = (overlay.label().to_string(), host.label().to_string());
+        overlay.on_window_event(move |event| {
+            if let WindowEvent::Destroyed = event {
+                forget(&overlay_, &key);
+            }
+        });
+    }
+    drop(followed);
+
+    cover(overlay, host, region)
+}
+
+pub(crate) fn unfollow<R: Runtime>(overlay: &Window<R>, host: &Window<R>) {
+    forget(
+        overlay,
+        &(overlay.label().to_string(), host.label().to_string()),
+    );
+}
+
+/// Stops following, which also disarms the listener already attached to the host.
+fn forget<R: Runtime>(overlay: &Window<R>, key: &(String, String)) {
+    if let Some(region) = plugin_state::<R, Overlays>(overlay)
+        .0
+        .lock()
+        .unwrap()
+        .remove(key)
+    {
+        region.lock().unwrap().take();
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::Rect;
+use serde::{Serialize, Serializer};
+use std::{cmp::Ordering, fmt, sync::Arc};
+
+type Comparator = Arc<dyn Fn(&Rect, &Rect) -> Ordering + Send + Sync>;
+
+/// Which placement wins when several candidates fit equally well, e.g. when
+/// [`WindowExt::move_out_of`](crate::WindowExt::move_out_of) can push a window the same
+/// distance in more than one direction.
+///
+/// Set it with [`Builder::with_tie_break`](crate::Builder::with_tie_break). It is used by
+/// every placement heuristic that picks among candidates.
+#[derive(Clone, Default)]
+pub enum TieBreak {
+    /// The candidate that was considered first, following the order documented by each
+    /// method.
+    #[default]
+    PreferAnchorOrder,
+    /// The candidate closest to the top, then to the left.
+    PreferTopLeft,
+    /// The candidate whose center is closest to the center of its monitor.
+    PreferCenter,
+    /// The candidate the comparator orders first.
+    Custom(Comparator),
+}
+
+impl TieBreak {
+    /// Orders two equally good candidates, each paired with the bounds of its monitor, the
+    /// preferred one first.
+    pub(crate) fn compare(
+        &self,
+        (a, a_bounds): (&Rect, &Rect),
+        (b, b_bounds): (&Rect, &Rect),
+    ) -> Ordering {
+        match self {
+            Self::PreferAnchorOrder => Ordering::Equal,
+            Self::PreferTopLeft => (a.top(), a.left()).cmp(&(b.top(), b.left())),
+            Self::PreferCenter => {
+                // compares doubled center coordinates to stay in integers
+                let distance = |r: &Rect, bounds: &Rect| {
+                    let dx =
+                        (r.left() + r.right()) as i64 - (bounds.left() + bounds.right()) as i64;
+                    let dy =
+                        (r.top() + r.bottom()) as i64 - (bounds.top() + bounds.bottom()) as i64;
+                    dx * dx + dy * dy
+                };
+                distance(a, a_bounds).cmp(&distance(b, b_bounds))
+            }
+            Self::Custom(comparator) => comparator(a, b),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::PreferAnchorOrder => "preferAnchorOrder",
+            Self::PreferTopLeft => "preferTopLeft",
+            Self::PreferCenter => "preferCenter",
+            Self::Custom(_) => "custom",
+        }
+    }
+}
+
+impl fmt::Debug for TieBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PreferAnchorOrder => f.write_str("PreferAnchorOrder"),
+            Self::PreferTopLeft => f.write_str("PreferTopLeft"),
+            Self::PreferCenter => f.write_str("PreferCenter"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl Serialize for TieBreak {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.name())
+    }
+}
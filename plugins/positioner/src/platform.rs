@@ -0,0 +1,316 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+//! Monitor details Tauri doesn't expose, queried from the windowing system directly.
+
+use crate::Rect;
+use tauri::{Monitor, Runtime, Window};
+
+/// The GDK monitor at the given physical position.
+#[cfg(target_os = "linux")]
+fn gdk_monitor(position: tauri::PhysicalPosition<i32>) -> Option<gdk::Monitor> {
+    let display = gdk::Display::default()?;
+    let found = (0..display.n_monitors())
+        .filter_map(|i| display.monitor(i))
+        .find(|m| {
+            let geometry = m.geometry();
+            geometry.x() * m.scale_factor() == position.x
+                && geometry.y() * m.scale_factor() == position.y
+        });
+    found
+}
+
+/// Returns the refresh rate of the monitor in Hz, if the platform reports it.
+#[cfg(target_os = "linux")]
+pub(crate) fn refresh_rate<R: Runtime>(window: &Window<R>, monitor: &Monitor) -> Option<u32> {
+    let position = *monitor.position();
+    let (tx, rx) = std::sync::mpsc::channel();
+    // GDK may only be used from the main thread
+    window
+        .run_on_main_thread(move || {
+            let _ = tx.send(gdk_monitor(position).map(|m| m.refresh_rate()));
+        })
+        .ok()?;
+
+    // GDK reports the refresh rate in millihertz, or 0 if it is unknown
+    rx.recv()
+        .ok()
+        .flatten()
+        .filter(|rate| *rate > 0)
+        .map(|rate| (rate as u32 + 500) / 1000)
+}
+
+/// Returns the refresh rate of the monitor in Hz, if the platform reports it.
+#[cfg(target_os = "windows")]
+pub(crate) fn refresh_rate<R: Runtime>(_window: &Window<R>, monitor: &Monitor) -> Option<u32> {
+    use windows_sys::Win32::Graphics::Gdi::{
+        EnumDisplaySettingsW, DEVMODEW, ENUM_CURRENT_SETTINGS,
+    };
+
+    // on Windows the monitor name is its device name, e.g. `\\.\DISPLAY1`
+    let device: Vec<u16> = monitor.name()?.encode_utf16().chain(Some(0)).collect();
+    let mut mode: DEVMODEW = unsafe { std::mem::zeroed() };
+    mode.dmSize = std::mem::size_of::<DEVMODEW>() as u16;
+
+    let found = unsafe { EnumDisplaySettingsW(device.as_ptr(), ENUM_CURRENT_SETTINGS, &mut mode) };
+    // 0 and 1 stand for the hardware's default refresh rate
+    (found != 0 && mode.dmDisplayFrequency > 1).then(|| mode.dmDisplayFrequency)
+}
+
+/// Returns the refresh rate of the monitor in Hz, if the platform reports it.
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub(crate) fn refresh_rate<R: Runtime>(_window: &Window<R>, _monitor: &Monitor) -> Option<u32> {
+    None
+}
+
+/// Returns the part of the monitor that isn't reserved for taskbars, docks or panels, if
+/// the platform reports it.
+#[cfg(target_os = "linux")]
+pub(crate) fn work_area<R: Runtime>(window: &Window<R>, monitor: &Monitor) -> Option<Rect> {
+    let position = *monitor.position();
+    let (tx, rx) = std::sync::mpsc::channel();
+    // GDK may only be used from the main thread, it reads `_NET_WORKAREA` on X11
+    window
+        .run_on_main_thread(move || {
+            let area = gdk_monitor(position).map(|m| {
+                let area = m.workarea();
+                let scale = m.scale_factor();
+                Rect::new(
+                    area.x() * scale,
+                    area.y() * scale,
+                    area.width() * scale,
+                    area.height() * scale,
+                )
+            });
+            let _ = tx.send(area);
+        })
+        .ok()?;
+
+    rx.recv().ok().flatten()
+}
+
+/// Returns the part of the monitor that isn't reserved for taskbars, docks or panels, if
+/// the platform reports it.
+#[cfg(target_os = "windows")]
+pub(crate) fn work_area<R: Runtime>(_window: &Window<R>, monitor: &Monitor) -> Option<Rect> {
+    use windows_sys::Win32::{
+        Foundation::POINT,
+        Graphics::Gdi::{GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITOR_DEFAULTTONULL},
+    };
+
+    let position = monitor.position();
+    let point = POINT {
+        x: position.x,
+        y: position.y,
+    };
+    let mut info: MONITORINFO = unsafe { std::mem::zeroed() };
+    info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+
+    unsafe {
+        let handle = MonitorFromPoint(point, MONITOR_DEFAULTTONULL);
+        if handle == 0 || GetMonitorInfoW(handle, &mut info) == 0 {
+            return None;
+        }
+    }
+    let work = info.rcWork;
+    Some(Rect::new(
+        work.left,
+        work.top,
+        work.right - work.left,
+        work.bottom - work.top,
+    ))
+}
+
+/// Returns the part of the monitor that isn't reserved for taskbars, docks or panels, if
+/// the platform reports it.
+#[cfg(target_os = "macos")]
+pub(crate) fn work_area<R: Runtime>(window: &Window<R>, monitor: &Monitor) -> Option<Rect> {
+    use cocoa::{
+        appkit::NSScreen,
+        base::nil,
+        foundation::{NSArray, NSRect},
+    };
+
+    let position = *monitor.position();
+    let scale = monitor.scale_factor();
+    let (tx, rx) = std::sync::mpsc::channel();
+    // AppKit may only be used from the main thread
+    window
+        .run_on_main_thread(move || {
+            let area = unsafe {
+                let screens = NSScreen::screens(nil);
+                // AppKit's origin is the bottom-left corner of the primary screen
+                let primary_height = NSScreen::frame(screens.objectAtIndex(0)).size.height;
+                let to_physical = |rect: NSRect| {
+                    let top = primary_height - (rect.origin.y + rect.size.height);
+                    Rect::new(
+                        (rect.origin.x * scale).round() as i32,
+                        (top * scale).round() as i32,
+                        (rect.size.width * scale).round() as i32,
+                        (rect.size.height * scale).round() as i32,
+                    )
+                };
+                (0..screens.count())
+                    .map(|i| screens.objectAtIndex(i))
+                    .find(|screen| to_physical(NSScreen::frame(*screen)).position == position)
+                    .map(|screen| to_physical(NSScreen::visibleFrame(screen)))
+            };
+            let _ = tx.send(area);
+        })
+        .ok()?;
+
+    rx.recv().ok().flatten()
+}
+
+/// Returns the part of the monitor that isn't reserved for taskbars, docks or panels, if
+/// the platform reports it.
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+pub(crate) fn work_area<R: Runtime>(_window: &Window<R>, _monitor: &Monitor) -> Option<Rect> {
+    None
+}
+
+/// Returns the position of the mouse cursor in physical screen coordinates, if the platform
+/// reports it.
+#[cfg(target_os = "linux")]
+pub(crate) fn cursor_position<R: Runtime>(
+    window: &Window<R>,
+) -> Option<tauri::PhysicalPosition<i32>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    // GDK may only be used from the main thread
+    window
+        .run_on_main_thread(move || {
+            let position = gdk::Display::default().and_then(|display| {
+                let (_, x, y) = display.default_seat()?.pointer()?.position();
+                let scale = display.monitor_at_point(x, y)?.scale_factor();
+                Some(tauri::PhysicalPosition {
+                    x: x * scale,
+                    y: y * scale,
+                })
+            });
+            let _ = tx.send(position);
+        })
+        .ok()?;
+
+    rx.recv().ok().flatten()
+}
+
+/// Returns the position of the mouse cursor in physical screen coordinates, if the platform
+/// reports it.
+#[cfg(target_os = "windows")]
+pub(crate) fn cursor_position<R: Runtime>(
+    _window: &Window<R>,
+) -> Option<tauri::PhysicalPosition<i32>> {
+    use windows_sys::Win32::{Foundation::POINT, UI::WindowsAndMessaging::GetCursorPos};
+
+    let mut point = POINT { x: 0, y: 0 };
+    (unsafe { GetCursorPos(&mut point) } != 0).then(|| tauri::PhysicalPosition {
+        x: point.x,
+        y: point.y,
+    })
+}
+
+/// Returns the position of the mouse cursor in physical screen coordinates, if the platform
+/// reports it.
+#[cfg(target_os = "macos")]
+pub(crate) fn cursor_position<R: Runtime>(
+    window: &Window<R>,
+) -> Option<tauri::PhysicalPosition<i32>> {
+    use cocoa::{
+        appkit::{NSEvent, NSScreen},
+        base::nil,
+        foundation::NSArray,
+    };
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    // AppKit may only be used from the main thread
+    window
+        .run_on_main_thread(move || {
+            let position = unsafe {
+                let point = NSEvent::mouseLocation(nil);
+                let screens = NSScreen::screens(nil);
+                // AppKit's origin is the bottom-left corner of the primary screen
+                let primary_height = NSScreen::frame(screens.objectAtIndex(0)).size.height;
+                (0..screens.count())
+                    .map(|i| screens.objectAtIndex(i))
+                    .find(|screen| {
+                        let frame = NSScreen::frame(*screen);
+                        frame.origin.x <= point.x
+                            && point.x <= frame.origin.x + frame.size.width
+                            && frame.origin.y <= point.y
+                            && point.y <= frame.origin.y + frame.size.height
+                    })
+                    .map(|screen| {
+                        let scale = NSScreen::backingScaleFactor(screen);
+                        tauri::PhysicalPosition {
+                            x: (point.x * scale).round() as i32,
+                            y: ((primary_height - point.y) * scale).round() as i32,
+                        }
+                    })
+            };
+            let _ = tx.send(position);
+        })
+        .ok()?;
+
+    rx.recv().ok().flatten()
+}
+
+/// Returns the position of the mouse cursor in physical screen coordinates, if the platform
+/// reports it.
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+pub(crate) fn cursor_position<R: Runtime>(
+    _window: &Window<R>,
+) -> Option<tauri::PhysicalPosition<i32>> {
+    None
+}
+
+/// Sets the opacity of the whole window, from `0.0` to `1.0`.
+///
+/// Returns `false` if the platform doesn't support it.
+#[cfg(target_os = "linux")]
+pub(crate) fn set_opacity<R: Runtime>(window: &Window<R>, opacity: f64) -> bool {
+    use gtk::prelude::WidgetExt;
+
+    let window_ = window.clone();
+    // GTK may only be used from the main thread
+    window
+        .run_on_main_thread(move || {
+            if let Ok(gtk_window) = window_.gtk_window() {
+                gtk_window.set_opacity(opacity);
+            }
+        })
+        .is_ok()
+}
+
+/// Sets the opacity of the whole window, from `0.0` to `1.0`.
+///
+/// Returns `false` if the platform doesn't support it.
+#[cfg(target_os = "windows")]
+pub(crate) fn set_opacity<R: Runtime>(window: &Window<R>, opacity: f64) -> bool {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongW, SetLayeredWindowAttributes, SetWindowLongW, GWL_EXSTYLE, LWA_ALPHA,
+        WS_EX_LAYERED,
+    };
+
+    let hwnd = match window.hwnd() {
+        Ok(hwnd) => hwnd.0,
+        Err(_) => return false,
+    };
+    let alpha = (opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    unsafe {
+        // only layered windows can be translucent
+        let style = GetWindowLongW(hwnd, GWL_EXSTYLE);
+        if style & WS_EX_LAYERED as i32 == 0 {
+            SetWindowLongW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED as i32);
+        }
+        SetLayeredWindowAttributes(hwnd, 0, alpha, LWA_ALPHA) != 0
+    }
+}
+
+/// Sets the opacity of the whole window, from `0.0` to `1.0`.
+///
+/// Returns `false` if the platform doesn't support it.
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+pub(crate) fn set_opacity<R: Runtime>(_window: &Window<R>, _opacity: f64) -> bool {
+    false
+}
@@ -0,0 +1,106 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{platform, plugin_state, Position, Result, WindowExt};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    thread::{sleep, spawn},
+    time::Duration,
+};
+use tauri::{Runtime, Window};
+
+/// The fade [`dismiss`] uses for windows that weren't presented with [`present_centered`].
+const DEFAULT_FADE: (u32, Duration) = (10, Duration::from_millis(15));
+
+#[derive(Clone, Copy)]
+struct Presentation {
+    /// Bumped by every fade, which makes the previous one stop.
+    generation: u64,
+    steps: u32,
+    interval: Duration,
+}
+
+/// The fade of every presented window, keyed by label.
+#[derive(Default)]
+pub(crate) struct Presentations(Mutex<HashMap<String, Presentation>>);
+
+impl Presentations {
+    fn next(&self, label: &str, fade: Option<(u32, Duration)>) -> Presentation {
+        let mut presentations = self.0.lock().unwrap();
+        let presentation = presentations
+            .entry(label.to_string())
+            .or_insert(Presentation {
+                generation: 0,
+                steps: DEFAULT_FADE.0,
+                interval: DEFAULT_FADE.1,
+            });
+        presentation.generation += 1;
+        if let Some((steps, interval)) = fade {
+            presentation.steps = steps;
+            presentation.interval = interval;
+        }
+        *presentation
+    }
+
+    fn is_current(&self, label: &str, generation: u64) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .get(label)
+            .map_or(false, |p| p.generation == generation)
+    }
+}
+
+pub(crate) fn present_centered<R: Runtime>(
+    window: &Window<R>,
+    steps: u32,
+    interval: Duration,
+) -> Result<()> {
+    let presentation =
+        plugin_state::<R, Presentations>(window).next(window.label(), Some((steps, interval)));
+    let fades = steps > 0 && platform::set_opacity(window, 0.0);
+
+    window.move_window(Position::Center)?;
+    window.show()?;
+    if fades {
+        fade(window, presentation, true);
+    }
+    Ok(())
+}
+
+pub(crate) fn dismiss<R: Runtime>(window: &Window<R>) -> Result<()> {
+    let presentation = plugin_state::<R, Presentations>(window).next(window.label(), None);
+    if presentation.steps == 0 || !platform::set_opacity(window, 1.0) {
+        window.hide()?;
+        return Ok(());
+    }
+
+    fade(window, presentation, false);
+    Ok(())
+}
+
+/// Ramps the opacity on a background thread, hiding the window at the end of a fade-out.
+fn fade<R: Runtime>(window: &Window<R>, presentation: Presentation, fade_in: bool) {
+    let window = window.clone();
+    spawn(move || {
+        for step in 1..=presentation.steps {
+            sleep(presentation.interval);
+            if !plugin_state::<R, Presentations>(&window)
+                .is_current(window.label(), presentation.generation)
+            {
+                return;
+            }
+
+            let progress = step as f64 / presentation.steps as f64;
+            let opacity = if fade_in { progress } else { 1.0 - progress };
+            platform::set_opacity(&window, opacity);
+        }
+
+        if !fade_in {
+            let _ = window.hide();
+            // leave the window opaque for whoever shows it next
+            platform::set_opacity(&window, 1.0);
+        }
+    });
+}
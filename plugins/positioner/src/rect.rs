@@ -0,0 +1,191 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use tauri::{Monitor, PhysicalPosition, PhysicalSize, Runtime, Window};
+
+/// A rectangle in physical pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rect {
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<i32>,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            position: PhysicalPosition { x, y },
+            size: PhysicalSize { width, height },
+        }
+    }
+
+    /// The full bounds of the given [`Monitor`].
+    pub fn of_monitor(monitor: &Monitor) -> Self {
+        Self {
+            position: *monitor.position(),
+            size: PhysicalSize {
+                width: monitor.size().width as i32,
+                height: monitor.size().height as i32,
+            },
+        }
+    }
+
+    /// The outer bounds of the given [`Window`].
+    pub fn of_window<R: Runtime>(window: &Window<R>) -> Result<Self> {
+        let position = window.outer_position()?;
+        let size = window.outer_size()?;
+        Ok(Self {
+            position,
+            size: PhysicalSize {
+                width: size.width as i32,
+                height: size.height as i32,
+            },
+        })
+    }
+
+    pub fn left(&self) -> i32 {
+        self.position.x
+    }
+
+    pub fn top(&self) -> i32 {
+        self.position.y
+    }
+
+    pub fn right(&self) -> i32 {
+        self.position.x + self.size.width
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.position.y + self.size.height
+    }
+
+    pub fn area(&self) -> i64 {
+        self.size.width.max(0) as i64 * self.size.height.max(0) as i64
+    }
+
+    /// Whether the point lies inside the rectangle. The right and bottom edges are exclusive.
+    pub fn contains(&self, point: PhysicalPosition<i32>) -> bool {
+        self.left() <= point.x
+            && point.x < self.right()
+            && self.top() <= point.y
+            && point.y < self.bottom()
+    }
+
+    /// The overlapping part of both rectangles, if they overlap at all.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        (left < right && top < bottom).then(|| Rect::new(left, top, right - left, bottom - top))
+    }
+
+    /// The smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &Rect) -> Rect {
+        let left = self.left().min(other.left());
+        let top = self.top().min(other.top());
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Rect::new(left, top, right - left, bottom - top)
+    }
+
+    /// Whether `other` lies completely inside this rectangle.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        self.left() <= other.left()
+            && other.right() <= self.right()
+            && self.top() <= other.top()
+            && other.bottom() <= self.bottom()
+    }
+
+    /// Shifts the rectangle, without resizing it, so that it lies inside `bounds`.
+    ///
+    /// If it is larger than `bounds` along an axis, it is aligned with the top or left edge
+    /// of `bounds` on that axis.
+    pub fn clamp_within(&self, bounds: &Rect) -> Rect {
+        let x = self
+            .left()
+            .min(bounds.right() - self.size.width)
+            .max(bounds.left());
+        let y = self
+            .top()
+            .min(bounds.bottom() - self.size.height)
+            .max(bounds.top());
+        Rect::new(x, y, self.size.width, self.size.height)
+    }
+
+    /// Shifts the rectangle, without resizing it, the least amount that keeps a strip of at
+    /// least `min_visible` along its top edge inside `bounds`, like a reachable title bar.
+    ///
+    /// `min_visible` is capped at the size of both rectangles.
+    pub fn keep_top_visible_within(&self, bounds: &Rect, min_visible: PhysicalSize<i32>) -> Rect {
+        let visible_width = min_visible
+            .width
+            .min(self.size.width)
+            .min(bounds.size.width)
+            .max(0);
+        let visible_height = min_visible
+            .height
+            .min(self.size.height)
+            .min(bounds.size.height)
+            .max(0);
+
+        let x = self
+            .left()
+            .min(bounds.right() - visible_width)
+            .max(bounds.left() + visible_width - self.size.width);
+        let y = self
+            .top()
+            .min(bounds.bottom() - visible_height)
+            .max(bounds.top());
+        Rect::new(x, y, self.size.width, self.size.height)
+    }
+
+    /// The same rectangle moved by the given amount.
+    pub fn translate(&self, dx: i32, dy: i32) -> Rect {
+        Rect::new(
+            self.position.x + dx,
+            self.position.y + dy,
+            self.size.width,
+            self.size.height,
+        )
+    }
+}
+
+/// Distances from the edges of a rectangle, in physical pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Margins {
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub left: i32,
+}
+
+impl Margins {
+    pub fn new(top: i32, right: i32, bottom: i32, left: i32) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+}
+
+/// Moves and resizes the window so that its outer bounds match `rect`.
+///
+/// Tauri sizes windows by their inner size, so the current decoration size is subtracted.
+pub(crate) fn set_outer_rect<R: Runtime>(window: &Window<R>, rect: Rect) -> Result<()> {
+    let outer = window.outer_size()?;
+    let inner = window.inner_size()?;
+    let frame_width = outer.width.saturating_sub(inner.width) as i32;
+    let frame_height = outer.height.saturating_sub(inner.height) as i32;
+
+    window.set_position(tauri::Position::Physical(rect.position))?;
+    window.set_size(tauri::Size::Physical(PhysicalSize {
+        width: (rect.size.width - frame_width).max(1) as u32,
+        height: (rect.size.height - frame_height).max(1) as u32,
+    }))?;
+    Ok(())
+}
@@ -0,0 +1,59 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{platform, Rect};
+use tauri::{Monitor, PhysicalPosition, PhysicalSize, Runtime, Window};
+
+/// Splits the work area of `monitor` into a grid of `rows` by `cols` evenly sized cells,
+/// `gap` pixels apart, without moving any window, e.g. to preview a tiling or to lay out
+/// views inside a window.
+///
+/// Cells are returned in row-major order: left to right, then top to bottom. The gap is only
+/// left between cells, not along the edges, and pixels that don't divide evenly are spread
+/// across the cells so the grid fills the work area exactly. `window` is only used to query
+/// the work area, which falls back to the full monitor bounds where it can't be determined,
+/// see [`WindowExt::move_window_in_work_area`](crate::WindowExt::move_window_in_work_area).
+/// Returns an empty list if `rows` or `cols` is zero.
+pub fn tile_rects<R: Runtime>(
+    window: &Window<R>,
+    monitor: &Monitor,
+    rows: usize,
+    cols: usize,
+    gap: i32,
+) -> Vec<(PhysicalPosition<i32>, PhysicalSize<i32>)> {
+    let bounds = platform::work_area(window, monitor).unwrap_or_else(|| Rect::of_monitor(monitor));
+    grid(bounds, rows, cols, gap)
+        .into_iter()
+        .map(|cell| (cell.position, cell.size))
+        .collect()
+}
+
+pub(crate) fn grid(bounds: Rect, rows: usize, cols: usize, gap: i32) -> Vec<Rect> {
+    if rows == 0 || cols == 0 {
+        return Vec::new();
+    }
+
+    let columns = split(bounds.left(), bounds.size.width, cols, gap);
+    let rows = split(bounds.top(), bounds.size.height, rows, gap);
+    rows.iter()
+        .flat_map(|&(top, height)| {
+            columns
+                .iter()
+                .map(move |&(left, width)| Rect::new(left, top, width, height))
+        })
+        .collect()
+}
+
+/// Splits a span into `count` segments `gap` apart, returning their start and length.
+fn split(start: i32, length: i32, count: usize, gap: i32) -> Vec<(i32, i32)> {
+    let count = count as i64;
+    let available = (length as i64 - gap as i64 * (count - 1)).max(0);
+    let edge = |i: i64| (available * i / count) as i32;
+
+    (0..count)
+        .map(|i| {
+            let offset = start + edge(i) + gap * i as i32;
+            (offset, edge(i + 1) - edge(i))
+        })
+        .collect()
+}
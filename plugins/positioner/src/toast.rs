@@ -0,0 +1,96 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{ext::WindowExtInternal, AnimationConfig, Error, Position, Result};
+use tauri::{PhysicalPosition, Runtime, Window};
+
+/// A stack of toast windows growing away from a corner of a monitor.
+///
+/// The first toast sits at the anchor and every following one is stacked `gap` pixels
+/// further away from it: upwards for the bottom positions and downwards for all others.
+/// Toasts are laid out on the monitor the first toast is on.
+pub struct ToastStack<R: Runtime> {
+    anchor: Position,
+    gap: i32,
+    animation: Option<AnimationConfig>,
+    toasts: Vec<Window<R>>,
+}
+
+impl<R: Runtime> ToastStack<R> {
+    pub fn new(anchor: Position, gap: i32) -> Self {
+        Self {
+            anchor,
+            gap,
+            animation: None,
+            toasts: Vec::new(),
+        }
+    }
+
+    /// Animates the toasts to their new positions when the stack is reflowed instead of
+    /// moving them instantly.
+    pub fn with_animation(mut self, config: AnimationConfig) -> Self {
+        self.animation = Some(config);
+        self
+    }
+
+    /// The position the stack grows from.
+    pub fn anchor(&self) -> Position {
+        self.anchor
+    }
+
+    /// Adds a toast at the end of the stack and moves it into place.
+    pub fn push(&mut self, toast: Window<R>) -> Result<()> {
+        self.toasts.push(toast);
+        self.reflow()
+    }
+
+    /// Removes the toast with the given label, if it is in the stack, and closes the gap it
+    /// leaves.
+    pub fn remove(&mut self, label: &str) -> Result<()> {
+        self.toasts.retain(|toast| toast.label() != label);
+        self.reflow()
+    }
+
+    /// Moves the stack to another corner, e.g. after the user changed their preferred
+    /// notification corner.
+    ///
+    /// All toasts are moved to the new anchor, keeping their order, and the stacking
+    /// direction follows the new anchor.
+    pub fn set_stack_anchor(&mut self, pos: Position) -> Result<()> {
+        self.anchor = pos;
+        self.reflow()
+    }
+
+    /// Moves every toast to its place in the stack.
+    pub fn reflow(&self) -> Result<()> {
+        let monitor = match self.toasts.first() {
+            Some(first) => first.current_monitor()?.ok_or(Error::NoMonitor)?,
+            None => return Ok(()),
+        };
+        let grows_up = matches!(
+            self.anchor,
+            Position::BottomLeft | Position::BottomCenter | Position::BottomRight
+        );
+
+        let mut offset = 0;
+        for toast in &self.toasts {
+            let base = toast.position_on(self.anchor, &monitor)?;
+            let height = toast.outer_size()?.height as i32;
+            let target = PhysicalPosition {
+                x: base.x,
+                y: if grows_up {
+                    base.y - offset
+                } else {
+                    base.y + offset
+                },
+            };
+            offset += height + self.gap;
+
+            match self.animation {
+                Some(config) => toast.place_animated(target, &monitor, config, || ())?,
+                None => toast.place(target, &monitor)?,
+            }
+        }
+        Ok(())
+    }
+}
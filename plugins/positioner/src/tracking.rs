@@ -0,0 +1,162 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{ext::WindowExtInternal, plugin_state, Config, Error, Position, Result};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    thread::{sleep, spawn},
+    time::{Duration, Instant},
+};
+use tauri::{LogicalSize, Runtime, Window, WindowEvent};
+
+/// How long window events have to settle before tracked windows are repositioned.
+pub(crate) const DEBOUNCE: Duration = Duration::from_millis(100);
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Coalesces bursts of calls into a single one that runs once the calls stop.
+#[derive(Clone, Default)]
+pub(crate) struct Debounce(Arc<Mutex<Option<(Instant, Job)>>>);
+
+impl Debounce {
+    /// Schedules `f` to run after `delay`.
+    ///
+    /// A call made while another one is still pending pushes the deadline back and
+    /// replaces the pending callback, so only the latest one runs.
+    pub(crate) fn call(&self, delay: Duration, f: impl FnOnce() + Send + 'static) {
+        let mut pending = self.0.lock().unwrap();
+        let running = pending.is_some();
+        pending.replace((Instant::now() + delay, Box::new(f)));
+        if running {
+            return;
+        }
+
+        let pending = self.0.clone();
+        spawn(move || loop {
+            let mut guard = pending.lock().unwrap();
+            match guard.as_ref() {
+                None => return,
+                Some((deadline, _)) => {
+                    let now = Instant::now();
+                    if *deadline > now {
+                        let wait = *deadline - now;
+                        drop(guard);
+                        sleep(wait);
+                        continue;
+                    }
+                }
+            }
+            let job = guard.take().map(|(_, job)| job);
+            drop(guard);
+            if let Some(job) = job {
+                job();
+            }
+            return;
+        });
+    }
+}
+
+/// The minimum size, in logical pixels, a window must have for a breakpoint to apply.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeThreshold {
+    pub min_width: f64,
+    pub min_height: f64,
+}
+
+impl SizeThreshold {
+    pub fn new(min_width: f64, min_height: f64) -> Self {
+        Self {
+            min_width,
+            min_height,
+        }
+    }
+
+    /// Whether a window of the given size satisfies this threshold.
+    pub fn matches(&self, size: LogicalSize<f64>) -> bool {
+        size.width >= self.min_width && size.height >= self.min_height
+    }
+}
+
+type Breakpoints = Arc<Mutex<Vec<(SizeThreshold, Position)>>>;
+
+/// The responsive breakpoints of every tracked window, keyed by label.
+#[derive(Default)]
+pub(crate) struct Responsive(Mutex<HashMap<String, Breakpoints>>);
+
+impl Responsive {
+    /// The breakpoints of every tracked window, keyed by label.
+    pub(crate) fn breakpoints(&self) -> BTreeMap<String, Vec<(SizeThreshold, Position)>> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(label, breakpoints)| (label.clone(), breakpoints.lock().unwrap().clone()))
+            .collect()
+    }
+}
+
+pub(crate) fn track_responsive<R: Runtime>(
+    window: &Window<R>,
+    breakpoints: Vec<(SizeThreshold, Position)>,
+) -> Result<()> {
+    let responsive = plugin_state::<R, Responsive>(window);
+    let mut tracked = responsive.0.lock().unwrap();
+
+    if let Some(existing) = tracked.get(window.label()) {
+        *existing.lock().unwrap() = breakpoints;
+    } else {
+        let breakpoints: Breakpoints = Arc::new(Mutex::new(breakpoints));
+        tracked.insert(window.label().to_string(), breakpoints.clone());
+
+        let window_ = window.clone();
+        let debounce = Debounce::default();
+        window.on_window_event(move |event| match event {
+            WindowEvent::Resized(_) | WindowEvent::ScaleFactorChanged { .. } => {
+                let window = window_.clone();
+                let breakpoints = breakpoints.clone();
+                debounce.call(DEBOUNCE, move || {
+                    let _ = apply_breakpoints(&window, &breakpoints);
+                });
+            }
+            WindowEvent::Destroyed => {
+                plugin_state::<R, Responsive>(&window_)
+                    .0
+                    .lock()
+                    .unwrap()
+                    .remove(window_.label());
+            }
+            _ => {}
+        });
+    }
+    let breakpoints = tracked[window.label()].clone();
+    drop(tracked);
+
+    apply_breakpoints(window, &breakpoints)
+}
+
+fn apply_breakpoints<R: Runtime>(window: &Window<R>, breakpoints: &Breakpoints) -> Result<()> {
+    let size = window
+        .inner_size()?
+        .to_logical::<f64>(window.scale_factor()?);
+    let position = breakpoints
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(threshold, _)| threshold.matches(size))
+        .map(|(_, position)| *position);
+
+    let position = match position {
+        Some(position) => position,
+        None => return Ok(()),
+    };
+
+    let monitor = window.current_monitor()?.ok_or(Error::NoMonitor)?;
+    let target = window.position_on(position, &monitor)?;
+    match plugin_state::<R, Config>(window).animated_tracking {
+        Some(config) => window.place_animated(target, &monitor, config, || ()),
+        None => window.place(target, &monitor),
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright 2021 Jonas Kruckenberg
+// SPDX-License-Identifier: MIT
+
+use crate::{plugin_state, rect::set_outer_rect, Error, Rect, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Mutex};
+use tauri::{Manager, Monitor, PhysicalPosition, Runtime, Window};
+
+/// A named area of a monitor that windows can be snapped into.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Zone {
+    pub name: String,
+    /// The area of the zone, relative to the top-left corner of its monitor.
+    pub rect: Rect,
+}
+
+/// The registered zones, keyed by monitor name.
+#[derive(Default)]
+pub(crate) struct Zones(Mutex<HashMap<String, Vec<Zone>>>);
+
+/// Registers the zones of the monitor with the given name, replacing the previous ones.
+///
+/// Zones are looked up by monitor name rather than by position, so they keep working when
+/// monitors are rearranged.
+pub fn register_zones<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    monitor_name: impl Into<String>,
+    zones: Vec<Zone>,
+) {
+    plugin_state::<R, Zones>(manager)
+        .0
+        .lock()
+        .unwrap()
+        .insert(monitor_name.into(), zones);
+}
+
+/// Returns the zones registered for the given monitor, in absolute screen coordinates.
+pub(crate) fn monitor_zones<R: Runtime>(
+    window: &Window<R>,
+    monitor: &Monitor,
+) -> Result<Vec<Zone>> {
+    let name = monitor.name().ok_or(Error::UnnamedMonitor)?;
+    let origin = monitor.position();
+    let zones = plugin_state::<R, Zones>(window);
+    let zones = zones.0.lock().unwrap();
+    let zones = zones
+        .get(name)
+        .ok_or_else(|| Error::NoZones(name.clone()))?;
+
+    Ok(zones
+        .iter()
+        .map(|zone| Zone {
+            name: zone.name.clone(),
+            rect: zone.rect.translate(origin.x, origin.y),
+        })
+        .collect())
+}
+
+/// Returns the zone containing `point`, on the monitor containing `point`, or `None` if
+/// that monitor has no zones.
+pub(crate) fn zone_at<R: Runtime>(
+    window: &Window<R>,
+    point: PhysicalPosition<i32>,
+) -> Result<Option<Zone>> {
+    let monitor = match window
+        .available_monitors()?
+        .into_iter()
+        .find(|m| Rect::of_monitor(m).contains(point))
+    {
+        Some(monitor) => monitor,
+        None => return Ok(None),
+    };
+
+    // monitors without zones are crossed while dragging between monitors, they just don't
+    // offer any zone to drop into
+    let zones = match monitor_zones(window, &monitor) {
+        Ok(zones) => zones,
+        Err(Error::NoZones(_) | Error::UnnamedMonitor) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    Ok(zones.into_iter().find(|zone| zone.rect.contains(point)))
+}
+
+pub(crate) fn move_to_zone<R: Runtime>(window: &Window<R>, zone_name: &str) -> Result<()> {
+    let monitor = window.current_monitor()?.ok_or(Error::NoMonitor)?;
+    let zone = monitor_zones(window, &monitor)?
+        .into_iter()
+        .find(|zone| zone.name == zone_name)
+        .ok_or_else(|| Error::ZoneNotFound(zone_name.to_string()))?;
+
+    set_outer_rect(window, zone.rect)
+}
@@ -14,6 +14,169 @@ export interface QueryResult {
   lastInsertId: number;
 }
 
+/**
+ * **Transaction**
+ *
+ * A transaction begun with `Database.begin`. Its queries are only applied once it's committed.
+ *
+ * The transaction is rolled back if the window that began it is closed or if it isn't committed
+ * before the timeout configured in the Rust plugin builder (60 seconds by default).
+ */
+export class Transaction {
+  id: number;
+  constructor(id: number) {
+    this.id = id;
+  }
+
+  /**
+   * **execute**
+   *
+   * Passes a SQL expression to the database for execution in the transaction, like `Database.execute`.
+   */
+  async execute(query: string, bindValues?: unknown[]): Promise<QueryResult> {
+    const [rowsAffected, lastInsertId] = await invoke<[number, number]>(
+      "plugin:sql|execute_in",
+      {
+        txId: this.id,
+        query,
+        values: bindValues ?? [],
+      },
+    );
+    return {
+      lastInsertId,
+      rowsAffected,
+    };
+  }
+
+  /**
+   * **select**
+   *
+   * Passes in a SELECT query to the database for execution in the transaction, like `Database.select`.
+   */
+  async select<T>(query: string, bindValues?: unknown[]): Promise<T> {
+    return await invoke<T>("plugin:sql|select_in", {
+      txId: this.id,
+      query,
+      values: bindValues ?? [],
+    });
+  }
+
+  /**
+   * **commit**
+   *
+   * Commits the transaction.
+   */
+  async commit(): Promise<void> {
+    await invoke("plugin:sql|commit", { txId: this.id });
+  }
+
+  /**
+   * **rollback**
+   *
+   * Rolls the transaction back, discarding its changes.
+   */
+  async rollback(): Promise<void> {
+    await invoke("plugin:sql|rollback", { txId: this.id });
+  }
+}
+
+/**
+ * Options for the connection pool of a database. Options that aren't set use the
+ * ones set in the plugin config or by the Rust builder, or the defaults.
+ */
+export interface PoolOptions {
+  /** The maximum number of connections. */
+  maxConnections?: number;
+  /** The number of connections to keep open. */
+  minConnections?: number;
+  /** How long to wait for a connection before failing, in milliseconds. */
+  acquireTimeout?: number;
+  /** How long a connection may stay idle before it's closed, in milliseconds. */
+  idleTimeout?: number;
+  /** How long a connection may be kept open before it's closed, in milliseconds. */
+  maxLifetime?: number;
+  /** The journal mode of a SQLite database, like `wal`. */
+  journalMode?: "delete" | "truncate" | "persist" | "memory" | "wal" | "off";
+  /** How long to wait for a lock on a SQLite database before failing, in milliseconds. */
+  busyTimeout?: number;
+}
+
+/**
+ * The state of a migration, returned by `Database.migrationStatus`.
+ */
+export interface MigrationStatus {
+  version: number;
+  description: string;
+  applied: boolean;
+  /** When the migration was applied, as an RFC 3339 timestamp. */
+  appliedAt: string | null;
+  /** Whether the migration can be reverted. */
+  reversible: boolean;
+}
+
+export interface SelectOptions {
+  /** Whether to return the columns of the result along with its rows. */
+  columns?: boolean;
+}
+
+/**
+ * A column of the result of a query.
+ */
+export interface ColumnInfo {
+  name: string;
+  /** The name of the type of the column in the database, like `VARCHAR` or `INT8`. */
+  type_name: string;
+  /** Whether the column may be null, or `null` if the database can't tell. */
+  nullable: boolean | null;
+}
+
+/**
+ * The result of `Database.select` with `columns: true`.
+ */
+export interface SelectResult<T> {
+  rows: T;
+  columns: ColumnInfo[];
+}
+
+/**
+ * A chunk of the rows of a cursor.
+ */
+export interface Chunk<T> {
+  rows: T[];
+  /** Whether these are the last rows, after which the cursor is closed. */
+  done: boolean;
+}
+
+/**
+ * A cursor opened with `Database.selectCursor`, which fetches the rows of a query in chunks.
+ */
+export class Cursor<T> {
+  id: number;
+  constructor(id: number) {
+    this.id = id;
+  }
+
+  /**
+   * **fetch**
+   *
+   * Fetches the next chunk of rows. The cursor is closed once `done` is true.
+   */
+  async fetch(): Promise<Chunk<T>> {
+    return await invoke<Chunk<T>>("plugin:sql|fetch", { cursorId: this.id });
+  }
+
+  /**
+   * **close**
+   *
+   * Closes the cursor before all of its rows were fetched.
+   */
+  async close(): Promise<boolean> {
+    return await invoke<boolean>("plugin:sql|close_cursor", {
+      cursorId: this.id,
+    });
+  }
+}
+
 /**
  * **Database**
  *
@@ -39,11 +202,21 @@ export default class Database {
    * @example
    * ```ts
    * const db = await Database.load("sqlite:test.db");
+   *
+   * // with options for the connection pool
+   * const db = await Database.load("sqlite:test.db", {
+   *   maxConnections: 1,
+   *   journalMode: "wal",
+   *   busyTimeout: 5000,
+   * });
    * ```
+   *
+   * @param options - Options for the connection pool, which take precedence over the ones in the plugin config.
    */
-  static async load(path: string): Promise<Database> {
+  static async load(path: string, options?: PoolOptions): Promise<Database> {
     const _path = await invoke<string>("plugin:sql|load", {
       db: path,
+      options,
     });
 
     return new Database(_path);
@@ -115,6 +288,50 @@ export default class Database {
       rowsAffected,
     };
   }
+  /**
+   * **executeBatch**
+   *
+   * Executes a SQL expression once for each set of bind values in a single transaction.
+   * If any of them fails, the whole batch is rolled back and the error names the index of the failing set.
+   *
+   * @example
+   * ```ts
+   * const rowsAffected = await db.executeBatch(
+   *    "INSERT into todos (title, status) VALUES ($1, $2)",
+   *    todos.map((todo) => [todo.title, todo.status])
+   * );
+   * ```
+   * @returns The total number of rows affected.
+   */
+  async executeBatch(query: string, bindValues: unknown[][]): Promise<number> {
+    return await invoke<number>("plugin:sql|execute_batch", {
+      db: this.path,
+      query,
+      valuesBatches: bindValues,
+    });
+  }
+
+  /**
+   * **executeScript**
+   *
+   * Executes a string of one or more SQL statements without bind values, like a schema setup script.
+   *
+   * @example
+   * ```ts
+   * await db.executeScript(`
+   *    CREATE TABLE todos (id INTEGER PRIMARY KEY, title TEXT);
+   *    CREATE INDEX todos_title ON todos (title);
+   * `);
+   * ```
+   * @returns The total number of rows affected.
+   */
+  async executeScript(sql: string): Promise<number> {
+    return await invoke<number>("plugin:sql|execute_script", {
+      db: this.path,
+      sql,
+    });
+  }
+
   /**
    * **select**
    *
@@ -131,18 +348,120 @@ export default class Database {
    * const result = await db.select(
    *    "SELECT * from todos WHERE id = ?", id
    * );
+   *
+   * // with the columns of the result
+   * const { rows, columns } = await db.select(
+   *    "SELECT * from todos", [], { columns: true }
+   * );
    * ```
+   *
+   * Dates and times are returned as RFC 3339 strings, decimals as strings to keep
+   * their precision, and binary data as arrays of bytes.
    */
-  async select<T>(query: string, bindValues?: unknown[]): Promise<T> {
-    const result = await invoke<T>("plugin:sql|select", {
+  select<T>(query: string, bindValues?: unknown[]): Promise<T>;
+  select<T>(
+    query: string,
+    bindValues: unknown[] | undefined,
+    options: SelectOptions & { columns: true },
+  ): Promise<SelectResult<T>>;
+  async select<T>(
+    query: string,
+    bindValues?: unknown[],
+    options?: SelectOptions,
+  ): Promise<T | SelectResult<T>> {
+    const result = await invoke<T | SelectResult<T>>("plugin:sql|select", {
       db: this.path,
       query,
       values: bindValues ?? [],
+      withColumns: options?.columns ?? false,
     });
 
     return result;
   }
 
+  /**
+   * **selectCursor**
+   *
+   * Passes in a SELECT query whose rows are fetched in chunks of `chunkSize`,
+   * without loading the whole result at once.
+   * A cursor that isn't used for a while is closed.
+   *
+   * @example
+   * ```ts
+   * const cursor = await db.selectCursor("SELECT * from logs", [], 500);
+   * let chunk;
+   * do {
+   *   chunk = await cursor.fetch();
+   *   process(chunk.rows);
+   * } while (!chunk.done);
+   * ```
+   */
+  async selectCursor<T>(
+    query: string,
+    bindValues?: unknown[],
+    chunkSize = 100,
+  ): Promise<Cursor<T>> {
+    const id = await invoke<number>("plugin:sql|select_cursor", {
+      db: this.path,
+      query,
+      values: bindValues ?? [],
+      chunkSize,
+    });
+    return new Cursor(id);
+  }
+
+  /**
+   * **begin**
+   *
+   * Begins a transaction, which must be committed or rolled back.
+   *
+   * @example
+   * ```ts
+   * const tx = await db.begin();
+   * try {
+   *   const { lastInsertId } = await tx.execute("INSERT into todos (title) VALUES ($1)", [title]);
+   *   await tx.execute("INSERT into tags (todo_id, name) VALUES ($1, $2)", [lastInsertId, tag]);
+   *   await tx.commit();
+   * } catch (e) {
+   *   await tx.rollback();
+   *   throw e;
+   * }
+   * ```
+   */
+  async begin(): Promise<Transaction> {
+    const id = await invoke<number>("plugin:sql|begin", {
+      db: this.path,
+    });
+    return new Transaction(id);
+  }
+
+  /**
+   * **migrateTo**
+   *
+   * Applies or reverts the migrations added with the Rust builder so that `version` is the
+   * latest one applied, or reverts all of them if it's `0`.
+   * Fails before reverting anything if a migration to revert has no down migration.
+   *
+   * @example
+   * ```ts
+   * await db.migrateTo(2);
+   * ```
+   */
+  async migrateTo(version: number): Promise<void> {
+    await invoke("plugin:sql|migrate_to", { db: this.path, version });
+  }
+
+  /**
+   * **migrationStatus**
+   *
+   * Lists the migrations of the database, and whether and when they were applied.
+   */
+  async migrationStatus(): Promise<MigrationStatus[]> {
+    return await invoke<MigrationStatus[]>("plugin:sql|migration_status", {
+      db: this.path,
+    });
+  }
+
   /**
    * **close**
    *
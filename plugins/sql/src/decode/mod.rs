@@ -13,3 +13,43 @@ pub(crate) use postgres::to_json;
 
 #[cfg(feature = "sqlite")]
 pub(crate) use sqlite::to_json;
+
+use serde_json::Value as JsonValue;
+use time::{
+    format_description::well_known::Rfc3339, Date, OffsetDateTime, PrimitiveDateTime, Time,
+};
+
+/// Formats a timestamp as RFC 3339.
+pub(crate) fn rfc3339(v: OffsetDateTime) -> String {
+    v.format(&Rfc3339).unwrap_or_else(|_| v.to_string())
+}
+
+pub(crate) fn offset_date_time(v: OffsetDateTime) -> JsonValue {
+    JsonValue::String(rfc3339(v))
+}
+
+/// Formats a timestamp without a time zone as RFC 3339, taking it as UTC.
+pub(crate) fn primitive_date_time(v: PrimitiveDateTime) -> JsonValue {
+    offset_date_time(v.assume_utc())
+}
+
+/// Formats a date as an RFC 3339 `full-date`, like `2023-04-01`.
+pub(crate) fn date(v: Date) -> JsonValue {
+    JsonValue::String(v.to_string())
+}
+
+/// Formats a time as an RFC 3339 `partial-time`, like `13:05:00` or `13:05:00.25`.
+pub(crate) fn time(v: Time) -> JsonValue {
+    let mut time = format!("{:02}:{:02}:{:02}", v.hour(), v.minute(), v.second());
+    if v.nanosecond() != 0 {
+        let fraction = format!("{:09}", v.nanosecond());
+        time.push('.');
+        time.push_str(fraction.trim_end_matches('0'));
+    }
+    JsonValue::String(time)
+}
+
+/// Returns binary data as an array of bytes.
+pub(crate) fn bytes(v: Vec<u8>) -> JsonValue {
+    JsonValue::Array(v.into_iter().map(|n| JsonValue::Number(n.into())).collect())
+}
@@ -1,8 +1,8 @@
 use serde_json::Value as JsonValue;
-use sqlx::{mysql::MySqlValueRef, TypeInfo, Value, ValueRef};
+use sqlx::{mysql::MySqlValueRef, types::BigDecimal, TypeInfo, Value, ValueRef};
 use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
 
-use crate::Error;
+use crate::{decode, Error};
 
 pub(crate) fn to_json(v: MySqlValueRef) -> Result<JsonValue, Error> {
     if v.is_null() {
@@ -46,45 +46,54 @@ pub(crate) fn to_json(v: MySqlValueRef) -> Result<JsonValue, Error> {
                 JsonValue::Null
             }
         }
+        // TINYINT(1)
         "BOOLEAN" => {
-            if let Ok(v) = ValueRef::to_owned(&v).try_decode() {
-                JsonValue::Bool(v)
+            if let Ok(v) = ValueRef::to_owned(&v).try_decode::<i8>() {
+                JsonValue::Bool(v != 0)
+            } else {
+                JsonValue::Null
+            }
+        }
+        // as a string, since it may not fit in a double
+        "DECIMAL" => {
+            if let Ok(v) = ValueRef::to_owned(&v).try_decode::<BigDecimal>() {
+                JsonValue::String(v.to_string())
             } else {
                 JsonValue::Null
             }
         }
         "DATE" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode::<Date>() {
-                JsonValue::String(v.to_string())
+                decode::date(v)
             } else {
                 JsonValue::Null
             }
         }
         "TIME" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode::<Time>() {
-                JsonValue::String(v.to_string())
+                decode::time(v)
             } else {
                 JsonValue::Null
             }
         }
         "DATETIME" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode::<PrimitiveDateTime>() {
-                JsonValue::String(v.to_string())
+                decode::primitive_date_time(v)
             } else {
                 JsonValue::Null
             }
         }
         "TIMESTAMP" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode::<OffsetDateTime>() {
-                JsonValue::String(v.to_string())
+                decode::offset_date_time(v)
             } else {
                 JsonValue::Null
             }
         }
         "JSON" => ValueRef::to_owned(&v).try_decode().unwrap_or_default(),
-        "TINIYBLOB" | "MEDIUMBLOB" | "BLOB" | "LONGBLOB" => {
+        "TINYBLOB" | "MEDIUMBLOB" | "BLOB" | "LONGBLOB" | "BINARY" | "VARBINARY" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode::<Vec<u8>>() {
-                JsonValue::Array(v.into_iter().map(|n| JsonValue::Number(n.into())).collect())
+                decode::bytes(v)
             } else {
                 JsonValue::Null
             }
@@ -1,8 +1,8 @@
 use serde_json::Value as JsonValue;
-use sqlx::{postgres::PgValueRef, TypeInfo, Value, ValueRef};
+use sqlx::{postgres::PgValueRef, types::BigDecimal, TypeInfo, Value, ValueRef};
 use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
 
-use crate::Error;
+use crate::{decode, Error};
 
 pub(crate) fn to_json(v: PgValueRef) -> Result<JsonValue, Error> {
     if v.is_null() {
@@ -52,6 +52,14 @@ pub(crate) fn to_json(v: PgValueRef) -> Result<JsonValue, Error> {
                 JsonValue::Null
             }
         }
+        // as a string, since it may not fit in a double
+        "NUMERIC" => {
+            if let Ok(v) = ValueRef::to_owned(&v).try_decode::<BigDecimal>() {
+                JsonValue::String(v.to_string())
+            } else {
+                JsonValue::Null
+            }
+        }
         "BOOL" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode() {
                 JsonValue::Bool(v)
@@ -61,28 +69,28 @@ pub(crate) fn to_json(v: PgValueRef) -> Result<JsonValue, Error> {
         }
         "DATE" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode::<Date>() {
-                JsonValue::String(v.to_string())
+                decode::date(v)
             } else {
                 JsonValue::Null
             }
         }
         "TIME" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode::<Time>() {
-                JsonValue::String(v.to_string())
+                decode::time(v)
             } else {
                 JsonValue::Null
             }
         }
         "TIMESTAMP" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode::<PrimitiveDateTime>() {
-                JsonValue::String(v.to_string())
+                decode::primitive_date_time(v)
             } else {
                 JsonValue::Null
             }
         }
         "TIMESTAMPTZ" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode::<OffsetDateTime>() {
-                JsonValue::String(v.to_string())
+                decode::offset_date_time(v)
             } else {
                 JsonValue::Null
             }
@@ -90,7 +98,7 @@ pub(crate) fn to_json(v: PgValueRef) -> Result<JsonValue, Error> {
         "JSON" | "JSONB" => ValueRef::to_owned(&v).try_decode().unwrap_or_default(),
         "BYTEA" => {
             if let Ok(v) = ValueRef::to_owned(&v).try_decode::<Vec<u8>>() {
-                JsonValue::Array(v.into_iter().map(|n| JsonValue::Number(n.into())).collect())
+                decode::bytes(v)
             } else {
                 JsonValue::Null
             }
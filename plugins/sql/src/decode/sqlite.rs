@@ -1,14 +1,35 @@
 use serde_json::Value as JsonValue;
-use sqlx::{sqlite::SqliteValueRef, TypeInfo, Value, ValueRef};
-use time::{Date, PrimitiveDateTime, Time};
+use sqlx::{
+    sqlite::{SqliteTypeInfo, SqliteValueRef},
+    TypeInfo, Value, ValueRef,
+};
+use time::{Date, OffsetDateTime, Time};
 
-use crate::Error;
+use crate::{decode, Error};
 
-pub(crate) fn to_json(v: SqliteValueRef) -> Result<JsonValue, Error> {
+/// Values are typed by how SQLite stored them, so the declared type of the column is used
+/// to read booleans and dates, which are stored as integers or text.
+pub(crate) fn to_json(v: SqliteValueRef, declared: &SqliteTypeInfo) -> Result<JsonValue, Error> {
     if v.is_null() {
         return Ok(JsonValue::Null);
     }
 
+    let decoded = match declared.name() {
+        "BOOLEAN" => v.to_owned().try_decode().ok().map(JsonValue::Bool),
+        "DATE" => v.to_owned().try_decode::<Date>().ok().map(decode::date),
+        "TIME" => v.to_owned().try_decode::<Time>().ok().map(decode::time),
+        "DATETIME" => v
+            .to_owned()
+            .try_decode::<OffsetDateTime>()
+            .ok()
+            .map(decode::offset_date_time),
+        _ => None,
+    };
+    // fall back to the stored value if it couldn't be read as the declared type
+    if let Some(decoded) = decoded {
+        return Ok(decoded);
+    }
+
     let res = match v.type_info().name() {
         "TEXT" => {
             if let Ok(v) = v.to_owned().try_decode() {
@@ -31,37 +52,9 @@ pub(crate) fn to_json(v: SqliteValueRef) -> Result<JsonValue, Error> {
                 JsonValue::Null
             }
         }
-        "BOOLEAN" => {
-            if let Ok(v) = v.to_owned().try_decode() {
-                JsonValue::Bool(v)
-            } else {
-                JsonValue::Null
-            }
-        }
-        "DATE" => {
-            if let Ok(v) = v.to_owned().try_decode::<Date>() {
-                JsonValue::String(v.to_string())
-            } else {
-                JsonValue::Null
-            }
-        }
-        "TIME" => {
-            if let Ok(v) = v.to_owned().try_decode::<Time>() {
-                JsonValue::String(v.to_string())
-            } else {
-                JsonValue::Null
-            }
-        }
-        "DATETIME" => {
-            if let Ok(v) = v.to_owned().try_decode::<PrimitiveDateTime>() {
-                JsonValue::String(v.to_string())
-            } else {
-                JsonValue::Null
-            }
-        }
         "BLOB" => {
             if let Ok(v) = v.to_owned().try_decode::<Vec<u8>>() {
-                JsonValue::Array(v.into_iter().map(|n| JsonValue::Number(n.into())).collect())
+                decode::bytes(v)
             } else {
                 JsonValue::Null
             }
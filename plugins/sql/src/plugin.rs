@@ -2,24 +2,36 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use futures_core::future::BoxFuture;
+use futures_core::{future::BoxFuture, Stream};
 use serde::{ser::Serializer, Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use sqlx::{
+    database::HasArguments,
     error::BoxDynError,
     migrate::{
-        MigrateDatabase, Migration as SqlxMigration, MigrationSource, MigrationType, Migrator,
+        Migrate, MigrateDatabase, Migration as SqlxMigration, MigrationSource, MigrationType,
+        Migrator,
     },
-    Column, Pool, Row,
+    query::Query,
+    Column, Database, Executor, Pool, Row, Transaction, TypeInfo,
 };
 use tauri::{
     command,
     plugin::{Builder as PluginBuilder, TauriPlugin},
-    AppHandle, Manager, RunEvent, Runtime, State,
+    AppHandle, Manager, RunEvent, Runtime, State, Window, WindowEvent,
 };
-use tokio::sync::Mutex;
+use time::OffsetDateTime;
+use tokio::sync::{mpsc, Mutex};
 
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    future::poll_fn,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 #[cfg(feature = "sqlite")]
 use std::{fs::create_dir_all, path::PathBuf};
@@ -46,6 +58,16 @@ pub enum Error {
     DatabaseNotLoaded(String),
     #[error("unsupported datatype: {0}")]
     UnsupportedDatatype(String),
+    #[error("transaction {0} not found")]
+    TransactionNotFound(u64),
+    #[error("cursor {0} not found")]
+    CursorNotFound(u64),
+    #[error("migration {0} not found")]
+    MigrationNotFound(i64),
+    #[error("migration {0} has no down migration, so it can't be reverted")]
+    MissingDownMigration(i64),
+    #[error("parameter set {index} failed, the batch was rolled back: {source}")]
+    Batch { index: usize, source: sqlx::Error },
 }
 
 impl Serialize for Error {
@@ -88,8 +110,180 @@ fn path_mapper(mut app_path: PathBuf, connection_string: &str) -> String {
     )
 }
 
+/// The connection pools of the loaded databases, by connection string.
 #[derive(Default)]
-struct DbInstances(Mutex<HashMap<String, Pool<Db>>>);
+pub struct DbInstances(Mutex<HashMap<String, Pool<Db>>>);
+
+impl DbInstances {
+    async fn pool(&self, db: &str) -> Result<Pool<Db>> {
+        self.0
+            .lock()
+            .await
+            .get(db)
+            .cloned()
+            .ok_or_else(|| Error::DatabaseNotLoaded(db.to_string()))
+    }
+
+    /// Runs `f` in a transaction on the database, committing it if `f` succeeds and rolling it back otherwise.
+    pub async fn transaction<T, F>(&self, db: &str, f: F) -> Result<T>
+    where
+        F: for<'c> FnOnce(&'c mut Transaction<'static, Db>) -> BoxFuture<'c, Result<T>>,
+    {
+        let mut tx = self.pool(db).await?.begin().await?;
+        match f(&mut tx).await {
+            Ok(value) => {
+                tx.commit().await?;
+                Ok(value)
+            }
+            Err(err) => {
+                tx.rollback().await?;
+                Err(err)
+            }
+        }
+    }
+}
+
+/// A transaction opened by a window.
+struct LiveTransaction {
+    /// Locked while a query runs in the transaction, `None` once it's committed or rolled back.
+    tx: Arc<Mutex<Option<Transaction<'static, Db>>>>,
+    window: String,
+}
+
+impl LiveTransaction {
+    /// Takes the transaction once the query running in it, if any, is done.
+    async fn take(self) -> Option<Transaction<'static, Db>> {
+        self.tx.lock().await.take()
+    }
+}
+
+/// The transactions opened from the frontend, by id.
+///
+/// The map is only locked to look transactions up, so queries in one transaction don't
+/// block the others.
+struct Transactions {
+    transactions: Mutex<HashMap<u64, LiveTransaction>>,
+    next_id: AtomicU64,
+    /// How long a transaction may stay open before it's rolled back.
+    timeout: Duration,
+}
+
+impl Transactions {
+    async fn get(&self, id: u64) -> Result<Arc<Mutex<Option<Transaction<'static, Db>>>>> {
+        self.transactions
+            .lock()
+            .await
+            .get(&id)
+            .map(|live| live.tx.clone())
+            .ok_or(Error::TransactionNotFound(id))
+    }
+
+    /// Removes the transaction, taking it once the query running in it, if any, is done.
+    async fn remove(&self, id: u64) -> Option<Transaction<'static, Db>> {
+        let live = self.transactions.lock().await.remove(&id)?;
+        live.take().await
+    }
+
+    async fn rollback_window(&self, window: &str) {
+        let lives: Vec<(u64, LiveTransaction)> = {
+            let mut transactions = self.transactions.lock().await;
+            let ids: Vec<u64> = transactions
+                .iter()
+                .filter(|(_, live)| live.window == window)
+                .map(|(id, _)| *id)
+                .collect();
+            ids.into_iter()
+                .filter_map(|id| transactions.remove(&id).map(|live| (id, live)))
+                .collect()
+        };
+        for (id, live) in lives {
+            if let Some(tx) = live.take().await {
+                if let Err(err) = tx.rollback().await {
+                    log::warn!("failed to roll back transaction {id}: {err}");
+                }
+            }
+        }
+    }
+}
+
+/// A chunk of the rows of a cursor.
+#[derive(Serialize)]
+struct Chunk {
+    rows: Vec<HashMap<String, JsonValue>>,
+    /// Whether these are the last rows.
+    done: bool,
+}
+
+/// The rows of a query, fetched in chunks by a task that holds the query's connection
+/// until the cursor is done or dropped.
+struct Cursor {
+    chunks: Arc<Mutex<mpsc::Receiver<Result<Chunk>>>>,
+    window: String,
+    last_used: Instant,
+}
+
+/// The cursors opened from the frontend, by id.
+struct Cursors {
+    cursors: Mutex<HashMap<u64, Cursor>>,
+    next_id: AtomicU64,
+    /// How long a cursor may stay unused before it's closed.
+    idle_timeout: Duration,
+}
+
+impl Cursors {
+    async fn close_window(&self, window: &str) {
+        self.cursors
+            .lock()
+            .await
+            .retain(|_, cursor| cursor.window != window);
+    }
+}
+
+type SqlQuery<'q> = Query<'q, Db, <Db as HasArguments<'q>>::Arguments>;
+
+fn bind_values(query: &str, values: Vec<JsonValue>) -> SqlQuery<'_> {
+    let mut query = sqlx::query(query);
+    for value in values {
+        if value.is_null() {
+            query = query.bind(None::<JsonValue>);
+        } else if value.is_string() {
+            query = query.bind(value.as_str().unwrap().to_owned())
+        } else {
+            query = query.bind(value);
+        }
+    }
+    query
+}
+
+fn execute_result(result: <Db as Database>::QueryResult) -> (u64, LastInsertId) {
+    #[cfg(feature = "sqlite")]
+    let r = (result.rows_affected(), result.last_insert_rowid());
+    #[cfg(feature = "mysql")]
+    let r = (result.rows_affected(), result.last_insert_id());
+    #[cfg(feature = "postgres")]
+    let r = (result.rows_affected(), 0);
+    r
+}
+
+fn rows_to_json(rows: Vec<<Db as Database>::Row>) -> Result<Vec<HashMap<String, JsonValue>>> {
+    let mut values = Vec::new();
+    for row in rows {
+        let mut value = HashMap::default();
+        for (i, column) in row.columns().iter().enumerate() {
+            let v = row.try_get_raw(i)?;
+
+            #[cfg(feature = "sqlite")]
+            let v = crate::decode::to_json(v, column.type_info())?;
+            #[cfg(not(feature = "sqlite"))]
+            let v = crate::decode::to_json(v)?;
+
+            value.insert(column.name().to_string(), v);
+        }
+
+        values.push(value);
+    }
+    Ok(values)
+}
 
 struct Migrations(Mutex<HashMap<String, MigrationList>>);
 
@@ -97,9 +291,107 @@ struct Migrations(Mutex<HashMap<String, MigrationList>>);
 pub struct PluginConfig {
     #[serde(default)]
     preload: Vec<String>,
+    /// The options of the connection pools, by connection string.
+    #[serde(default)]
+    pools: HashMap<String, PoolOptions>,
+}
+
+/// Options of the connection pool of a database. Options that aren't set use the defaults
+/// of sqlx.
+///
+/// They can be set with [`Builder::pool_options`], in the plugin config under `pools`, keyed
+/// by connection string, and when loading the database, each taking precedence over the
+/// previous one. Durations are given in milliseconds in the config and when loading.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolOptions {
+    /// The maximum number of connections.
+    pub max_connections: Option<u32>,
+    /// The number of connections to keep open.
+    pub min_connections: Option<u32>,
+    /// How long to wait for a connection before failing.
+    #[serde(default, deserialize_with = "deserialize_millis")]
+    pub acquire_timeout: Option<Duration>,
+    /// How long a connection may stay idle before it's closed.
+    #[serde(default, deserialize_with = "deserialize_millis")]
+    pub idle_timeout: Option<Duration>,
+    /// How long a connection may be kept open before it's closed.
+    #[serde(default, deserialize_with = "deserialize_millis")]
+    pub max_lifetime: Option<Duration>,
+    /// The journal mode of the database, like `wal`.
+    #[cfg(feature = "sqlite")]
+    pub journal_mode: Option<String>,
+    /// How long to wait for a lock on the database before failing.
+    #[cfg(feature = "sqlite")]
+    #[serde(default, deserialize_with = "deserialize_millis")]
+    pub busy_timeout: Option<Duration>,
+}
+
+impl PoolOptions {
+    /// Fills the options that aren't set with the ones of `defaults`.
+    fn or(self, defaults: &Self) -> Self {
+        Self {
+            max_connections: self.max_connections.or(defaults.max_connections),
+            min_connections: self.min_connections.or(defaults.min_connections),
+            acquire_timeout: self.acquire_timeout.or(defaults.acquire_timeout),
+            idle_timeout: self.idle_timeout.or(defaults.idle_timeout),
+            max_lifetime: self.max_lifetime.or(defaults.max_lifetime),
+            #[cfg(feature = "sqlite")]
+            journal_mode: self.journal_mode.or_else(|| defaults.journal_mode.clone()),
+            #[cfg(feature = "sqlite")]
+            busy_timeout: self.busy_timeout.or(defaults.busy_timeout),
+        }
+    }
+}
+
+fn deserialize_millis<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Duration>, D::Error> {
+    Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
 }
 
-#[derive(Debug)]
+/// The pool options set with the builder and in the plugin config, by connection string.
+struct ConfiguredPoolOptions(HashMap<String, PoolOptions>);
+
+/// Creates the database if it doesn't exist and connects to it.
+async fn connect(url: &str, options: &PoolOptions) -> Result<Pool<Db>> {
+    if !Db::database_exists(url).await.unwrap_or(false) {
+        Db::create_database(url).await?;
+    }
+
+    let mut pool_options = sqlx::pool::PoolOptions::<Db>::new();
+    if let Some(max_connections) = options.max_connections {
+        pool_options = pool_options.max_connections(max_connections);
+    }
+    if let Some(min_connections) = options.min_connections {
+        pool_options = pool_options.min_connections(min_connections);
+    }
+    if let Some(timeout) = options.acquire_timeout {
+        pool_options = pool_options.acquire_timeout(timeout);
+    }
+    if let Some(timeout) = options.idle_timeout {
+        pool_options = pool_options.idle_timeout(timeout);
+    }
+    if let Some(lifetime) = options.max_lifetime {
+        pool_options = pool_options.max_lifetime(lifetime);
+    }
+
+    #[cfg(feature = "sqlite")]
+    {
+        let mut connect_options: sqlx::sqlite::SqliteConnectOptions = url.parse()?;
+        if let Some(journal_mode) = &options.journal_mode {
+            connect_options = connect_options.journal_mode(journal_mode.parse()?);
+        }
+        if let Some(timeout) = options.busy_timeout {
+            connect_options = connect_options.busy_timeout(timeout);
+        }
+        Ok(pool_options.connect_with(connect_options).await?)
+    }
+    #[cfg(not(feature = "sqlite"))]
+    Ok(pool_options.connect(url).await?)
+}
+
+#[derive(Debug, Clone, Copy)]
 pub enum MigrationKind {
     Up,
     Down,
@@ -115,42 +407,223 @@ impl From<MigrationKind> for MigrationType {
 }
 
 /// A migration definition.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Migration {
     pub version: i64,
     pub description: &'static str,
     pub sql: &'static str,
     pub kind: MigrationKind,
+    /// The SQL that reverts an up migration, needed to migrate to an older version.
+    pub down: Option<&'static str>,
 }
 
-#[derive(Debug)]
+impl Migration {
+    /// An up migration, applying `sql` to migrate the database to `version`.
+    pub fn up(version: i64, description: &'static str, sql: &'static str) -> Self {
+        Self {
+            version,
+            description,
+            sql,
+            kind: MigrationKind::Up,
+            down: None,
+        }
+    }
+
+    /// A down migration, applying `sql` to revert the up migration of `version`.
+    pub fn down(version: i64, description: &'static str, sql: &'static str) -> Self {
+        Self {
+            kind: MigrationKind::Down,
+            ..Self::up(version, description, sql)
+        }
+    }
+
+    /// Sets the SQL that reverts this up migration, instead of adding a down migration of
+    /// the same version.
+    pub fn with_down(mut self, sql: &'static str) -> Self {
+        self.down = Some(sql);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 struct MigrationList(Vec<Migration>);
 
+impl MigrationList {
+    /// Whether the migration of `version` can be reverted, with its `down` SQL or a down
+    /// migration of the same version.
+    fn has_down(&self, version: i64) -> bool {
+        self.0.iter().any(|migration| {
+            migration.version == version
+                && (migration.down.is_some() || matches!(migration.kind, MigrationKind::Down))
+        })
+    }
+}
+
 impl MigrationSource<'static> for MigrationList {
     fn resolve(self) -> BoxFuture<'static, std::result::Result<Vec<SqlxMigration>, BoxDynError>> {
         Box::pin(async move {
+            // otherwise both down scripts of the version would be run when reverting it
+            let down_versions: HashSet<i64> = self
+                .0
+                .iter()
+                .filter(|migration| matches!(migration.kind, MigrationKind::Down))
+                .map(|migration| migration.version)
+                .collect();
+            if let Some(migration) = self.0.iter().find(|migration| {
+                matches!(migration.kind, MigrationKind::Up)
+                    && migration.down.is_some()
+                    && down_versions.contains(&migration.version)
+            }) {
+                return Err(format!(
+                    "migration {} has both a `down` script and a down migration",
+                    migration.version
+                )
+                .into());
+            }
+
             let mut migrations = Vec::new();
             for migration in self.0 {
-                if matches!(migration.kind, MigrationKind::Up) {
+                migrations.push(SqlxMigration::new(
+                    migration.version,
+                    migration.description.into(),
+                    migration.kind.into(),
+                    migration.sql.into(),
+                ));
+                if let (MigrationKind::Up, Some(down)) = (migration.kind, migration.down) {
                     migrations.push(SqlxMigration::new(
                         migration.version,
                         migration.description.into(),
-                        migration.kind.into(),
-                        migration.sql.into(),
+                        MigrationType::ReversibleDown,
+                        down.into(),
                     ));
                 }
             }
+            // sqlx applies and reverts migrations in the order they are resolved
+            migrations.sort_by_key(|m| (m.version, m.migration_type.is_down_migration()));
             Ok(migrations)
         })
     }
 }
 
+/// The state of a migration of a database.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MigrationStatus {
+    version: i64,
+    description: &'static str,
+    applied: bool,
+    /// When the migration was applied, as an RFC 3339 timestamp.
+    applied_at: Option<String>,
+    /// Whether the migration can be reverted.
+    reversible: bool,
+}
+
+/// The versions of the migrations applied to a database, with when they were applied.
+async fn applied_migrations(pool: &Pool<Db>) -> Result<HashMap<i64, OffsetDateTime>> {
+    pool.acquire().await?.ensure_migrations_table().await?;
+    let applied: Vec<(i64, OffsetDateTime)> =
+        sqlx::query_as("SELECT version, installed_on FROM _sqlx_migrations WHERE success = true")
+            .fetch_all(pool)
+            .await?;
+    Ok(applied.into_iter().collect())
+}
+
+/// Lists the migrations of a database, and whether and when they were applied.
+#[command]
+async fn migration_status(
+    db_instances: State<'_, DbInstances>,
+    migrations: State<'_, Migrations>,
+    db: String,
+) -> Result<Vec<MigrationStatus>> {
+    let pool = db_instances.pool(&db).await?;
+    let list = migrations
+        .0
+        .lock()
+        .await
+        .get(&db)
+        .cloned()
+        .unwrap_or_default();
+    let applied = applied_migrations(&pool).await?;
+
+    let mut status: Vec<MigrationStatus> = list
+        .0
+        .iter()
+        .filter(|migration| matches!(migration.kind, MigrationKind::Up))
+        .map(|migration| {
+            let applied_at = applied.get(&migration.version);
+            MigrationStatus {
+                version: migration.version,
+                description: migration.description,
+                applied: applied_at.is_some(),
+                applied_at: applied_at.map(|at| crate::decode::rfc3339(*at)),
+                reversible: list.has_down(migration.version),
+            }
+        })
+        .collect();
+    status.sort_by_key(|migration| migration.version);
+    Ok(status)
+}
+
+/// Applies or reverts migrations so that `version` is the latest one applied, reverting all of
+/// them if it's 0. Each migration runs in its own transaction where the database supports
+/// transactional DDL.
+#[command]
+async fn migrate_to(
+    db_instances: State<'_, DbInstances>,
+    migrations: State<'_, Migrations>,
+    db: String,
+    version: i64,
+) -> Result<()> {
+    let pool = db_instances.pool(&db).await?;
+    let list = migrations
+        .0
+        .lock()
+        .await
+        .get(&db)
+        .cloned()
+        .unwrap_or_default();
+    let defined = list.0.iter().any(|migration| {
+        migration.version == version && matches!(migration.kind, MigrationKind::Up)
+    });
+    if version != 0 && !defined {
+        return Err(Error::MigrationNotFound(version));
+    }
+
+    // fail before reverting anything if one of the migrations can't be
+    let mut revert: Vec<i64> = applied_migrations(&pool)
+        .await?
+        .into_keys()
+        .filter(|applied| *applied > version)
+        .collect();
+    revert.sort_unstable();
+    if let Some(missing) = revert.iter().find(|applied| !list.has_down(**applied)) {
+        return Err(Error::MissingDownMigration(*missing));
+    }
+    if !revert.is_empty() {
+        Migrator::new(list.clone())
+            .await?
+            .undo(&pool, version)
+            .await?;
+    }
+
+    let up = MigrationList(
+        list.0
+            .into_iter()
+            .filter(|migration| migration.version <= version)
+            .collect(),
+    );
+    Migrator::new(up).await?.run(&pool).await?;
+    Ok(())
+}
+
 #[command]
 async fn load<R: Runtime>(
     #[allow(unused_variables)] app: AppHandle<R>,
     db_instances: State<'_, DbInstances>,
     migrations: State<'_, Migrations>,
+    pool_options: State<'_, ConfiguredPoolOptions>,
     db: String,
+    options: Option<PoolOptions>,
 ) -> Result<String> {
     #[cfg(feature = "sqlite")]
     let fqdb = path_mapper(app_path(&app), &db);
@@ -160,12 +633,15 @@ async fn load<R: Runtime>(
     #[cfg(feature = "sqlite")]
     create_dir_all(app_path(&app)).expect("Problem creating App directory!");
 
-    if !Db::database_exists(&fqdb).await.unwrap_or(false) {
-        Db::create_database(&fqdb).await?;
-    }
-    let pool = Pool::connect(&fqdb).await?;
+    let options = match pool_options.0.get(&db) {
+        Some(configured) => options.unwrap_or_default().or(configured),
+        None => options.unwrap_or_default(),
+    };
+    let pool = connect(&fqdb, &options).await?;
 
-    if let Some(migrations) = migrations.0.lock().await.remove(&db) {
+    // kept to migrate the database to other versions later
+    let migrations = migrations.0.lock().await.get(&db).cloned();
+    if let Some(migrations) = migrations {
         let migrator = Migrator::new(migrations).await?;
         migrator.run(&pool).await?;
     }
@@ -208,70 +684,326 @@ async fn execute(
     let mut instances = db_instances.0.lock().await;
 
     let db = instances.get_mut(&db).ok_or(Error::DatabaseNotLoaded(db))?;
-    let mut query = sqlx::query(&query);
-    for value in values {
-        if value.is_null() {
-            query = query.bind(None::<JsonValue>);
-        } else if value.is_string() {
-            query = query.bind(value.as_str().unwrap().to_owned())
-        } else {
-            query = query.bind(value);
-        }
-    }
-    let result = query.execute(&*db).await?;
-    #[cfg(feature = "sqlite")]
-    let r = Ok((result.rows_affected(), result.last_insert_rowid()));
-    #[cfg(feature = "mysql")]
-    let r = Ok((result.rows_affected(), result.last_insert_id()));
-    #[cfg(feature = "postgres")]
-    let r = Ok((result.rows_affected(), 0));
-    r
+    let result = bind_values(&query, values).execute(&*db).await?;
+    Ok(execute_result(result))
 }
 
+/// A column of the result of a query.
+#[derive(Serialize)]
+struct ColumnInfo {
+    name: String,
+    type_name: String,
+    /// Whether the column may be null, if the database can tell.
+    nullable: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum SelectResult {
+    Rows(Vec<HashMap<String, JsonValue>>),
+    WithColumns {
+        rows: Vec<HashMap<String, JsonValue>>,
+        columns: Vec<ColumnInfo>,
+    },
+}
+
+/// Runs a query, returning its rows, along with its columns if `with_columns` is set.
 #[command]
 async fn select(
     db_instances: State<'_, DbInstances>,
     db: String,
     query: String,
     values: Vec<JsonValue>,
-) -> Result<Vec<HashMap<String, JsonValue>>> {
+    with_columns: Option<bool>,
+) -> Result<SelectResult> {
     let mut instances = db_instances.0.lock().await;
     let db = instances.get_mut(&db).ok_or(Error::DatabaseNotLoaded(db))?;
-    let mut query = sqlx::query(&query);
-    for value in values {
-        if value.is_null() {
-            query = query.bind(None::<JsonValue>);
-        } else if value.is_string() {
-            query = query.bind(value.as_str().unwrap().to_owned())
-        } else {
-            query = query.bind(value);
+    let rows = rows_to_json(bind_values(&query, values).fetch_all(&*db).await?)?;
+    if !with_columns.unwrap_or_default() {
+        return Ok(SelectResult::Rows(rows));
+    }
+
+    // described separately, so the columns are known even if there are no rows
+    let describe = (&*db).describe(&query).await?;
+    let columns = describe
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| ColumnInfo {
+            name: column.name().to_string(),
+            type_name: column.type_info().name().to_string(),
+            nullable: describe.nullable(i),
+        })
+        .collect();
+    Ok(SelectResult::WithColumns { rows, columns })
+}
+
+/// Executes a command once for each set of values in a single transaction, returning the
+/// total number of rows affected. The whole batch is rolled back if any of them fails.
+#[command]
+async fn execute_batch(
+    db_instances: State<'_, DbInstances>,
+    db: String,
+    query: String,
+    values_batches: Vec<Vec<JsonValue>>,
+) -> Result<u64> {
+    let mut tx = db_instances.pool(&db).await?.begin().await?;
+    let mut rows_affected = 0;
+    for (index, values) in values_batches.into_iter().enumerate() {
+        match bind_values(&query, values).execute(&mut *tx).await {
+            Ok(result) => rows_affected += result.rows_affected(),
+            Err(source) => {
+                let _ = tx.rollback().await;
+                return Err(Error::Batch { index, source });
+            }
         }
     }
-    let rows = query.fetch_all(&*db).await?;
-    let mut values = Vec::new();
-    for row in rows {
-        let mut value = HashMap::default();
-        for (i, column) in row.columns().iter().enumerate() {
-            let v = row.try_get_raw(i)?;
+    tx.commit().await?;
+    Ok(rows_affected)
+}
 
-            let v = crate::decode::to_json(v)?;
+/// Executes a string of one or more SQL statements without bind values, like a schema
+/// setup script, returning the total number of rows affected.
+#[command]
+async fn execute_script(
+    db_instances: State<'_, DbInstances>,
+    db: String,
+    sql: String,
+) -> Result<u64> {
+    let pool = db_instances.pool(&db).await?;
+    let result = pool.execute(sql.as_str()).await?;
+    Ok(result.rows_affected())
+}
 
-            value.insert(column.name().to_string(), v);
+/// Begins a transaction that is rolled back if the window is destroyed or the transaction
+/// times out before it's committed.
+#[command]
+async fn begin<R: Runtime>(
+    window: Window<R>,
+    db_instances: State<'_, DbInstances>,
+    transactions: State<'_, Transactions>,
+    db: String,
+) -> Result<u64> {
+    let tx = db_instances.pool(&db).await?.begin().await?;
+
+    let id = transactions.next_id.fetch_add(1, Ordering::Relaxed);
+    transactions.transactions.lock().await.insert(
+        id,
+        LiveTransaction {
+            tx: Arc::new(Mutex::new(Some(tx))),
+            window: window.label().into(),
+        },
+    );
+
+    let app = window.app_handle();
+    let timeout = transactions.timeout;
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(timeout).await;
+        let transactions = app.state::<Transactions>();
+        if let Some(tx) = transactions.remove(id).await {
+            log::warn!("transaction {id} timed out, rolling it back");
+            if let Err(err) = tx.rollback().await {
+                log::warn!("failed to roll back transaction {id}: {err}");
+            }
         }
+    });
 
-        values.push(value);
+    Ok(id)
+}
+
+/// Runs a query whose rows are fetched in chunks of `chunk_size` with `fetch`, returning the
+/// id of the cursor. The cursor is closed when the window is destroyed or it isn't used for
+/// the idle timeout.
+#[command]
+async fn select_cursor<R: Runtime>(
+    window: Window<R>,
+    db_instances: State<'_, DbInstances>,
+    cursors: State<'_, Cursors>,
+    db: String,
+    query: String,
+    values: Vec<JsonValue>,
+    chunk_size: usize,
+) -> Result<u64> {
+    let pool = db_instances.pool(&db).await?;
+    let chunk_size = chunk_size.max(1);
+    let (sender, receiver) = mpsc::channel(1);
+    tauri::async_runtime::spawn(async move {
+        let mut rows = bind_values(&query, values).fetch(&pool);
+        let mut chunk = Vec::with_capacity(chunk_size);
+        loop {
+            match poll_fn(|cx| rows.as_mut().poll_next(cx)).await {
+                Some(Ok(row)) => {
+                    // only send full chunks once there are more rows, so the last one is marked as done
+                    if chunk.len() == chunk_size {
+                        let rows = rows_to_json(std::mem::take(&mut chunk))
+                            .map(|rows| Chunk { rows, done: false });
+                        if sender.send(rows).await.is_err() {
+                            // the cursor was closed
+                            return;
+                        }
+                    }
+                    chunk.push(row);
+                }
+                Some(Err(err)) => {
+                    let _ = sender.send(Err(err.into())).await;
+                    return;
+                }
+                None => break,
+            }
+        }
+        let _ = sender
+            .send(rows_to_json(chunk).map(|rows| Chunk { rows, done: true }))
+            .await;
+    });
+
+    let id = cursors.next_id.fetch_add(1, Ordering::Relaxed);
+    cursors.cursors.lock().await.insert(
+        id,
+        Cursor {
+            chunks: Arc::new(Mutex::new(receiver)),
+            window: window.label().into(),
+            last_used: Instant::now(),
+        },
+    );
+
+    let app = window.app_handle();
+    let idle_timeout = cursors.idle_timeout;
+    tauri::async_runtime::spawn(async move {
+        let mut wait = idle_timeout;
+        loop {
+            tokio::time::sleep(wait).await;
+            let cursors = app.state::<Cursors>();
+            let mut cursors = cursors.cursors.lock().await;
+            let idle = match cursors.get(&id) {
+                Some(cursor) => cursor.last_used.elapsed(),
+                None => return,
+            };
+            if idle >= idle_timeout {
+                cursors.remove(&id);
+                return;
+            }
+            wait = idle_timeout - idle;
+        }
+    });
+
+    Ok(id)
+}
+
+/// Fetches the next chunk of rows of a cursor, closing it once the last rows are fetched.
+#[command]
+async fn fetch(cursors: State<'_, Cursors>, cursor_id: u64) -> Result<Chunk> {
+    let chunks = match cursors.cursors.lock().await.get_mut(&cursor_id) {
+        Some(cursor) => {
+            cursor.last_used = Instant::now();
+            cursor.chunks.clone()
+        }
+        None => return Err(Error::CursorNotFound(cursor_id)),
+    };
+
+    let chunk = chunks.lock().await.recv().await;
+    let mut cursors = cursors.cursors.lock().await;
+    match chunk {
+        Some(Ok(chunk)) if !chunk.done => {
+            if let Some(cursor) = cursors.get_mut(&cursor_id) {
+                cursor.last_used = Instant::now();
+            }
+            Ok(chunk)
+        }
+        chunk => {
+            cursors.remove(&cursor_id);
+            chunk.unwrap_or(Ok(Chunk {
+                rows: Vec::new(),
+                done: true,
+            }))
+        }
     }
+}
 
-    Ok(values)
+/// Closes a cursor before all of its rows were fetched, returning whether it was open.
+#[command]
+async fn close_cursor(cursors: State<'_, Cursors>, cursor_id: u64) -> Result<bool> {
+    Ok(cursors.cursors.lock().await.remove(&cursor_id).is_some())
+}
+
+/// Execute a command in a transaction
+#[command]
+async fn execute_in(
+    transactions: State<'_, Transactions>,
+    tx_id: u64,
+    query: String,
+    values: Vec<JsonValue>,
+) -> Result<(u64, LastInsertId)> {
+    let tx = transactions.get(tx_id).await?;
+    let mut tx = tx.lock().await;
+    let tx = tx.as_mut().ok_or(Error::TransactionNotFound(tx_id))?;
+    let result = bind_values(&query, values).execute(&mut **tx).await?;
+    Ok(execute_result(result))
+}
+
+#[command]
+async fn select_in(
+    transactions: State<'_, Transactions>,
+    tx_id: u64,
+    query: String,
+    values: Vec<JsonValue>,
+) -> Result<Vec<HashMap<String, JsonValue>>> {
+    let tx = transactions.get(tx_id).await?;
+    let mut tx = tx.lock().await;
+    let tx = tx.as_mut().ok_or(Error::TransactionNotFound(tx_id))?;
+    let rows = bind_values(&query, values).fetch_all(&mut **tx).await?;
+    rows_to_json(rows)
+}
+
+#[command]
+async fn commit(transactions: State<'_, Transactions>, tx_id: u64) -> Result<()> {
+    let tx = transactions.remove(tx_id).await;
+    let tx = tx.ok_or(Error::TransactionNotFound(tx_id))?;
+    tx.commit().await.map_err(Into::into)
+}
+
+#[command]
+async fn rollback(transactions: State<'_, Transactions>, tx_id: u64) -> Result<()> {
+    let tx = transactions.remove(tx_id).await;
+    let tx = tx.ok_or(Error::TransactionNotFound(tx_id))?;
+    tx.rollback().await.map_err(Into::into)
 }
 
 /// Tauri SQL plugin builder.
-#[derive(Default)]
 pub struct Builder {
     migrations: Option<HashMap<String, MigrationList>>,
+    pool_options: Option<HashMap<String, PoolOptions>>,
+    transaction_timeout: Duration,
+    cursor_idle_timeout: Duration,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            migrations: None,
+            pool_options: None,
+            transaction_timeout: Duration::from_secs(60),
+            cursor_idle_timeout: Duration::from_secs(60),
+        }
+    }
 }
 
 impl Builder {
+    /// Sets how long a transaction begun from the frontend may stay open before it's rolled back.
+    /// Defaults to 60 seconds.
+    #[must_use]
+    pub fn transaction_timeout(mut self, timeout: Duration) -> Self {
+        self.transaction_timeout = timeout;
+        self
+    }
+
+    /// Sets how long a cursor opened from the frontend may stay unused before it's closed.
+    /// Defaults to 60 seconds.
+    #[must_use]
+    pub fn cursor_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.cursor_idle_timeout = timeout;
+        self
+    }
+
     /// Add migrations to a database.
     #[must_use]
     pub fn add_migrations(mut self, db_url: &str, migrations: Vec<Migration>) -> Self {
@@ -281,15 +1013,51 @@ impl Builder {
         self
     }
 
+    /// Sets the options of the connection pool of a database, which are overridden by the
+    /// ones in the plugin config and the ones given when loading it.
+    #[must_use]
+    pub fn pool_options(mut self, db_url: &str, options: PoolOptions) -> Self {
+        self.pool_options
+            .get_or_insert(Default::default())
+            .insert(db_url.to_string(), options);
+        self
+    }
+
     pub fn build<R: Runtime>(mut self) -> TauriPlugin<R, Option<PluginConfig>> {
         PluginBuilder::new("sql")
-            .invoke_handler(tauri::generate_handler![load, execute, select, close])
+            .invoke_handler(tauri::generate_handler![
+                load,
+                execute,
+                execute_batch,
+                execute_script,
+                select,
+                select_cursor,
+                fetch,
+                close_cursor,
+                close,
+                begin,
+                execute_in,
+                select_in,
+                commit,
+                rollback,
+                migrate_to,
+                migration_status
+            ])
             .setup_with_config(|app, config: Option<PluginConfig>| {
-                let config = config.unwrap_or_default();
+                let mut config = config.unwrap_or_default();
 
                 #[cfg(feature = "sqlite")]
                 create_dir_all(app_path(app)).expect("problems creating App directory!");
 
+                let mut pool_options = self.pool_options.take().unwrap_or_default();
+                for (db, options) in config.pools.drain() {
+                    let options = match pool_options.remove(&db) {
+                        Some(builder_options) => options.or(&builder_options),
+                        None => options,
+                    };
+                    pool_options.insert(db, options);
+                }
+
                 tauri::async_runtime::block_on(async move {
                     let instances = DbInstances::default();
                     let mut lock = instances.0.lock().await;
@@ -299,13 +1067,15 @@ impl Builder {
                         #[cfg(not(feature = "sqlite"))]
                         let fqdb = db.clone();
 
-                        if !Db::database_exists(&fqdb).await.unwrap_or(false) {
-                            Db::create_database(&fqdb).await?;
-                        }
-                        let pool = Pool::connect(&fqdb).await?;
+                        let options = pool_options.get(&db).cloned().unwrap_or_default();
+                        let pool = connect(&fqdb, &options).await?;
 
-                        if let Some(migrations) = self.migrations.as_mut().unwrap().remove(&db) {
-                            let migrator = Migrator::new(migrations).await?;
+                        if let Some(migrations) = self
+                            .migrations
+                            .as_ref()
+                            .and_then(|migrations| migrations.get(&db))
+                        {
+                            let migrator = Migrator::new(migrations.clone()).await?;
                             migrator.run(&pool).await?;
                         }
                         lock.insert(db, pool);
@@ -313,6 +1083,17 @@ impl Builder {
                     drop(lock);
 
                     app.manage(instances);
+                    app.manage(ConfiguredPoolOptions(pool_options));
+                    app.manage(Transactions {
+                        transactions: Default::default(),
+                        next_id: AtomicU64::new(1),
+                        timeout: self.transaction_timeout,
+                    });
+                    app.manage(Cursors {
+                        cursors: Default::default(),
+                        next_id: AtomicU64::new(1),
+                        idle_timeout: self.cursor_idle_timeout,
+                    });
                     app.manage(Migrations(Mutex::new(
                         self.migrations.take().unwrap_or_default(),
                     )));
@@ -321,6 +1102,24 @@ impl Builder {
                 })
             })
             .on_event(|app, event| {
+                if let RunEvent::WindowEvent {
+                    label,
+                    event: WindowEvent::Destroyed,
+                    ..
+                } = event
+                {
+                    let app = app.clone();
+                    let label = label.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Some(transactions) = app.try_state::<Transactions>() {
+                            transactions.rollback_window(&label).await;
+                        }
+                        if let Some(cursors) = app.try_state::<Cursors>() {
+                            cursors.close_window(&label).await;
+                        }
+                    });
+                }
+
                 if let RunEvent::Exit = event {
                     tauri::async_runtime::block_on(async move {
                         let instances = &*app.state::<DbInstances>();
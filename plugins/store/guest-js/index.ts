@@ -9,6 +9,26 @@ interface ChangePayload<T> {
   path: string;
   key: string;
   value: T | null;
+  oldValue: T | null;
+}
+
+interface ChangesPayload<T> {
+  path: string;
+  changes: Array<ChangePayload<T>>;
+}
+
+interface ClearPayload {
+  path: string;
+  keys: string[];
+}
+
+/**
+ * Returns the paths of the stores that are loaded in memory.
+ *
+ * @returns
+ */
+export async function loadedStores(): Promise<string[]> {
+  return await invoke("plugin:store|loaded_stores");
 }
 
 /**
@@ -25,13 +45,55 @@ export class Store {
    *
    * @param key
    * @param value
+   * @param ttl The number of milliseconds after which the pair is treated as absent.
    * @returns
    */
-  async set(key: string, value: unknown): Promise<void> {
+  async set(key: string, value: unknown, ttl?: number): Promise<void> {
     return await invoke("plugin:store|set", {
       path: this.path,
       key,
       value,
+      ttl,
+    });
+  }
+
+  /**
+   * Inserts multiple key-value pairs into the store at once.
+   *
+   * Listeners are notified of all changes with a single event.
+   * @param entries
+   * @returns
+   */
+  async setMany(entries: Array<[key: string, value: unknown]>): Promise<void> {
+    return await invoke("plugin:store|set_many", {
+      path: this.path,
+      entries,
+    });
+  }
+
+  /**
+   * Merges the top-level keys of an object into the store, like `setMany`.
+   *
+   * @param object
+   * @returns
+   */
+  async patch(object: Record<string, unknown>): Promise<void> {
+    return await invoke("plugin:store|patch", {
+      path: this.path,
+      object,
+    });
+  }
+
+  /**
+   * Returns the values for the given `keys` in the same order, `null` for keys that do not exist.
+   *
+   * @param keys
+   * @returns
+   */
+  async getMany<T>(keys: string[]): Promise<Array<T | null>> {
+    return await invoke("plugin:store|get_many", {
+      path: this.path,
+      keys,
     });
   }
 
@@ -48,6 +110,50 @@ export class Store {
     });
   }
 
+  /**
+   * Returns the value at a path nested in a value of the store, or `null` if it does not exist.
+   *
+   * The path starts with the key and is either separated by dots, like `"appearance.editor.fontSize"` or `"items.3.name"`,
+   * or an array of segments, like `["a.b", "c"]`, for keys that contain dots.
+   *
+   * @param keyPath
+   * @returns
+   */
+  async getPath<T>(keyPath: string | string[]): Promise<T | null> {
+    return await invoke("plugin:store|get_path", {
+      path: this.path,
+      keyPath,
+    });
+  }
+
+  /**
+   * Sets the value at a path nested in a value of the store, creating missing objects on the way.
+   *
+   * @param keyPath See `getPath`.
+   * @param value
+   * @returns
+   */
+  async setPath(keyPath: string | string[], value: unknown): Promise<void> {
+    return await invoke("plugin:store|set_path", {
+      path: this.path,
+      keyPath,
+      value,
+    });
+  }
+
+  /**
+   * Removes the value at a path nested in a value of the store.
+   *
+   * @param keyPath See `getPath`.
+   * @returns
+   */
+  async deletePath(keyPath: string | string[]): Promise<boolean> {
+    return await invoke("plugin:store|delete_path", {
+      path: this.path,
+      keyPath,
+    });
+  }
+
   /**
    * Returns `true` if the given `key` exists in the store.
    *
@@ -98,6 +204,18 @@ export class Store {
     });
   }
 
+  /**
+   * Removes the key-value pairs whose `ttl` has passed.
+   *
+   * Expired pairs are already treated as absent and aren't saved, this removes them from memory.
+   * @returns
+   */
+  async prune(): Promise<void> {
+    return await invoke("plugin:store|prune", {
+      path: this.path,
+    });
+  }
+
   /**
    * Returns a list of all key in the store.
    *
@@ -169,21 +287,89 @@ export class Store {
     });
   }
 
+  /**
+   * Writes the contents of the store as pretty-printed JSON to `destination`, including changes that weren't saved yet.
+   *
+   * The destination must be allowed by the `fs` scope.
+   * @param destination
+   * @returns
+   */
+  async export(destination: string): Promise<void> {
+    return await invoke("plugin:store|export_store", {
+      path: this.path,
+      destination,
+    });
+  }
+
+  /**
+   * Imports the entries of a JSON file, like one written by `export`.
+   *
+   * With the `replace` mode, the entries that aren't imported are removed, with `merge` they are kept.
+   * The store is left untouched if the file is invalid. The source must be allowed by the `fs` scope.
+   * @param source
+   * @param mode
+   * @returns
+   */
+  async import(source: string, mode: "replace" | "merge"): Promise<void> {
+    return await invoke("plugin:store|import_store", {
+      path: this.path,
+      source,
+      mode,
+    });
+  }
+
+  /**
+   * Returns the absolute path of the store file, with relative paths resolved against the base directory of the store.
+   *
+   * @returns
+   */
+  async resolvedPath(): Promise<string> {
+    return await invoke("plugin:store|store_path", {
+      path: this.path,
+    });
+  }
+
+  /**
+   * Returns `true` if the store is loaded in memory.
+   *
+   * @returns
+   */
+  async isLoaded(): Promise<boolean> {
+    return await invoke("plugin:store|is_loaded", {
+      path: this.path,
+    });
+  }
+
+  /**
+   * Saves the store to disk and removes it from memory. It is loaded from disk again the next time it's used.
+   *
+   * Stores registered with the plugin from Rust can't be unloaded.
+   * @returns `true` if the store was loaded.
+   */
+  async unload(): Promise<boolean> {
+    return await invoke("plugin:store|unload", {
+      path: this.path,
+    });
+  }
+
   /**
    * Listen to changes on a store key.
+   *
+   * Once a window listens to specific keys of a store, it only receives the change events of those keys,
+   * unless it also listens to all changes with `onChange`.
    * @param key
    * @param cb
    * @returns A promise resolving to a function to unlisten to the event.
    */
   async onKeyChange<T>(
     key: string,
-    cb: (value: T | null) => void,
+    cb: (value: T | null, oldValue: T | null) => void,
   ): Promise<UnlistenFn> {
-    return await listen<ChangePayload<T>>("store://change", (event) => {
-      if (event.payload.path === this.path && event.payload.key === key) {
-        cb(event.payload.value);
+    return await this.listenChanges<T>((change) => {
+      if (change.key === key) {
+        cb(change.value, change.oldValue);
       }
-    });
+    }, [key]);
   }
 
   /**
@@ -192,11 +378,56 @@ export class Store {
    * @returns A promise resolving to a function to unlisten to the event.
    */
   async onChange<T>(
-    cb: (key: string, value: T | null) => void,
+    cb: (key: string, value: T | null, oldValue: T | null) => void,
+  ): Promise<UnlistenFn> {
+    return await this.listenChanges<T>((change) => {
+      cb(change.key, change.value, change.oldValue);
+    }, null);
+  }
+
+  /**
+   * Calls `cb` for every change of the store, whether emitted on its own or with other changes of a bulk operation.
+   */
+  private async listenChanges<T>(
+    cb: (change: ChangePayload<T>) => void,
+    keys: string[] | null,
   ): Promise<UnlistenFn> {
-    return await listen<ChangePayload<T>>("store://change", (event) => {
+    const unlistenChange = await listen<ChangePayload<T>>(
+      "store://change",
+      (event) => {
+        if (event.payload.path === this.path) {
+          cb(event.payload);
+        }
+      },
+    );
+    const unlistenChanges = await listen<ChangesPayload<T>>(
+      "store://changes",
+      (event) => {
+        if (event.payload.path === this.path) {
+          event.payload.changes.forEach(cb);
+        }
+      },
+    );
+    const id = await invoke<number>("plugin:store|subscribe", {
+      path: this.path,
+      keys,
+    });
+    return () => {
+      unlistenChange();
+      unlistenChanges();
+      void invoke("plugin:store|unsubscribe", { path: this.path, id });
+    };
+  }
+
+  /**
+   * Listen to the store being cleared or reset, after the change events of the affected keys.
+   * @param cb
+   * @returns A promise resolving to a function to unlisten to the event.
+   */
+  async onClear(cb: (keys: string[]) => void): Promise<UnlistenFn> {
+    return await listen<ClearPayload>("store://clear", (event) => {
       if (event.payload.path === this.path) {
-        cb(event.payload.key, event.payload.value);
+        cb(event.payload.keys);
       }
     });
   }
@@ -0,0 +1,59 @@
+// Copyright 2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{format::BoxError, Error};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use std::{path::Path, sync::Arc};
+
+pub(crate) type KeyProvider = Arc<dyn Fn() -> Result<[u8; 32], BoxError> + Send + Sync>;
+
+/// Identifies encrypted store files, followed by the version of the encrypted format.
+const MAGIC: &[u8] = b"TSEC";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = MAGIC.len() + 1;
+const NONCE_LEN: usize = 24;
+
+pub(crate) fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+fn cipher(key_provider: &KeyProvider) -> Result<XChaCha20Poly1305, Error> {
+    let key = key_provider().map_err(Error::KeyProvider)?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+/// Encrypts the serialized store with a random nonce, prefixed by the header and the nonce.
+pub(crate) fn encrypt(key_provider: &KeyProvider, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher(key_provider)?
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| Error::Serialize("failed to encrypt store".into()))?;
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + NONCE_LEN + ciphertext.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.extend_from_slice(&nonce);
+    bytes.extend_from_slice(&ciphertext);
+    Ok(bytes)
+}
+
+/// Decrypts a store file, failing with [`Error::Decrypt`] if the key is wrong or the file was tampered with.
+pub(crate) fn decrypt(
+    key_provider: Option<&KeyProvider>,
+    path: &Path,
+    bytes: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let key_provider = key_provider.ok_or_else(|| Error::MissingKey(path.to_path_buf()))?;
+    if bytes.len() < HEADER_LEN + NONCE_LEN || bytes[MAGIC.len()] != VERSION {
+        return Err(Error::Decrypt(path.to_path_buf()));
+    }
+
+    let (nonce, ciphertext) = bytes[HEADER_LEN..].split_at(NONCE_LEN);
+    cipher(key_provider)?
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| Error::Decrypt(path.to_path_buf()))
+}
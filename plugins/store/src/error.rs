@@ -22,6 +22,33 @@ pub enum Error {
     /// Store not found
     #[error("Store \"{0}\" not found")]
     NotFound(PathBuf),
+    /// The path is not allowed by the fs scope.
+    #[error("Path \"{0}\" is not allowed by the fs scope")]
+    PathForbidden(PathBuf),
+    /// The store is read-only.
+    #[error("Store \"{0}\" is read-only")]
+    ReadOnly(PathBuf),
+    /// The store is being saved.
+    #[error("Store \"{0}\" is being saved")]
+    SaveInProgress(PathBuf),
+    /// The store was registered with the plugin and can't be unloaded.
+    #[error("Store \"{0}\" was registered with the plugin and can't be unloaded")]
+    Registered(PathBuf),
+    /// The key is reserved for the metadata the store saves alongside its entries.
+    #[error("Key \"{0}\" is reserved by the store")]
+    ReservedKey(String),
+    /// A key path goes through a value that is not an object or array.
+    #[error("Key path {0} does not point into an object or array")]
+    InvalidKeyPath(String),
+    /// The store is encrypted but no key provider was configured.
+    #[error("Store \"{0}\" is encrypted but no key was provided")]
+    MissingKey(PathBuf),
+    /// The store could not be decrypted with the provided key.
+    #[error("Failed to decrypt store \"{0}\". The key is wrong or the file is corrupted")]
+    Decrypt(PathBuf),
+    /// The key provider failed to provide a key.
+    #[error("Failed to get the store encryption key. {0}")]
+    KeyProvider(Box<dyn std::error::Error + Send + Sync>),
     /// Some Tauri API failed
     #[error(transparent)]
     Tauri(#[from] tauri::Error),
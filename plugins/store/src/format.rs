@@ -0,0 +1,78 @@
+// Copyright 2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+pub(crate) type BoxError = Box<dyn std::error::Error + Send + Sync>;
+pub(crate) type SerializeFn = fn(&HashMap<String, JsonValue>) -> Result<Vec<u8>, BoxError>;
+pub(crate) type DeserializeFn = fn(&[u8]) -> Result<HashMap<String, JsonValue>, BoxError>;
+
+/// The built-in formats a store can be saved in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    /// Compact JSON on a single line.
+    #[default]
+    Json,
+    /// JSON indented over multiple lines, easier to read and diff.
+    PrettyJson,
+    /// MessagePack, a compact binary format that is faster to parse.
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+impl Format {
+    /// Guesses the format of a saved store from its first byte.
+    ///
+    /// Both JSON formats are detected as [`Format::Json`] since they are read the same way.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes.iter().find(|b| !b.is_ascii_whitespace())? {
+            b'{' => Some(Self::Json),
+            // fixmap, map 16 and map 32
+            #[cfg(feature = "msgpack")]
+            0x80..=0x8f | 0xde | 0xdf => Some(Self::MessagePack),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn serialize_fn(self) -> SerializeFn {
+        match self {
+            Self::Json => serialize_json,
+            Self::PrettyJson => serialize_pretty_json,
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => serialize_msgpack,
+        }
+    }
+
+    pub(crate) fn deserialize_fn(self) -> DeserializeFn {
+        match self {
+            Self::Json | Self::PrettyJson => deserialize_json,
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => deserialize_msgpack,
+        }
+    }
+}
+
+pub(crate) fn serialize_json(cache: &HashMap<String, JsonValue>) -> Result<Vec<u8>, BoxError> {
+    Ok(serde_json::to_vec(&cache)?)
+}
+
+fn serialize_pretty_json(cache: &HashMap<String, JsonValue>) -> Result<Vec<u8>, BoxError> {
+    Ok(serde_json::to_vec_pretty(&cache)?)
+}
+
+pub(crate) fn deserialize_json(bytes: &[u8]) -> Result<HashMap<String, JsonValue>, BoxError> {
+    serde_json::from_slice(bytes).map_err(Into::into)
+}
+
+#[cfg(feature = "msgpack")]
+fn serialize_msgpack(cache: &HashMap<String, JsonValue>) -> Result<Vec<u8>, BoxError> {
+    Ok(rmp_serde::to_vec_named(&cache)?)
+}
+
+#[cfg(feature = "msgpack")]
+fn deserialize_msgpack(bytes: &[u8]) -> Result<HashMap<String, JsonValue>, BoxError> {
+    rmp_serde::from_slice(bytes).map_err(Into::into)
+}
@@ -0,0 +1,156 @@
+// Copyright 2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::Error;
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+use std::fmt;
+
+/// A path to a value nested in a store entry, starting with the key of the entry.
+///
+/// Segments go into objects by key and into arrays by index. A path can be written
+/// with dots, like `appearance.editor.fontSize` or `items.3.name`, or as a list of
+/// segments, like `["a.b", "c"]`, for keys that contain dots themselves.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum KeyPath {
+    /// Segments separated by dots.
+    Dotted(String),
+    /// Segments that may contain dots.
+    Segments(Vec<String>),
+}
+
+impl KeyPath {
+    pub(crate) fn segments(&self) -> Result<Vec<&str>, Error> {
+        let segments: Vec<&str> = match self {
+            Self::Dotted(path) => path.split('.').collect(),
+            Self::Segments(segments) => segments.iter().map(String::as_str).collect(),
+        };
+        if segments.is_empty() {
+            return Err(Error::InvalidKeyPath(self.to_string()));
+        }
+        Ok(segments)
+    }
+
+    fn invalid(&self) -> Error {
+        Error::InvalidKeyPath(self.to_string())
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dotted(path) => write!(f, "{path:?}"),
+            Self::Segments(segments) => write!(f, "{segments:?}"),
+        }
+    }
+}
+
+impl From<&str> for KeyPath {
+    fn from(path: &str) -> Self {
+        Self::Dotted(path.into())
+    }
+}
+
+impl From<String> for KeyPath {
+    fn from(path: String) -> Self {
+        Self::Dotted(path)
+    }
+}
+
+impl From<Vec<String>> for KeyPath {
+    fn from(segments: Vec<String>) -> Self {
+        Self::Segments(segments)
+    }
+}
+
+impl From<&[&str]> for KeyPath {
+    fn from(segments: &[&str]) -> Self {
+        Self::Segments(segments.iter().map(|s| s.to_string()).collect())
+    }
+}
+
+/// Gets the value at `segments` inside `value`, failing if a segment goes through a value
+/// that isn't an object or array.
+pub(crate) fn get<'a>(
+    path: &KeyPath,
+    mut value: &'a JsonValue,
+    segments: &[&str],
+) -> Result<Option<&'a JsonValue>, Error> {
+    for segment in segments {
+        let next = match value {
+            JsonValue::Object(map) => map.get(*segment),
+            JsonValue::Array(array) => match segment.parse::<usize>() {
+                Ok(index) => array.get(index),
+                Err(_) => return Err(path.invalid()),
+            },
+            _ => return Err(path.invalid()),
+        };
+        value = match next {
+            Some(next) => next,
+            None => return Ok(None),
+        };
+    }
+    Ok(Some(value))
+}
+
+/// Sets the value at `segments` inside `value`, creating missing objects on the way.
+pub(crate) fn set(
+    path: &KeyPath,
+    mut value: &mut JsonValue,
+    segments: &[&str],
+    new_value: JsonValue,
+) -> Result<(), Error> {
+    for segment in segments {
+        if value.is_null() {
+            *value = JsonValue::Object(Map::new());
+        }
+        value = match value {
+            JsonValue::Object(map) => map.entry(*segment).or_insert(JsonValue::Null),
+            JsonValue::Array(array) => match segment.parse::<usize>() {
+                Ok(index) => array.get_mut(index).ok_or_else(|| path.invalid())?,
+                Err(_) => return Err(path.invalid()),
+            },
+            _ => return Err(path.invalid()),
+        };
+    }
+    *value = new_value;
+    Ok(())
+}
+
+/// Removes the value at `segments` inside `value`, returning it.
+pub(crate) fn delete(
+    path: &KeyPath,
+    value: &mut JsonValue,
+    segments: &[&str],
+) -> Result<Option<JsonValue>, Error> {
+    let (last, parents) = match segments.split_last() {
+        Some(split) => split,
+        None => return Err(path.invalid()),
+    };
+    let mut parent = value;
+    for segment in parents {
+        let next = match parent {
+            JsonValue::Object(map) => map.get_mut(*segment),
+            JsonValue::Array(array) => match segment.parse::<usize>() {
+                Ok(index) => array.get_mut(index),
+                Err(_) => return Err(path.invalid()),
+            },
+            _ => return Err(path.invalid()),
+        };
+        parent = match next {
+            Some(next) => next,
+            None => return Ok(None),
+        };
+    }
+    match parent {
+        JsonValue::Object(map) => Ok(map.remove(*last)),
+        JsonValue::Array(array) => match last.parse::<usize>() {
+            Ok(index) if index < array.len() => Ok(Some(array.remove(index))),
+            Ok(_) => Ok(None),
+            Err(_) => Err(path.invalid()),
+        },
+        _ => Err(path.invalid()),
+    }
+}
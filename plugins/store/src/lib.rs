@@ -3,36 +3,247 @@
 // SPDX-License-Identifier: MIT
 
 pub use error::Error;
+pub use format::Format;
+pub use key_path::KeyPath;
 use log::warn;
 use serde::Serialize;
 pub use serde_json::Value as JsonValue;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
-    sync::Mutex,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+    time::Duration,
 };
-pub use store::{Store, StoreBuilder};
+pub use store::{BaseDirectory, ImportMode, Store, StoreBuilder};
 use tauri::{
     plugin::{self, TauriPlugin},
-    AppHandle, Manager, RunEvent, Runtime, State,
+    AppHandle, Manager, RunEvent, Runtime, State, Window, WindowEvent,
 };
 
+mod encryption;
 mod error;
+mod format;
+mod key_path;
+mod save_queue;
 mod store;
 
+/// The payload of the `store://change` event, emitted for every changed key.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePayload<'a> {
+    pub path: &'a Path,
+    pub key: &'a str,
+    /// The new value, `null` if the key was removed.
+    pub value: &'a JsonValue,
+    /// The previous value, `null` if the key was inserted.
+    pub old_value: &'a JsonValue,
+}
+
+/// The payload of the `store://changes` event, emitted once for the keys changed by a bulk operation.
+#[derive(Serialize, Clone)]
+struct ChangesPayload<'a> {
+    path: &'a Path,
+    changes: Vec<ChangePayload<'a>>,
+}
+
+/// The payload of the `store://clear` event, emitted after a store was cleared or reset.
 #[derive(Serialize, Clone)]
-struct ChangePayload<'a> {
+struct ClearPayload<'a> {
     path: &'a Path,
-    key: &'a str,
-    value: &'a JsonValue,
+    /// The keys that were removed or reset to their default.
+    keys: Vec<String>,
+}
+
+/// The keys windows want change events for, by window label, store path and subscription id.
+///
+/// Windows receive the change events of stores they didn't subscribe to.
+#[derive(Default)]
+struct Subscriptions {
+    next_id: AtomicU32,
+    windows: Mutex<HashMap<String, HashMap<PathBuf, HashMap<u32, Option<HashSet<String>>>>>>,
+}
+
+impl Subscriptions {
+    /// Subscribes the window to changes of `keys`, or all keys if `None`, returning the subscription id.
+    fn subscribe(&self, label: &str, path: PathBuf, keys: Option<Vec<String>>) -> u32 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.windows
+            .lock()
+            .expect("mutex poisoned")
+            .entry(label.into())
+            .or_default()
+            .entry(path)
+            .or_default()
+            .insert(id, keys.map(|keys| keys.into_iter().collect()));
+        id
+    }
+
+    /// Removes the subscription, dropping the window's entries once it has none left.
+    fn unsubscribe(&self, label: &str, path: &Path, id: u32) {
+        let mut windows = self.windows.lock().expect("mutex poisoned");
+        if let Some(paths) = windows.get_mut(label) {
+            if let Some(subscriptions) = paths.get_mut(path) {
+                subscriptions.remove(&id);
+                if subscriptions.is_empty() {
+                    paths.remove(path);
+                }
+            }
+            if paths.is_empty() {
+                windows.remove(label);
+            }
+        }
+    }
+
+    /// Removes the subscriptions of a destroyed window.
+    fn destroyed(&self, label: &str) {
+        self.windows.lock().expect("mutex poisoned").remove(label);
+    }
+
+    fn wants(&self, label: &str, path: &Path, key: &str) -> bool {
+        let windows = self.windows.lock().expect("mutex poisoned");
+        match windows.get(label).and_then(|paths| paths.get(path)) {
+            Some(subscriptions) => subscriptions
+                .values()
+                .any(|keys| keys.as_ref().map_or(true, |keys| keys.contains(key))),
+            None => true,
+        }
+    }
+
+    fn emit<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        event: &str,
+        payload: ChangePayload<'_>,
+    ) -> Result<(), Error> {
+        for (label, window) in app.windows() {
+            if self.wants(&label, payload.path, payload.key) {
+                window.emit(event, payload.clone())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Emits the clear event to the windows that want at least one of the cleared keys.
+    fn emit_clear<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        event: &str,
+        payload: ClearPayload<'_>,
+    ) -> Result<(), Error> {
+        for (label, window) in app.windows() {
+            if payload
+                .keys
+                .iter()
+                .any(|key| self.wants(&label, payload.path, key))
+            {
+                window.emit(event, payload.clone())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Emits the changes each window wants, skipping windows that want none of them.
+    fn emit_changes<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        event: &str,
+        payload: ChangesPayload<'_>,
+    ) -> Result<(), Error> {
+        for (label, window) in app.windows() {
+            let changes: Vec<ChangePayload<'_>> = payload
+                .changes
+                .iter()
+                .filter(|change| self.wants(&label, payload.path, change.key))
+                .cloned()
+                .collect();
+            if !changes.is_empty() {
+                window.emit(
+                    event,
+                    ChangesPayload {
+                        path: payload.path,
+                        changes,
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Default)]
 pub struct StoreCollection<R: Runtime> {
     stores: Mutex<HashMap<PathBuf, Store<R>>>,
+    /// The paths of the stores registered with the plugin.
+    registered: HashSet<PathBuf>,
+    default_base_dir: BaseDirectory,
     frozen: bool,
 }
 
+impl<R: Runtime> StoreCollection<R> {
+    /// The paths of the stores that are loaded.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.stores
+            .lock()
+            .expect("mutex poisoned")
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Whether the store at `path` is loaded.
+    pub fn is_loaded(&self, path: impl AsRef<Path>) -> bool {
+        self.stores
+            .lock()
+            .expect("mutex poisoned")
+            .contains_key(path.as_ref())
+    }
+
+    /// Saves the store at `path` and removes it from memory, returning whether it was loaded.
+    ///
+    /// The store is loaded from disk again the next time it's used.
+    /// Stores registered with the plugin can't be unloaded.
+    pub fn unload(&self, path: impl AsRef<Path>) -> Result<bool, Error> {
+        let path = path.as_ref();
+        if self.registered.contains(path) {
+            return Err(Error::Registered(path.to_path_buf()));
+        }
+
+        let mut loaded = false;
+        loop {
+            let mut stores = self.stores.lock().expect("mutex poisoned");
+            let store = match stores.get(path) {
+                Some(store) => store,
+                None => return Ok(loaded),
+            };
+            loaded = true;
+            if store.is_saving() {
+                return Err(Error::SaveInProgress(path.to_path_buf()));
+            }
+            if !store.is_read_only() {
+                // the store stays loaded while the snapshot is written, so it isn't reloaded
+                // from the old file in the meantime, and is saved again if it changed
+                let snapshot = store.snapshot()?;
+                drop(stores);
+                snapshot.write()?;
+                stores = self.stores.lock().expect("mutex poisoned");
+                match stores.get(path) {
+                    Some(store) if !store.matches(&snapshot) => continue,
+                    Some(_) => {}
+                    None => return Ok(true),
+                }
+            }
+            let store = stores.remove(path);
+            // drop the store once unlocked, as its file watcher may be waiting for the lock
+            drop(stores);
+            drop(store);
+            return Ok(true);
+        }
+    }
+}
+
 pub fn with_store<R: Runtime, T, F: FnOnce(&mut Store<R>) -> Result<T, Error>>(
     app: AppHandle<R>,
     collection: State<'_, StoreCollection<R>>,
@@ -46,7 +257,9 @@ pub fn with_store<R: Runtime, T, F: FnOnce(&mut Store<R>) -> Result<T, Error>>(
         if collection.frozen {
             return Err(Error::NotFound(path.to_path_buf()));
         }
-        let mut store = StoreBuilder::new(app, path.to_path_buf()).build();
+        let mut store = StoreBuilder::new(app, path.to_path_buf())
+            .base_dir(collection.default_base_dir.clone())
+            .build();
         // ignore loading errors, just use the default
         if let Err(err) = store.load() {
             warn!(
@@ -69,8 +282,12 @@ async fn set<R: Runtime>(
     path: PathBuf,
     key: String,
     value: JsonValue,
+    ttl: Option<u64>,
 ) -> Result<(), Error> {
-    with_store(app, stores, path, |store| store.insert(key, value))
+    with_store(app, stores, path, |store| match ttl {
+        Some(ttl) => store.insert_with_ttl(key, value, Duration::from_millis(ttl)),
+        None => store.insert(key, value),
+    })
 }
 
 #[tauri::command]
@@ -83,6 +300,75 @@ async fn get<R: Runtime>(
     with_store(app, stores, path, |store| Ok(store.get(key).cloned()))
 }
 
+#[tauri::command]
+async fn get_path<R: Runtime>(
+    app: AppHandle<R>,
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+    key_path: KeyPath,
+) -> Result<Option<JsonValue>, Error> {
+    with_store(app, stores, path, |store| {
+        Ok(store.get_path(key_path)?.cloned())
+    })
+}
+
+#[tauri::command]
+async fn set_path<R: Runtime>(
+    app: AppHandle<R>,
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+    key_path: KeyPath,
+    value: JsonValue,
+) -> Result<(), Error> {
+    with_store(app, stores, path, |store| store.set_path(key_path, value))
+}
+
+#[tauri::command]
+async fn delete_path<R: Runtime>(
+    app: AppHandle<R>,
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+    key_path: KeyPath,
+) -> Result<bool, Error> {
+    with_store(app, stores, path, |store| store.delete_path(key_path))
+}
+
+#[tauri::command]
+async fn set_many<R: Runtime>(
+    app: AppHandle<R>,
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+    entries: Vec<(String, JsonValue)>,
+) -> Result<(), Error> {
+    with_store(app, stores, path, |store| store.set_many(entries))
+}
+
+#[tauri::command]
+async fn get_many<R: Runtime>(
+    app: AppHandle<R>,
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+    keys: Vec<String>,
+) -> Result<Vec<Option<JsonValue>>, Error> {
+    with_store(app, stores, path, |store| {
+        Ok(store
+            .get_many(keys)
+            .into_iter()
+            .map(|value| value.cloned())
+            .collect())
+    })
+}
+
+#[tauri::command]
+async fn patch<R: Runtime>(
+    app: AppHandle<R>,
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+    object: serde_json::Map<String, JsonValue>,
+) -> Result<(), Error> {
+    with_store(app, stores, path, |store| store.patch(object))
+}
+
 #[tauri::command]
 async fn has<R: Runtime>(
     app: AppHandle<R>,
@@ -121,6 +407,95 @@ async fn reset<R: Runtime>(
     with_store(app, collection, path, |store| store.reset())
 }
 
+#[tauri::command]
+async fn prune<R: Runtime>(
+    app: AppHandle<R>,
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+) -> Result<(), Error> {
+    with_store(app, stores, path, |store| store.prune())
+}
+
+#[tauri::command]
+async fn subscribe<R: Runtime>(
+    window: Window<R>,
+    subscriptions: State<'_, Subscriptions>,
+    path: PathBuf,
+    keys: Option<Vec<String>>,
+) -> Result<u32, Error> {
+    Ok(subscriptions.subscribe(window.label(), path, keys))
+}
+
+#[tauri::command]
+async fn unsubscribe<R: Runtime>(
+    window: Window<R>,
+    subscriptions: State<'_, Subscriptions>,
+    path: PathBuf,
+    id: u32,
+) -> Result<(), Error> {
+    subscriptions.unsubscribe(window.label(), &path, id);
+    Ok(())
+}
+
+#[tauri::command]
+async fn loaded_stores<R: Runtime>(
+    stores: State<'_, StoreCollection<R>>,
+) -> Result<Vec<PathBuf>, Error> {
+    Ok(stores.paths())
+}
+
+#[tauri::command]
+async fn is_loaded<R: Runtime>(
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+) -> Result<bool, Error> {
+    Ok(stores.is_loaded(path))
+}
+
+#[tauri::command]
+async fn unload<R: Runtime>(
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+) -> Result<bool, Error> {
+    stores.unload(path)
+}
+
+#[tauri::command]
+async fn export_store<R: Runtime>(
+    app: AppHandle<R>,
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+    destination: PathBuf,
+) -> Result<(), Error> {
+    if !app.fs_scope().is_allowed(&destination) {
+        return Err(Error::PathForbidden(destination));
+    }
+    with_store(app, stores, path, |store| store.export(destination))
+}
+
+#[tauri::command]
+async fn import_store<R: Runtime>(
+    app: AppHandle<R>,
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+    source: PathBuf,
+    mode: ImportMode,
+) -> Result<(), Error> {
+    if !app.fs_scope().is_allowed(&source) {
+        return Err(Error::PathForbidden(source));
+    }
+    with_store(app, stores, path, |store| store.import(source, mode))
+}
+
+#[tauri::command]
+async fn store_path<R: Runtime>(
+    app: AppHandle<R>,
+    stores: State<'_, StoreCollection<R>>,
+    path: PathBuf,
+) -> Result<PathBuf, Error> {
+    with_store(app, stores, path, |store| Ok(store.path()))
+}
+
 #[tauri::command]
 async fn keys<R: Runtime>(
     app: AppHandle<R>,
@@ -181,12 +556,15 @@ async fn save<R: Runtime>(
     stores: State<'_, StoreCollection<R>>,
     path: PathBuf,
 ) -> Result<(), Error> {
-    with_store(app, stores, path, |store| store.save())
+    // the store is unlocked while it's written
+    let save = with_store(app, stores, path, |store| Ok(store.save()))?;
+    save.await
 }
 
 // #[derive(Default)]
 pub struct Builder<R: Runtime> {
     stores: HashMap<PathBuf, Store<R>>,
+    default_base_dir: BaseDirectory,
     frozen: bool,
 }
 
@@ -194,6 +572,7 @@ impl<R: Runtime> Default for Builder<R> {
     fn default() -> Self {
         Self {
             stores: Default::default(),
+            default_base_dir: Default::default(),
             frozen: false,
         }
     }
@@ -243,6 +622,25 @@ impl<R: Runtime> Builder<R> {
         self
     }
 
+    /// Sets the directory relative store paths are resolved against, for stores that don't set
+    /// their own with [`StoreBuilder::base_dir`]. Defaults to the app data directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::{BaseDirectory, PluginBuilder};
+    ///
+    /// let builder = PluginBuilder::default().default_base_dir(BaseDirectory::Executable);
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn default_base_dir(mut self, base_dir: impl Into<BaseDirectory>) -> Self {
+        self.default_base_dir = base_dir.into();
+        self
+    }
+
     /// Freezes the collection.
     ///
     /// This causes requests for plugins that haven't been registered to fail
@@ -284,10 +682,37 @@ impl<R: Runtime> Builder<R> {
     pub fn build(mut self) -> TauriPlugin<R> {
         plugin::Builder::new("store")
             .invoke_handler(tauri::generate_handler![
-                set, get, has, delete, clear, reset, keys, values, length, entries, load, save
+                set,
+                get,
+                get_path,
+                set_path,
+                delete_path,
+                set_many,
+                get_many,
+                patch,
+                has,
+                delete,
+                clear,
+                reset,
+                prune,
+                subscribe,
+                unsubscribe,
+                keys,
+                values,
+                length,
+                entries,
+                load,
+                save,
+                loaded_stores,
+                is_loaded,
+                unload,
+                export_store,
+                import_store,
+                store_path
             ])
             .setup(move |app_handle| {
                 for (path, store) in self.stores.iter_mut() {
+                    store.default_base_dir(&self.default_base_dir);
                     // ignore loading errors, just use the default
                     if let Err(err) = store.load() {
                         warn!(
@@ -297,7 +722,10 @@ impl<R: Runtime> Builder<R> {
                     }
                 }
 
+                app_handle.manage(Subscriptions::default());
                 app_handle.manage(StoreCollection {
+                    registered: self.stores.keys().cloned().collect(),
+                    default_base_dir: self.default_base_dir,
                     stores: Mutex::new(self.stores),
                     frozen: self.frozen,
                 });
@@ -305,12 +733,43 @@ impl<R: Runtime> Builder<R> {
                 Ok(())
             })
             .on_event(|app_handle, event| {
+                if let RunEvent::WindowEvent {
+                    label,
+                    event: WindowEvent::Destroyed,
+                    ..
+                } = event
+                {
+                    app_handle.state::<Subscriptions>().destroyed(label);
+                }
+
                 if let RunEvent::Exit = event {
                     let collection = app_handle.state::<StoreCollection<R>>();
 
-                    for store in collection.stores.lock().expect("mutex poisoned").values() {
-                        if let Err(err) = store.save() {
-                            eprintln!("failed to save store {:?} with error {:?}", store.path, err);
+                    // written once unlocked, so saving doesn't block other store operations
+                    let snapshots: Vec<_> = collection
+                        .stores
+                        .lock()
+                        .expect("mutex poisoned")
+                        .values()
+                        .filter(|store| !store.is_read_only())
+                        .filter_map(|store| match store.snapshot() {
+                            Ok(snapshot) => Some(snapshot),
+                            Err(err) => {
+                                eprintln!(
+                                    "failed to save store {:?} with error {:?}",
+                                    store.path, err
+                                );
+                                None
+                            }
+                        })
+                        .collect();
+                    for snapshot in snapshots {
+                        if let Err(err) = snapshot.write() {
+                            eprintln!(
+                                "failed to save store {:?} with error {:?}",
+                                snapshot.path(),
+                                err
+                            );
                         }
                     }
                 }
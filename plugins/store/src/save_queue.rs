@@ -0,0 +1,124 @@
+// Copyright 2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{Error, Store};
+use std::{
+    io,
+    sync::{Arc, Condvar, Mutex},
+};
+use tauri::Runtime;
+
+/// Orders the saves of a store and its clones, so a snapshot is never written over a newer one.
+///
+/// Every save request gets an increasing generation. Snapshots saved in the background wait
+/// for a single writer, which only writes the latest one.
+pub(crate) struct SaveQueue<R: Runtime> {
+    state: Mutex<State<R>>,
+    saved: Condvar,
+    /// Held while the file is written.
+    writing: Mutex<()>,
+}
+
+struct State<R: Runtime> {
+    /// The generation of the last requested save.
+    requested: u64,
+    /// The generation of the last finished save.
+    saved: u64,
+    /// The error of the last finished save.
+    error: Option<String>,
+    /// The snapshot waiting for the background writer.
+    pending: Option<(u64, Store<R>)>,
+    /// Whether the background writer is running.
+    running: bool,
+}
+
+impl<R: Runtime> Default for SaveQueue<R> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(State {
+                requested: 0,
+                saved: 0,
+                error: None,
+                pending: None,
+                running: false,
+            }),
+            saved: Condvar::new(),
+            writing: Mutex::new(()),
+        }
+    }
+}
+
+impl<R: Runtime> SaveQueue<R> {
+    /// Whether a save is pending or being written.
+    pub(crate) fn is_saving(&self) -> bool {
+        self.state.lock().unwrap().running || self.writing.try_lock().is_err()
+    }
+
+    /// Reserves the generation of a save.
+    pub(crate) fn request(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.requested += 1;
+        state.requested
+    }
+
+    /// Writes the store unless a newer snapshot was written in the meantime.
+    pub(crate) fn write(&self, generation: u64, store: &Store<R>) -> Result<(), Error> {
+        let _writing = self.writing.lock().unwrap();
+        if self.state.lock().unwrap().saved >= generation {
+            return Ok(());
+        }
+
+        let result = store.write();
+        let mut state = self.state.lock().unwrap();
+        state.saved = generation;
+        state.error = result.as_ref().err().map(ToString::to_string);
+        self.saved.notify_all();
+        result
+    }
+
+    /// Queues a snapshot to be written in the background, replacing an older one that wasn't
+    /// written yet, and returns its generation.
+    pub(crate) fn push(self: &Arc<Self>, snapshot: Store<R>) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        state.requested += 1;
+        let generation = state.requested;
+        state.pending = Some((generation, snapshot));
+        if !state.running {
+            state.running = true;
+            let queue = self.clone();
+            tauri::async_runtime::spawn_blocking(move || queue.run());
+        }
+        generation
+    }
+
+    fn run(&self) {
+        loop {
+            let (generation, store) = {
+                let mut state = self.state.lock().unwrap();
+                match state.pending.take() {
+                    Some(pending) => pending,
+                    None => {
+                        state.running = false;
+                        return;
+                    }
+                }
+            };
+            if let Err(err) = self.write(generation, &store) {
+                log::warn!("failed to save store {:?}: {}", store.path, err);
+            }
+        }
+    }
+
+    /// Blocks until the save of `generation`, or a newer one, is finished.
+    pub(crate) fn wait(&self, generation: u64) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        while state.saved < generation {
+            state = self.saved.wait(state).unwrap();
+        }
+        match &state.error {
+            Some(err) => Err(Error::Io(io::Error::new(io::ErrorKind::Other, err.clone()))),
+            None => Ok(()),
+        }
+    }
+}
@@ -2,31 +2,233 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use crate::{ChangePayload, Error};
+use crate::{
+    encryption::{self, KeyProvider},
+    format::{deserialize_json, serialize_json, DeserializeFn, SerializeFn},
+    key_path,
+    save_queue::SaveQueue,
+    ChangePayload, ChangesPayload, ClearPayload, Error, Format, KeyPath, StoreCollection,
+    Subscriptions,
+};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::{
-    collections::HashMap,
-    fs::{create_dir_all, read, File},
-    io::Write,
-    path::PathBuf,
+    borrow::Cow,
+    collections::{hash_map::DefaultHasher, HashMap},
+    ffi::OsString,
+    fs::{copy, create_dir_all, read, remove_file, File},
+    future::Future,
+    hash::{Hash, Hasher},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use tauri::{AppHandle, Manager, Runtime};
 
-type SerializeFn =
-    fn(&HashMap<String, JsonValue>) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>>;
-type DeserializeFn =
-    fn(&[u8]) -> Result<HashMap<String, JsonValue>, Box<dyn std::error::Error + Send + Sync>>;
+type Migration = fn(&mut HashMap<String, JsonValue>);
+type ChangeListener = Box<dyn Fn(&ChangePayload<'_>) + Send + Sync>;
+
+/// The key under which the metadata of a store is saved alongside its entries. It is only
+/// present if there is any metadata, so stores that don't use it keep their format.
+const META_KEY: &str = "__tauri_store_meta__";
+
+/// Fails with [`Error::ReservedKey`] for [`META_KEY`], which would be overwritten by the
+/// metadata when saving.
+fn check_key(key: &str) -> Result<(), Error> {
+    if key == META_KEY {
+        Err(Error::ReservedKey(key.into()))
+    } else {
+        Ok(())
+    }
+}
+
+/// The metadata of a store's entries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Meta {
+    /// When entries expire, in milliseconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    expirations: HashMap<String, u64>,
+    /// When entries were last used, for stores with a maximum number of entries.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    accessed_at: HashMap<String, u64>,
+    /// The version of the last migration that ran on the entries.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    version: u32,
+}
+
+impl Meta {
+    fn is_empty(&self) -> bool {
+        self.expirations.is_empty() && self.accessed_at.is_empty() && self.version == 0
+    }
+}
+
+/// When entries were last used, in milliseconds since the Unix epoch. Entries used in the same
+/// millisecond get increasing times so they can still be ordered.
+#[derive(Debug, Default)]
+struct AccessTimes(Mutex<(HashMap<String, u64>, u64)>);
+
+impl Clone for AccessTimes {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.0.lock().unwrap().clone()))
+    }
+}
+
+impl AccessTimes {
+    fn touch(&self, key: &str) {
+        let mut access_times = self.0.lock().unwrap();
+        let (times, latest) = &mut *access_times;
+        *latest = now_millis().max(*latest + 1);
+        times.insert(key.into(), *latest);
+    }
+
+    fn extend(&self, accessed_at: HashMap<String, u64>) {
+        let mut access_times = self.0.lock().unwrap();
+        let (times, latest) = &mut *access_times;
+        *latest = accessed_at.values().copied().fold(*latest, u64::max);
+        times.extend(accessed_at);
+    }
+
+    fn remove(&self, key: &str) {
+        self.0.lock().unwrap().0.remove(key);
+    }
+
+    fn times(&self) -> HashMap<String, u64> {
+        self.0.lock().unwrap().0.clone()
+    }
+}
+
+fn is_zero(version: &u32) -> bool {
+    *version == 0
+}
+
+/// The suffix of the backup of the previous save.
+const BACKUP_SUFFIX: &str = ".bak";
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    path.into()
+}
+
+fn write_synced(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()
+}
+
+/// Moves `from` over `to`.
+///
+/// On Windows this replaces `to` with `MoveFileExW`, which fails while another process such
+/// as a virus scanner has the file open, so it is retried for a short while.
+fn rename(from: &Path, to: &Path) -> io::Result<()> {
+    #[cfg(windows)]
+    {
+        let mut attempts = 0;
+        loop {
+            match std::fs::rename(from, to) {
+                Err(err) if err.kind() == io::ErrorKind::PermissionDenied && attempts < 10 => {
+                    attempts += 1;
+                    thread::sleep(Duration::from_millis(20));
+                }
+                result => return result,
+            }
+        }
+    }
+    #[cfg(not(windows))]
+    std::fs::rename(from, to)
+}
+
+fn hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Watches the directory of the store file, since saving replaces the file, and reloads
+/// the store registered with the plugin when the file changes.
+fn watch<R: Runtime>(
+    app: AppHandle<R>,
+    path: PathBuf,
+    store_path: PathBuf,
+) -> notify::Result<RecommendedWatcher> {
+    let file_name = store_path.file_name().map(ToOwned::to_owned);
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let event = match event {
+            Ok(event) => event,
+            Err(err) => {
+                log::warn!("failed to watch store {:?}: {}", path, err);
+                return;
+            }
+        };
+        if matches!(event.kind, EventKind::Access(_))
+            || !event
+                .paths
+                .iter()
+                .any(|changed| changed.file_name() == file_name.as_deref())
+        {
+            return;
+        }
+
+        let collection = match app.try_state::<StoreCollection<R>>() {
+            Some(collection) => collection,
+            None => return,
+        };
+        let mut stores = collection.stores.lock().expect("mutex poisoned");
+        if let Some(store) = stores.get_mut(&path) {
+            if let Err(err) = store.reload() {
+                log::warn!("failed to reload store {:?}: {}", path, err);
+            }
+        }
+    })?;
+    let dir = store_path.parent().expect("invalid store path");
+    create_dir_all(dir)?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// The directory relative store paths are resolved against.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BaseDirectory {
+    /// The app data directory.
+    #[default]
+    AppData,
+    /// The app config directory.
+    AppConfig,
+    /// The resource directory of the app bundle.
+    Resource,
+    /// The directory of the executable, for portable apps.
+    Executable,
+    /// A custom directory.
+    Custom(PathBuf),
+}
 
-fn default_serialize(
-    cache: &HashMap<String, JsonValue>,
-) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
-    Ok(serde_json::to_vec(&cache)?)
+impl From<PathBuf> for BaseDirectory {
+    fn from(dir: PathBuf) -> Self {
+        Self::Custom(dir)
+    }
 }
 
-fn default_deserialize(
-    bytes: &[u8],
-) -> Result<HashMap<String, JsonValue>, Box<dyn std::error::Error + Send + Sync>> {
-    serde_json::from_slice(bytes).map_err(Into::into)
+/// How [`Store::import`] applies the imported entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+    /// Removes the entries that aren't imported.
+    Replace,
+    /// Keeps the entries that aren't imported.
+    Merge,
 }
 
 /// Builds a [`Store`]
@@ -37,6 +239,14 @@ pub struct StoreBuilder<R: Runtime> {
     cache: HashMap<String, JsonValue>,
     serialize: SerializeFn,
     deserialize: DeserializeFn,
+    auto_save: Option<Duration>,
+    key_provider: Option<KeyProvider>,
+    merge_defaults: bool,
+    migrations: Vec<(u32, Migration)>,
+    read_only: bool,
+    base_dir: Option<BaseDirectory>,
+    watch: bool,
+    max_entries: Option<usize>,
 }
 
 impl<R: Runtime> StoreBuilder<R> {
@@ -58,8 +268,16 @@ impl<R: Runtime> StoreBuilder<R> {
             path,
             defaults: None,
             cache: Default::default(),
-            serialize: default_serialize,
-            deserialize: default_deserialize,
+            serialize: serialize_json,
+            deserialize: deserialize_json,
+            auto_save: None,
+            key_provider: None,
+            merge_defaults: false,
+            migrations: Vec::new(),
+            read_only: false,
+            base_dir: None,
+            watch: false,
+            max_entries: None,
         }
     }
 
@@ -106,6 +324,149 @@ impl<R: Runtime> StoreBuilder<R> {
         self
     }
 
+    /// Fills the keys missing from the store with their defaults every time it is loaded,
+    /// so keys added by a new version of the app get their default value in existing stores.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::StoreBuilder;
+    ///
+    /// let builder = StoreBuilder::new("store.bin".parse()?)
+    ///   .default("theme".to_string(), "dark".into())
+    ///   .merge_defaults();
+    ///
+    /// # Ok(())
+    /// # }
+    pub fn merge_defaults(mut self) -> Self {
+        self.merge_defaults = true;
+        self
+    }
+
+    /// Registers a migration that runs once on stores saved before `version`, in ascending
+    /// order of version. The version of the last migration is saved with the store.
+    ///
+    /// Migrations run on the loaded entries before the defaults are merged into them.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::StoreBuilder;
+    ///
+    /// let builder = StoreBuilder::new("store.bin".parse()?)
+    ///   .migrate(1, |cache| {
+    ///     if let Some(value) = cache.remove("darkMode") {
+    ///       cache.insert("theme".to_string(), if value == true { "dark" } else { "light" }.into());
+    ///     }
+    ///   });
+    ///
+    /// # Ok(())
+    /// # }
+    pub fn migrate(mut self, version: u32, migration: Migration) -> Self {
+        self.migrations.push((version, migration));
+        self.migrations.sort_by_key(|(version, _)| *version);
+        self
+    }
+
+    /// Makes the store read-only, so changing or saving it fails with [`Error::ReadOnly`].
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::StoreBuilder;
+    ///
+    /// let builder = StoreBuilder::new("presets.json".parse()?)
+    ///   .read_only(true);
+    ///
+    /// # Ok(())
+    /// # }
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Resolves relative store paths against the given directory instead of the app data directory.
+    ///
+    /// Overrides the plugin's [default base directory](crate::Builder::default_base_dir).
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::{BaseDirectory, StoreBuilder};
+    ///
+    /// let builder = StoreBuilder::new("settings.json".parse()?)
+    ///   .base_dir(BaseDirectory::Executable);
+    ///
+    /// # Ok(())
+    /// # }
+    pub fn base_dir(mut self, base_dir: impl Into<BaseDirectory>) -> Self {
+        self.base_dir = Some(base_dir.into());
+        self
+    }
+
+    /// Resolves the store path relative to the resource directory, for stores bundled with the app.
+    /// These are usually also [read-only](Self::read_only).
+    ///
+    /// This is a shorthand for `base_dir(BaseDirectory::Resource)`.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::StoreBuilder;
+    ///
+    /// let builder = StoreBuilder::new("presets.json".parse()?)
+    ///   .resource_dir()
+    ///   .read_only(true);
+    ///
+    /// # Ok(())
+    /// # }
+    pub fn resource_dir(self) -> Self {
+        self.base_dir(BaseDirectory::Resource)
+    }
+
+    /// Reloads the store when its file is changed by another program, like a sync client,
+    /// emitting change events for the keys that differ.
+    ///
+    /// The last write wins: changes that weren't saved yet are discarded when the file changes,
+    /// and saving the store overwrites changes to the file that weren't reloaded yet.
+    ///
+    /// This only works for stores that are registered with the plugin.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::StoreBuilder;
+    ///
+    /// let builder = StoreBuilder::new("store.json".parse()?)
+    ///   .watch(true);
+    ///
+    /// # Ok(())
+    /// # }
+    pub fn watch(mut self, watch: bool) -> Self {
+        self.watch = watch;
+        self
+    }
+
+    /// Limits the number of entries, removing the least recently used ones when more are inserted.
+    ///
+    /// Getting or inserting an entry counts as using it. When entries were last used is saved with
+    /// the store, so the order is kept across restarts.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::StoreBuilder;
+    ///
+    /// let builder = StoreBuilder::new("thumbnails.json".parse()?)
+    ///   .max_entries(1000);
+    ///
+    /// # Ok(())
+    /// # }
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
     /// Defines a custom serialization function.
     ///
     /// # Examples
@@ -140,6 +501,77 @@ impl<R: Runtime> StoreBuilder<R> {
         self
     }
 
+    /// Saves the store in one of the built-in formats, replacing any custom
+    /// serialization and deserialization functions.
+    ///
+    /// Stores saved in another built-in format are still loaded and are saved in this format
+    /// the next time, so the format of an existing store can be changed.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::{Format, StoreBuilder};
+    ///
+    /// let builder = StoreBuilder::new("store.json".parse()?)
+    ///   .format(Format::PrettyJson);
+    ///
+    /// # Ok(())
+    /// # }
+    pub fn format(mut self, format: Format) -> Self {
+        self.serialize = format.serialize_fn();
+        self.deserialize = format.deserialize_fn();
+        self
+    }
+
+    /// Encrypts the store file with XChaCha20-Poly1305, using the 32-byte key returned by `key_provider`.
+    ///
+    /// The key is requested every time the store is loaded or saved, so it can be kept in the OS keychain.
+    /// Unencrypted stores are still loaded and are encrypted the next time they are saved.
+    /// A store whose file can't be decrypted, with a wrong key or without any, refuses to be
+    /// saved until it was loaded successfully, so the encrypted entries aren't overwritten.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::StoreBuilder;
+    ///
+    /// let builder = StoreBuilder::new("store.bin".parse()?)
+    ///   .encrypt_with(|| Ok([0; 32]));
+    ///
+    /// # Ok(())
+    /// # }
+    pub fn encrypt_with<F>(mut self, key_provider: F) -> Self
+    where
+        F: Fn() -> Result<[u8; 32], Box<dyn std::error::Error + Send + Sync>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.key_provider = Some(Arc::new(key_provider));
+        self
+    }
+
+    /// Saves the store once it hasn't been changed for the given duration, so changes
+    /// made in quick succession are written at once.
+    ///
+    /// This only works for stores that are registered with the plugin.
+    ///
+    /// # Examples
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// use tauri_plugin_store::StoreBuilder;
+    /// use std::time::Duration;
+    ///
+    /// let builder = StoreBuilder::new("store.json".parse()?)
+    ///   .auto_save(Duration::from_millis(100));
+    ///
+    /// # Ok(())
+    /// # }
+    pub fn auto_save(mut self, debounce_duration: Duration) -> Self {
+        self.auto_save = Some(debounce_duration);
+        self
+    }
+
     /// Builds the [`Store`].
     ///
     /// # Examples
@@ -159,6 +591,30 @@ impl<R: Runtime> StoreBuilder<R> {
             cache: self.cache,
             serialize: self.serialize,
             deserialize: self.deserialize,
+            auto_save: self.auto_save,
+            key_provider: self.key_provider,
+            auto_save_deadline: Default::default(),
+            save_queue: Default::default(),
+            meta: Meta {
+                // new stores don't need any migration
+                version: self
+                    .migrations
+                    .last()
+                    .map(|(version, _)| *version)
+                    .unwrap_or_default(),
+                ..Default::default()
+            },
+            merge_defaults: self.merge_defaults,
+            migrations: self.migrations,
+            read_only: self.read_only,
+            base_dir: self.base_dir,
+            listeners: Default::default(),
+            file_hash: Default::default(),
+            watcher: None,
+            max_entries: self.max_entries,
+            accessed: Default::default(),
+            watch: self.watch,
+            key_error: None,
         }
     }
 }
@@ -171,116 +627,643 @@ pub struct Store<R: Runtime> {
     cache: HashMap<String, JsonValue>,
     serialize: SerializeFn,
     deserialize: DeserializeFn,
+    auto_save: Option<Duration>,
+    key_provider: Option<KeyProvider>,
+    /// When the pending auto save is due, shared between clones of the store.
+    auto_save_deadline: Arc<Mutex<Option<Instant>>>,
+    /// Orders the saves of the store and its clones.
+    save_queue: Arc<SaveQueue<R>>,
+    meta: Meta,
+    merge_defaults: bool,
+    migrations: Vec<(u32, Migration)>,
+    read_only: bool,
+    base_dir: Option<BaseDirectory>,
+    listeners: Arc<Mutex<Vec<ChangeListener>>>,
+    /// The hash of the file as it was last read or written by this store.
+    file_hash: Arc<Mutex<Option<u64>>>,
+    watch: bool,
+    watcher: Option<Arc<Mutex<RecommendedWatcher>>>,
+    max_entries: Option<usize>,
+    accessed: AccessTimes,
+    /// The error the file couldn't be decrypted with when the store was loaded, if any.
+    /// Saving would replace the encrypted entries with the ones in memory, so it's refused.
+    key_error: Option<fn(PathBuf) -> Error>,
 }
 
 impl<R: Runtime> Store<R> {
     /// Update the store from the on-disk state
     pub fn load(&mut self) -> Result<(), Error> {
-        let app_dir = self
-            .app
-            .path_resolver()
-            .app_data_dir()
-            .expect("failed to resolve app dir");
-        let store_path = app_dir.join(&self.path);
+        let store_path = self.path();
+        if self.watch && self.watcher.is_none() {
+            match watch(self.app.clone(), self.path.clone(), store_path.clone()) {
+                Ok(watcher) => self.watcher = Some(Arc::new(Mutex::new(watcher))),
+                Err(err) => log::warn!("failed to watch store {:?}: {}", self.path, err),
+            }
+        }
 
-        let bytes = read(store_path)?;
+        let mut cache = match self.read_file(&store_path) {
+            Ok((cache, hash)) => {
+                *self.file_hash.lock().unwrap() = Some(hash);
+                cache
+            }
+            Err(Error::Io(err)) => return Err(Error::Io(err)),
+            // the store is unreadable, fall back to the previous save
+            Err(err) => match self.read_file(&with_suffix(&store_path, BACKUP_SUFFIX)) {
+                Ok((cache, _)) => {
+                    log::warn!(
+                        "failed to load store {:?}: {}. Restored the previous save from its backup.",
+                        self.path,
+                        err
+                    );
+                    cache
+                }
+                Err(_) => {
+                    self.key_error = match err {
+                        Error::MissingKey(_) => Some(Error::MissingKey),
+                        Error::Decrypt(_) => Some(Error::Decrypt),
+                        _ => None,
+                    };
+                    return Err(err);
+                }
+            },
+        };
+        self.key_error = None;
+        let meta = self.parse_meta(cache.remove(META_KEY));
+        let migrated = self.run_migrations(meta.version, &mut cache);
 
-        self.cache
-            .extend((self.deserialize)(&bytes).map_err(Error::Deserialize)?);
+        self.cache.extend(cache);
+        self.meta.expirations.extend(meta.expirations);
+        if self.max_entries.is_some() {
+            self.accessed.extend(meta.accessed_at);
+        }
+        if self.merge_defaults {
+            if let Some(defaults) = &self.defaults {
+                for (key, value) in defaults {
+                    self.cache
+                        .entry(key.clone())
+                        .or_insert_with(|| value.clone());
+                }
+            }
+        }
+        self.remove_expired();
+        if migrated {
+            self.trigger_auto_save();
+        }
 
         Ok(())
     }
 
-    /// Saves the store to disk
-    pub fn save(&self) -> Result<(), Error> {
-        let app_dir = self
-            .app
-            .path_resolver()
-            .app_data_dir()
-            .expect("failed to resolve app dir");
-        let store_path = app_dir.join(&self.path);
+    /// The absolute path of the store file.
+    ///
+    /// Relative store paths are resolved against the [base directory](StoreBuilder::base_dir).
+    pub fn path(&self) -> PathBuf {
+        let path_resolver = self.app.path_resolver();
+        let dir = match self.base_dir.as_ref().unwrap_or(&BaseDirectory::AppData) {
+            BaseDirectory::AppData => path_resolver
+                .app_data_dir()
+                .expect("failed to resolve app dir"),
+            BaseDirectory::AppConfig => path_resolver
+                .app_config_dir()
+                .expect("failed to resolve app config dir"),
+            BaseDirectory::Resource => path_resolver
+                .resource_dir()
+                .expect("failed to resolve resource dir"),
+            BaseDirectory::Executable => std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(ToOwned::to_owned))
+                .expect("failed to resolve executable dir"),
+            BaseDirectory::Custom(dir) => dir.clone(),
+        };
+        dir.join(&self.path)
+    }
 
-        create_dir_all(store_path.parent().expect("invalid store path"))?;
+    /// Sets the base directory if the store doesn't have one.
+    pub(crate) fn default_base_dir(&mut self, base_dir: &BaseDirectory) {
+        self.base_dir.get_or_insert_with(|| base_dir.clone());
+    }
+
+    /// Whether the store rejects changes and saves.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Parses the metadata saved alongside the entries. Metadata that can't be parsed is
+    /// dropped rather than failing the load, as the entries are still usable without it.
+    fn parse_meta(&self, meta: Option<JsonValue>) -> Meta {
+        match meta.map(serde_json::from_value) {
+            Some(Ok(meta)) => meta,
+            Some(Err(err)) => {
+                log::warn!(
+                    "ignoring invalid metadata of store {:?}: {}",
+                    self.path,
+                    err
+                );
+                Default::default()
+            }
+            None => Default::default(),
+        }
+    }
+
+    /// Fails like [`Store::check_writable`], or with the error the store couldn't be
+    /// decrypted with when it was loaded.
+    fn check_savable(&self) -> Result<(), Error> {
+        self.check_writable()?;
+        match self.key_error {
+            Some(error) => Err(error(self.path.clone())),
+            None => Ok(()),
+        }
+    }
+
+    fn check_writable(&self) -> Result<(), Error> {
+        if self.read_only {
+            Err(Error::ReadOnly(self.path.clone()))
+        } else {
+            Ok(())
+        }
+    }
 
-        let bytes = (self.serialize)(&self.cache).map_err(Error::Serialize)?;
-        let mut f = File::create(&store_path)?;
-        f.write_all(&bytes)?;
+    /// Runs the migrations newer than `version` on the loaded entries, returning whether any ran.
+    fn run_migrations(&mut self, version: u32, cache: &mut HashMap<String, JsonValue>) -> bool {
+        let mut migrated = false;
+        for (migration_version, migration) in &self.migrations {
+            if *migration_version > version {
+                migration(cache);
+                migrated = true;
+            }
+        }
+        self.meta.version = self.meta.version.max(version);
+        migrated
+    }
 
+    /// Reloads the store if its file was changed by another program, emitting change events
+    /// for the keys that differ. Unsaved changes are discarded.
+    fn reload(&mut self) -> Result<(), Error> {
+        let store_path = self.path();
+        let (mut cache, hash) = match self.read_file(&store_path) {
+            Ok(read) => read,
+            // the file is being replaced
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        self.key_error = None;
+        {
+            let mut file_hash = self.file_hash.lock().unwrap();
+            // written by this store
+            if *file_hash == Some(hash) {
+                return Ok(());
+            }
+            *file_hash = Some(hash);
+        }
+
+        let meta = self.parse_meta(cache.remove(META_KEY));
+        self.run_migrations(meta.version, &mut cache);
+        self.meta.expirations = meta.expirations;
+        if self.max_entries.is_some() {
+            self.accessed.extend(meta.accessed_at);
+        }
+
+        let old_cache = std::mem::replace(&mut self.cache, cache);
+        self.remove_expired();
+        for (key, old_value) in &old_cache {
+            if !self.cache.contains_key(key) {
+                self.emit_change(key, Some(old_value), None)?;
+            }
+        }
+        for (key, value) in &self.cache {
+            let old_value = old_cache.get(key);
+            if old_value != Some(value) {
+                self.emit_change(key, old_value, Some(value))?;
+            }
+        }
         Ok(())
     }
 
-    pub fn insert(&mut self, key: String, value: JsonValue) -> Result<(), Error> {
-        self.cache.insert(key.clone(), value.clone());
-        self.app.emit_all(
-            "store://change",
-            ChangePayload {
-                path: &self.path,
-                key: &key,
-                value: &value,
+    /// Reads and decodes the store file at `path`, returning it with the hash of the file.
+    fn read_file(&self, path: &Path) -> Result<(HashMap<String, JsonValue>, u64), Error> {
+        let mut bytes = read(path)?;
+        let hash = hash(&bytes);
+        if encryption::is_encrypted(&bytes) {
+            bytes = encryption::decrypt(self.key_provider.as_ref(), path, &bytes)?;
+        }
+
+        let cache = match (self.deserialize)(&bytes) {
+            Ok(cache) => Ok(cache),
+            // the store may have been saved in another format before, the next save migrates it
+            Err(err) => match Format::detect(&bytes) {
+                Some(format) => {
+                    (format.deserialize_fn())(&bytes).map_err(|_| Error::Deserialize(err))?
+                }
+                None => return Err(Error::Deserialize(err)),
             },
-        )?;
+        };
+        Ok((cache, hash))
+    }
+
+    /// Saves a snapshot of the store to disk on a background thread, cancelling a pending
+    /// auto save.
+    ///
+    /// The snapshot is taken right away, so the store can be unlocked before awaiting the
+    /// returned future, which resolves once the snapshot or a newer one is saved. Snapshots
+    /// queued while another one is being saved are coalesced into a single write.
+    ///
+    /// The store is written to a temporary file that replaces the old one once it's complete,
+    /// so a crash can't leave a truncated store behind. The previous save is kept as a backup
+    /// that is loaded if the store can't be read.
+    pub fn save(&self) -> impl Future<Output = Result<(), Error>> + Send + 'static {
+        let queue = self.save_queue.clone();
+        let generation = self.request_save();
+        async move {
+            let generation = generation?;
+            tauri::async_runtime::spawn_blocking(move || queue.wait(generation)).await?
+        }
+    }
+
+    /// Like [`Store::save`], but writes the store on the current thread and returns once it's
+    /// saved, e.g. where no async runtime is available.
+    pub fn save_blocking(&self) -> Result<(), Error> {
+        self.check_savable()?;
+        self.auto_save_deadline.lock().unwrap().take();
+
+        let generation = self.save_queue.request();
+        self.save_queue.write(generation, self)
+    }
+
+    /// Takes a snapshot of the store to write with [`Snapshot::write`] once the store is
+    /// unlocked, cancelling a pending auto save.
+    pub(crate) fn snapshot(&self) -> Result<Snapshot<R>, Error> {
+        self.check_savable()?;
+        self.auto_save_deadline.lock().unwrap().take();
+        Ok(Snapshot {
+            generation: self.save_queue.request(),
+            store: self.clone(),
+        })
+    }
+
+    /// Whether the entries are those of `snapshot`, i.e. nothing changed since it was taken.
+    pub(crate) fn matches(&self, snapshot: &Snapshot<R>) -> bool {
+        self.cache == snapshot.store.cache
+    }
+
+    /// Queues a snapshot of the store to be saved in the background, returning its generation.
+    pub(crate) fn request_save(&self) -> Result<u64, Error> {
+        self.check_savable()?;
+        self.auto_save_deadline.lock().unwrap().take();
+        Ok(self.save_queue.push(self.clone()))
+    }
+
+    /// Whether the store or a clone of it is being saved.
+    pub(crate) fn is_saving(&self) -> bool {
+        self.save_queue.is_saving()
+    }
+
+    pub(crate) fn write(&self) -> Result<(), Error> {
+        let store_path = self.path();
+
+        create_dir_all(store_path.parent().expect("invalid store path"))?;
+
+        let mut bytes = (self.serialize)(&self.persisted()?).map_err(Error::Serialize)?;
+        if let Some(key_provider) = &self.key_provider {
+            bytes = encryption::encrypt(key_provider, &bytes)?;
+        }
+
+        let temp_path = with_suffix(&store_path, &format!(".tmp-{}", process::id()));
+        if let Err(err) = write_synced(&temp_path, &bytes) {
+            let _ = remove_file(&temp_path);
+            return Err(err.into());
+        }
+        if store_path.exists() {
+            if let Err(err) = copy(&store_path, with_suffix(&store_path, BACKUP_SUFFIX)) {
+                log::warn!("failed to back up store {:?}: {}", self.path, err);
+            }
+        }
+        // set before replacing the file so the watcher doesn't reload it
+        *self.file_hash.lock().unwrap() = Some(hash(&bytes));
+        rename(&temp_path, &store_path).map_err(|err| {
+            let _ = remove_file(&temp_path);
+            err.into()
+        })
+    }
+
+    /// The entries to save, without expired ones and with the metadata if there is any.
+    fn persisted(&self) -> Result<Cow<'_, HashMap<String, JsonValue>>, Error> {
+        if self.meta.is_empty() && self.max_entries.is_none() {
+            return Ok(Cow::Borrowed(&self.cache));
+        }
+
+        let now = now_millis();
+        let mut meta = self.meta.clone();
+        meta.expirations.retain(|_, expires_at| *expires_at > now);
+        let mut cache: HashMap<String, JsonValue> = self
+            .entries()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if self.max_entries.is_some() {
+            meta.accessed_at = self.accessed.times();
+            meta.accessed_at.retain(|key, _| cache.contains_key(key));
+        }
+        cache.insert(META_KEY.into(), serde_json::to_value(meta)?);
+        Ok(Cow::Owned(cache))
+    }
+
+    pub fn insert(&mut self, key: String, value: JsonValue) -> Result<(), Error> {
+        self.check_writable()?;
+        check_key(&key)?;
+        self.meta.expirations.remove(&key);
+        let old_value = self.cache.insert(key.clone(), value.clone());
+        self.touch(&key);
+        self.trigger_auto_save();
+        self.emit_change(&key, old_value.as_ref(), Some(&value))?;
+        self.evict()
+    }
+
+    /// Inserts multiple key-value pairs at once, emitting a single `store://changes` event
+    /// and counting as one change for the auto save.
+    pub fn set_many<I: IntoIterator<Item = (String, JsonValue)>>(
+        &mut self,
+        entries: I,
+    ) -> Result<(), Error> {
+        self.check_writable()?;
+        let entries: Vec<_> = entries.into_iter().collect();
+        for (key, _) in &entries {
+            check_key(key)?;
+        }
+        let mut changes = Vec::new();
+        for (key, value) in entries {
+            self.meta.expirations.remove(&key);
+            let old_value = self.cache.insert(key.clone(), value);
+            self.touch(&key);
+            changes.push((key, old_value));
+        }
+        if changes.is_empty() {
+            return Ok(());
+        }
+        self.trigger_auto_save();
+        self.emit_changes(&changes)?;
+        self.evict()
+    }
+
+    /// Merges the top-level keys of a JSON object into the store, like [`Store::set_many`].
+    pub fn patch(&mut self, object: serde_json::Map<String, JsonValue>) -> Result<(), Error> {
+        self.set_many(object)
+    }
 
+    /// Writes the entries of the store as pretty-printed JSON to `destination`, including
+    /// changes that weren't saved yet.
+    pub fn export(&self, destination: impl AsRef<Path>) -> Result<(), Error> {
+        let entries: HashMap<&String, &JsonValue> = self.entries().collect();
+        std::fs::write(destination, serde_json::to_vec_pretty(&entries)?)?;
         Ok(())
     }
 
+    /// Imports the entries of a JSON file, like one written by [`Store::export`], emitting
+    /// a single `store://changes` event. The store is left untouched if the file is invalid.
+    pub fn import(&mut self, source: impl AsRef<Path>, mode: ImportMode) -> Result<(), Error> {
+        self.check_writable()?;
+        let mut entries: HashMap<String, JsonValue> = serde_json::from_slice(&read(source)?)?;
+        entries.remove(META_KEY);
+
+        match mode {
+            ImportMode::Merge => self.set_many(entries),
+            ImportMode::Replace => {
+                let old_cache = std::mem::take(&mut self.cache);
+                self.meta.expirations.clear();
+                let mut changes: Vec<(String, Option<JsonValue>)> = old_cache
+                    .iter()
+                    .filter(|(key, _)| !entries.contains_key(*key))
+                    .map(|(key, old_value)| (key.clone(), Some(old_value.clone())))
+                    .collect();
+                for (key, value) in entries {
+                    self.touch(&key);
+                    changes.push((key.clone(), old_cache.get(&key).cloned()));
+                    self.cache.insert(key, value);
+                }
+                if changes.is_empty() {
+                    return Ok(());
+                }
+                self.trigger_auto_save();
+                self.emit_changes(&changes)?;
+                self.evict()
+            }
+        }
+    }
+
+    /// Inserts a key-value pair that is treated as absent once `ttl` has passed.
+    ///
+    /// Expired entries are removed when the store is loaded or [`Store::prune`] is called,
+    /// and aren't saved.
+    pub fn insert_with_ttl(
+        &mut self,
+        key: String,
+        value: JsonValue,
+        ttl: Duration,
+    ) -> Result<(), Error> {
+        self.insert(key.clone(), value)?;
+        let expires_at = now_millis().saturating_add(ttl.as_millis() as u64);
+        self.meta.expirations.insert(key, expires_at);
+        Ok(())
+    }
+
+    fn is_expired(&self, key: &str) -> bool {
+        self.meta
+            .expirations
+            .get(key)
+            .map_or(false, |expires_at| *expires_at <= now_millis())
+    }
+
+    /// Removes the expired entries, emitting a change event for each.
+    pub fn prune(&mut self) -> Result<(), Error> {
+        self.check_writable()?;
+        for (key, old_value) in self.remove_expired() {
+            self.emit_change(&key, old_value.as_ref(), None)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the expired entries without emitting change events, returning them.
+    fn remove_expired(&mut self) -> Vec<(String, Option<JsonValue>)> {
+        let now = now_millis();
+        let expired: Vec<String> = self
+            .meta
+            .expirations
+            .iter()
+            .filter(|(_, expires_at)| **expires_at <= now)
+            .map(|(key, _)| key.clone())
+            .collect();
+        expired
+            .into_iter()
+            .map(|key| {
+                self.meta.expirations.remove(&key);
+                let value = self.cache.remove(&key);
+                (key, value)
+            })
+            .collect()
+    }
+
     pub fn get(&self, key: impl AsRef<str>) -> Option<&JsonValue> {
-        self.cache.get(key.as_ref())
+        let key = key.as_ref();
+        let value = self.cache.get(key).filter(|_| !self.is_expired(key));
+        if value.is_some() {
+            self.touch(key);
+        }
+        value
+    }
+
+    /// Gets the values of multiple keys, in the same order.
+    pub fn get_many<I: IntoIterator<Item = K>, K: AsRef<str>>(
+        &self,
+        keys: I,
+    ) -> Vec<Option<&JsonValue>> {
+        keys.into_iter().map(|key| self.get(key)).collect()
     }
 
     pub fn has(&self, key: impl AsRef<str>) -> bool {
-        self.cache.contains_key(key.as_ref())
+        self.get(key).is_some()
     }
 
     pub fn delete(&mut self, key: impl AsRef<str>) -> Result<bool, Error> {
-        let flag = self.cache.remove(key.as_ref()).is_some();
+        self.check_writable()?;
+        self.meta.expirations.remove(key.as_ref());
+        let old_value = self.cache.remove(key.as_ref());
+        let flag = old_value.is_some();
         if flag {
-            self.app.emit_all(
-                "store://change",
-                ChangePayload {
-                    path: &self.path,
-                    key: key.as_ref(),
-                    value: &JsonValue::Null,
-                },
-            )?;
+            self.trigger_auto_save();
+            self.emit_change(key.as_ref(), old_value.as_ref(), None)?;
         }
         Ok(flag)
     }
 
-    pub fn clear(&mut self) -> Result<(), Error> {
-        let keys: Vec<String> = self.cache.keys().cloned().collect();
-        self.cache.clear();
-        for key in keys {
-            self.app.emit_all(
-                "store://change",
-                ChangePayload {
-                    path: &self.path,
-                    key: &key,
-                    value: &JsonValue::Null,
-                },
-            )?;
+    /// Gets the value at a path nested in an entry, see [`KeyPath`].
+    ///
+    /// Fails if the path goes through a value that is not an object or array.
+    pub fn get_path(&self, path: impl Into<KeyPath>) -> Result<Option<&JsonValue>, Error> {
+        let path = path.into();
+        let segments = path.segments()?;
+        match self.get(segments[0]) {
+            Some(value) => key_path::get(&path, value, &segments[1..]),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets the value at a path nested in an entry, creating missing objects on the way.
+    ///
+    /// Fails if the path goes through a value that is not an object or array, or through an
+    /// array index that is out of bounds.
+    pub fn set_path(&mut self, path: impl Into<KeyPath>, value: JsonValue) -> Result<(), Error> {
+        self.check_writable()?;
+        let path = path.into();
+        let segments = path.segments()?;
+        let key = segments[0];
+        check_key(key)?;
+        let old_value = self.get(key).cloned();
+        let mut new_value = old_value.clone().unwrap_or(JsonValue::Null);
+        key_path::set(&path, &mut new_value, &segments[1..], value)?;
+        self.replace(key, old_value, new_value)
+    }
+
+    /// Removes the value at a path nested in an entry, returning whether it existed.
+    ///
+    /// Fails if the path goes through a value that is not an object or array.
+    pub fn delete_path(&mut self, path: impl Into<KeyPath>) -> Result<bool, Error> {
+        self.check_writable()?;
+        let path = path.into();
+        let segments = path.segments()?;
+        let key = segments[0];
+        if segments.len() == 1 {
+            return self.delete(key);
+        }
+
+        let old_value = match self.get(key) {
+            Some(old_value) => old_value.clone(),
+            None => return Ok(false),
+        };
+        let mut new_value = old_value.clone();
+        if key_path::delete(&path, &mut new_value, &segments[1..])?.is_none() {
+            return Ok(false);
+        }
+        self.replace(key, Some(old_value), new_value)?;
+        Ok(true)
+    }
+
+    /// Replaces an entry with a modified copy, keeping its expiration unless it had expired.
+    fn replace(
+        &mut self,
+        key: &str,
+        old_value: Option<JsonValue>,
+        value: JsonValue,
+    ) -> Result<(), Error> {
+        if old_value.is_none() {
+            self.meta.expirations.remove(key);
+        }
+        self.cache.insert(key.to_string(), value.clone());
+        self.touch(key);
+        self.trigger_auto_save();
+        self.emit_change(key, old_value.as_ref(), Some(&value))?;
+        self.evict()
+    }
+
+    /// Marks an entry as used, if the store has a maximum number of entries.
+    fn touch(&self, key: &str) {
+        if self.max_entries.is_some() {
+            self.accessed.touch(key);
+        }
+    }
+
+    /// Removes the least recently used entries while there are more than the maximum,
+    /// emitting a change event for each.
+    fn evict(&mut self) -> Result<(), Error> {
+        let max_entries = match self.max_entries {
+            Some(max_entries) => max_entries,
+            None => return Ok(()),
+        };
+        let len = self.len();
+        if len <= max_entries {
+            return Ok(());
+        }
+
+        let times = self.accessed.times();
+        let mut keys: Vec<(u64, String)> = self
+            .keys()
+            .map(|key| (times.get(key).copied().unwrap_or_default(), key.clone()))
+            .collect();
+        keys.sort_unstable();
+        for (_, key) in keys.into_iter().take(len - max_entries) {
+            self.meta.expirations.remove(&key);
+            self.accessed.remove(&key);
+            let old_value = self.cache.remove(&key);
+            self.emit_change(&key, old_value.as_ref(), None)?;
         }
         Ok(())
     }
 
+    pub fn clear(&mut self) -> Result<(), Error> {
+        self.check_writable()?;
+        let cache = std::mem::take(&mut self.cache);
+        self.meta.expirations.clear();
+        self.trigger_auto_save();
+        for (key, old_value) in &cache {
+            self.emit_change(key, Some(old_value), None)?;
+        }
+        self.emit_clear(cache.into_keys().collect())
+    }
+
     pub fn reset(&mut self) -> Result<(), Error> {
+        self.check_writable()?;
         let has_defaults = self.defaults.is_some();
 
         if has_defaults {
             if let Some(defaults) = &self.defaults {
+                let mut keys = Vec::new();
                 for (key, value) in &self.cache {
                     if defaults.get(key) != Some(value) {
-                        let _ = self.app.emit_all(
-                            "store://change",
-                            ChangePayload {
-                                path: &self.path,
-                                key,
-                                value: defaults.get(key).unwrap_or(&JsonValue::Null),
-                            },
-                        );
+                        let _ = self.emit_change(key, Some(value), defaults.get(key));
+                        keys.push(key.clone());
                     }
                 }
                 self.cache = defaults.clone();
+                self.meta.expirations.clear();
+                self.emit_clear(keys)?;
             }
+            self.trigger_auto_save();
             Ok(())
         } else {
             self.clear()
@@ -288,23 +1271,178 @@ impl<R: Runtime> Store<R> {
     }
 
     pub fn keys(&self) -> impl Iterator<Item = &String> {
-        self.cache.keys()
+        self.entries().map(|(key, _)| key)
     }
 
     pub fn values(&self) -> impl Iterator<Item = &JsonValue> {
-        self.cache.values()
+        self.entries().map(|(_, value)| value)
     }
 
     pub fn entries(&self) -> impl Iterator<Item = (&String, &JsonValue)> {
-        self.cache.iter()
+        self.cache.iter().filter(|(key, _)| !self.is_expired(key))
     }
 
     pub fn len(&self) -> usize {
-        self.cache.len()
+        self.entries().count()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.cache.is_empty()
+        self.len() == 0
+    }
+
+    /// Calls a function every time an entry of this store changes, including in clones of it.
+    ///
+    /// The function is called while the store is locked, so it must not access the store.
+    pub fn on_change<F: Fn(&ChangePayload<'_>) + Send + Sync + 'static>(&self, f: F) {
+        self.listeners.lock().unwrap().push(Box::new(f));
+    }
+
+    fn emit_change(
+        &self,
+        key: &str,
+        old_value: Option<&JsonValue>,
+        value: Option<&JsonValue>,
+    ) -> Result<(), Error> {
+        let payload = ChangePayload {
+            path: &self.path,
+            key,
+            value: value.unwrap_or(&JsonValue::Null),
+            old_value: old_value.unwrap_or(&JsonValue::Null),
+        };
+        for listener in self.listeners.lock().unwrap().iter() {
+            listener(&payload);
+        }
+        match self.app.try_state::<Subscriptions>() {
+            Some(subscriptions) => subscriptions.emit(&self.app, "store://change", payload),
+            None => self
+                .app
+                .emit_all("store://change", payload)
+                .map_err(Into::into),
+        }
+    }
+
+    /// Emits a single event for the keys changed by a bulk operation, with their previous values.
+    fn emit_changes(&self, changes: &[(String, Option<JsonValue>)]) -> Result<(), Error> {
+        let changes: Vec<ChangePayload<'_>> = changes
+            .iter()
+            .map(|(key, old_value)| ChangePayload {
+                path: &self.path,
+                key,
+                value: self.cache.get(key).unwrap_or(&JsonValue::Null),
+                old_value: old_value.as_ref().unwrap_or(&JsonValue::Null),
+            })
+            .collect();
+        for listener in self.listeners.lock().unwrap().iter() {
+            for change in &changes {
+                listener(change);
+            }
+        }
+
+        let payload = ChangesPayload {
+            path: &self.path,
+            changes,
+        };
+        match self.app.try_state::<Subscriptions>() {
+            Some(subscriptions) => {
+                subscriptions.emit_changes(&self.app, "store://changes", payload)
+            }
+            None => self
+                .app
+                .emit_all("store://changes", payload)
+                .map_err(Into::into),
+        }
+    }
+
+    /// Emits the event for clearing or resetting the store, after the change events, to the
+    /// windows that want at least one of the keys.
+    fn emit_clear(&self, keys: Vec<String>) -> Result<(), Error> {
+        let payload = ClearPayload {
+            path: &self.path,
+            keys,
+        };
+        match self.app.try_state::<Subscriptions>() {
+            Some(subscriptions) => subscriptions.emit_clear(&self.app, "store://clear", payload),
+            None => self
+                .app
+                .emit_all("store://clear", payload)
+                .map_err(Into::into),
+        }
+    }
+
+    /// Pushes the auto save back by the debounce duration, starting a timer if none is pending.
+    fn trigger_auto_save(&self) {
+        let debounce_duration = match self.auto_save {
+            Some(debounce_duration) => debounce_duration,
+            None => return,
+        };
+        let app = self.app.clone();
+        let path = self.path.clone();
+        debounce(&self.auto_save_deadline, debounce_duration, move || {
+            let collection = match app.try_state::<StoreCollection<R>>() {
+                Some(collection) => collection,
+                None => return,
+            };
+            let stores = collection.stores.lock().expect("mutex poisoned");
+            if let Some(store) = stores.get(&path) {
+                if let Err(err) = store.request_save() {
+                    log::warn!("failed to auto save store {:?}: {}", path, err);
+                }
+            }
+        });
+    }
+}
+
+/// Pushes `deadline` back by `duration` and calls `f` on a timer thread once it's due, starting
+/// the thread only if no deadline is pending. Taking the deadline cancels the call.
+fn debounce(
+    deadline: &Arc<Mutex<Option<Instant>>>,
+    duration: Duration,
+    f: impl FnOnce() + Send + 'static,
+) {
+    let mut due = deadline.lock().unwrap();
+    let pending = due.is_some();
+    *due = Some(Instant::now() + duration);
+    if pending {
+        return;
+    }
+
+    let deadline = deadline.clone();
+    thread::spawn(move || {
+        loop {
+            let wait = {
+                let mut deadline = deadline.lock().unwrap();
+                match *deadline {
+                    // cancelled by an explicit save
+                    None => return,
+                    Some(due) => match due.checked_duration_since(Instant::now()) {
+                        Some(wait) => wait,
+                        None => {
+                            *deadline = None;
+                            break;
+                        }
+                    },
+                }
+            };
+            thread::sleep(wait);
+        }
+        f();
+    });
+}
+
+/// A copy of a store taken to be saved without holding the lock on the store.
+pub(crate) struct Snapshot<R: Runtime> {
+    generation: u64,
+    store: Store<R>,
+}
+
+impl<R: Runtime> Snapshot<R> {
+    /// Writes the snapshot unless a newer one was saved in the meantime.
+    pub(crate) fn write(&self) -> Result<(), Error> {
+        self.store.save_queue.write(self.generation, &self.store)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.store.path
     }
 }
 
@@ -314,6 +1452,47 @@ impl<R: Runtime> std::fmt::Debug for Store<R> {
             .field("path", &self.path)
             .field("defaults", &self.defaults)
             .field("cache", &self.cache)
+            .field("meta", &self.meta)
+            .field("encrypted", &self.key_provider.is_some())
+            .field("read_only", &self.read_only)
+            .field("watching", &self.watcher.is_some())
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn rapid_changes_are_saved_once() {
+        let deadline = Arc::new(Mutex::new(None));
+        let writes = Arc::new(AtomicUsize::new(0));
+        for _ in 0..100 {
+            let writes = writes.clone();
+            debounce(&deadline, Duration::from_millis(100), move || {
+                writes.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        thread::sleep(Duration::from_millis(500));
+        assert_eq!(writes.load(Ordering::SeqCst), 1);
+        assert!(deadline.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn explicit_saves_cancel_the_auto_save() {
+        let deadline = Arc::new(Mutex::new(None));
+        let writes = Arc::new(AtomicUsize::new(0));
+        let writes_ = writes.clone();
+        debounce(&deadline, Duration::from_millis(100), move || {
+            writes_.fetch_add(1, Ordering::SeqCst);
+        });
+        // what `Store::save` does before writing
+        deadline.lock().unwrap().take();
+
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(writes.load(Ordering::SeqCst), 0);
+    }
+}
@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/tauri";
-import { WindowLabel, getCurrent } from "@tauri-apps/api/window";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { WindowLabel } from "@tauri-apps/api/window";
 
 export enum StateFlags {
   SIZE = 1 << 0,
@@ -8,31 +9,140 @@ export enum StateFlags {
   VISIBLE = 1 << 3,
   DECORATIONS = 1 << 4,
   FULLSCREEN = 1 << 5,
-  ALL = SIZE | POSITION | MAXIMIZED | VISIBLE | DECORATIONS | FULLSCREEN,
+  ALWAYS_ON_TOP = 1 << 6,
+  ALL = SIZE |
+    POSITION |
+    MAXIMIZED |
+    VISIBLE |
+    DECORATIONS |
+    FULLSCREEN |
+    ALWAYS_ON_TOP,
 }
 
 /**
  *  Save the state of all open windows to disk.
+ *
+ *  @param flags The state to save, defaults to the plugin's default flags.
  */
-async function saveWindowState(flags: StateFlags): Promise<void> {
+async function saveWindowState(flags?: StateFlags): Promise<void> {
   return invoke("plugin:window-state|save_window_state", { flags });
 }
 
 /**
  *  Restore the state for the specified window from disk.
+ *
+ *  Rejects if there is no saved state for the window.
+ *
+ *  @param flags The state to restore, defaults to the plugin's default flags.
  */
 async function restoreState(
   label: WindowLabel,
-  flags: StateFlags,
+  flags?: StateFlags,
 ): Promise<void> {
   return invoke("plugin:window-state|restore_state", { label, flags });
 }
 
 /**
  *  Restore the state for the current window from disk.
+ *
+ *  Rejects if there is no saved state for the window.
+ *
+ *  @param flags The state to restore, defaults to the plugin's default flags.
  */
-async function restoreStateCurrent(flags: StateFlags): Promise<void> {
-  return restoreState(getCurrent().label, flags);
+async function restoreStateCurrent(flags?: StateFlags): Promise<void> {
+  return invoke("plugin:window-state|restore_state", { flags });
 }
 
-export { restoreState, restoreStateCurrent, saveWindowState };
+/**
+ *  Remove the saved state of all windows.
+ *
+ *  @returns Whether there was any saved state.
+ */
+async function clearState(): Promise<boolean> {
+  return invoke("plugin:window-state|clear_state");
+}
+
+/**
+ *  Remove the saved state of the specified window.
+ *
+ *  @returns Whether there was any saved state for the window.
+ */
+async function clearStateFor(label: WindowLabel): Promise<boolean> {
+  return invoke("plugin:window-state|clear_state_for", { label });
+}
+
+/**
+ *  The payload of the event emitted after a window's state was restored.
+ */
+export interface RestoredEvent {
+  label: WindowLabel;
+  position: { x: number; y: number };
+  size: { width: number; height: number };
+  flags: StateFlags;
+}
+
+/**
+ *  The payload of the event emitted after the state was saved to disk.
+ */
+export interface SavedEvent {
+  labels: WindowLabel[];
+  /** The state file, or the directory of per-window files. */
+  path: string;
+}
+
+/**
+ *  The payload of the event emitted when a window's state couldn't be restored.
+ */
+export interface StateErrorEvent {
+  label: WindowLabel;
+  error: string;
+}
+
+/**
+ *  Calls `cb` every time the state of a window was restored.
+ *
+ *  @returns A function to stop listening.
+ */
+async function onRestored(
+  cb: (event: RestoredEvent) => void,
+): Promise<UnlistenFn> {
+  return listen<RestoredEvent>("window-state://restored", (event) => {
+    cb(event.payload);
+  });
+}
+
+/**
+ *  Calls `cb` every time the state was saved to disk.
+ *
+ *  @returns A function to stop listening.
+ */
+async function onSaved(cb: (event: SavedEvent) => void): Promise<UnlistenFn> {
+  return listen<SavedEvent>("window-state://saved", (event) => {
+    cb(event.payload);
+  });
+}
+
+/**
+ *  Calls `cb` every time the state of a window couldn't be restored, e.g. because none
+ *  was saved yet.
+ *
+ *  @returns A function to stop listening.
+ */
+async function onStateError(
+  cb: (event: StateErrorEvent) => void,
+): Promise<UnlistenFn> {
+  return listen<StateErrorEvent>("window-state://error", (event) => {
+    cb(event.payload);
+  });
+}
+
+export {
+  clearState,
+  clearStateFor,
+  onRestored,
+  onSaved,
+  onStateError,
+  restoreState,
+  restoreStateCurrent,
+  saveWindowState,
+};
@@ -1,13 +1,20 @@
-use crate::{AppHandleExt, StateFlags, WindowExt};
-use tauri::{command, AppHandle, Manager, Runtime};
+use crate::{AppHandleExt, StateFlags, WindowExt, WindowState, WindowStateCache};
+use tauri::{command, AppHandle, Manager, Runtime, Window};
+
+fn state_flags(flags: Option<u32>) -> std::result::Result<StateFlags, String> {
+    match flags {
+        Some(flags) => StateFlags::from_bits(flags)
+            .ok_or_else(|| format!("Invalid state flags bits: {}", flags)),
+        None => Ok(StateFlags::default()),
+    }
+}
 
 #[command]
 pub async fn save_window_state<R: Runtime>(
     app: AppHandle<R>,
-    flags: u32,
+    flags: Option<u32>,
 ) -> std::result::Result<(), String> {
-    let flags = StateFlags::from_bits(flags)
-        .ok_or_else(|| format!("Invalid state flags bits: {}", flags))?;
+    let flags = state_flags(flags)?;
     app.save_window_state(flags).map_err(|e| e.to_string())?;
     Ok(())
 }
@@ -15,14 +22,38 @@ pub async fn save_window_state<R: Runtime>(
 #[command]
 pub async fn restore_state<R: Runtime>(
     app: AppHandle<R>,
-    label: String,
-    flags: u32,
+    window: Window<R>,
+    label: Option<String>,
+    flags: Option<u32>,
 ) -> std::result::Result<(), String> {
-    let flags = StateFlags::from_bits(flags)
-        .ok_or_else(|| format!("Invalid state flags bits: {}", flags))?;
+    let flags = state_flags(flags)?;
+    let label = label.unwrap_or_else(|| window.label().into());
+    let has_state = app
+        .state::<WindowStateCache>()
+        .0
+        .lock()
+        .unwrap()
+        .get(&label)
+        .map_or(false, |state| *state != WindowState::default());
+    if !has_state {
+        return Err(format!("No saved state for window with label: {}", label));
+    }
     app.get_window(&label)
         .ok_or_else(|| format!("Couldn't find window with label: {}", label))?
         .restore_state(flags)
         .map_err(|e| e.to_string())?;
     Ok(())
 }
+
+#[command]
+pub async fn clear_state<R: Runtime>(app: AppHandle<R>) -> std::result::Result<bool, String> {
+    app.clear_state().map_err(|e| e.to_string())
+}
+
+#[command]
+pub async fn clear_state_for<R: Runtime>(
+    app: AppHandle<R>,
+    label: String,
+) -> std::result::Result<bool, String> {
+    app.clear_state_for(&label).map_err(|e| e.to_string())
+}
@@ -12,15 +12,27 @@ use tauri::{
 
 use std::{
     collections::{HashMap, HashSet},
-    fs::{create_dir_all, File},
-    io::Write,
+    path::PathBuf,
     sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
 };
 
 mod cmd;
+mod state_file;
+
+use state_file::StateFile;
 
 pub const STATE_FILENAME: &str = ".window-state";
 
+/// The event emitted after a window's state was restored, with a [`Restored`] payload.
+pub const RESTORED_EVENT: &str = "window-state://restored";
+/// The event emitted after the state was saved, with a [`Saved`] payload.
+pub const SAVED_EVENT: &str = "window-state://saved";
+/// The event emitted when a window's state couldn't be restored, with a [`StateError`]
+/// payload.
+pub const ERROR_EVENT: &str = "window-state://error";
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
@@ -31,10 +43,38 @@ pub enum Error {
     TauriApi(#[from] tauri::api::Error),
     #[error(transparent)]
     Bincode(#[from] Box<bincode::ErrorKind>),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The payload of [`RESTORED_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Restored {
+    pub label: String,
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    /// The bits of the [`StateFlags`] that were restored.
+    pub flags: u32,
+}
+
+/// The payload of [`SAVED_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Saved {
+    /// The labels of the windows whose state is saved.
+    pub labels: Vec<String>,
+    /// The state file, or the directory of per-window files.
+    pub path: PathBuf,
+}
+
+/// The payload of [`ERROR_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+pub struct StateError {
+    pub label: String,
+    pub error: String,
+}
+
 bitflags! {
     #[derive(Clone, Copy, Debug)]
     pub struct StateFlags: u32 {
@@ -44,17 +84,70 @@ bitflags! {
         const VISIBLE     = 1 << 3;
         const DECORATIONS = 1 << 4;
         const FULLSCREEN  = 1 << 5;
+        /// Tauri can't read whether a window is always on top, so only changes made through
+        /// [`WindowExt::set_always_on_top_tracked`] are saved, and windows that never went
+        /// through it are left as they are when restored.
+        const ALWAYS_ON_TOP = 1 << 6;
     }
 }
 
 impl Default for StateFlags {
+    /// All flags except [`StateFlags::ALWAYS_ON_TOP`].
     fn default() -> Self {
-        Self::all()
+        Self::all().difference(Self::ALWAYS_ON_TOP)
+    }
+}
+
+/// Where the plugin keeps the state of windows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Persistence {
+    /// In a file, so windows are restored when the app is reopened.
+    #[default]
+    File,
+    /// Only in memory, so windows are restored when they are reopened while the app is
+    /// running but nothing is written to disk.
+    Memory,
+}
+
+/// The monitor a window was on when its state was saved.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+struct MonitorState {
+    name: Option<String>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl MonitorState {
+    /// Whether `monitor` is the saved one, by name or, for unnamed monitors, by its bounds.
+    fn is(&self, monitor: &Monitor) -> bool {
+        match &self.name {
+            Some(name) => monitor.name() == Some(name),
+            None => Self::from(monitor) == *self,
+        }
+    }
+}
+
+impl From<&Monitor> for MonitorState {
+    fn from(monitor: &Monitor) -> Self {
+        let PhysicalPosition { x, y } = *monitor.position();
+        let PhysicalSize { width, height } = *monitor.size();
+        Self {
+            name: monitor.name().cloned(),
+            x,
+            y,
+            width,
+            height,
+        }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
 struct WindowState {
+    /// The size and position are those of the window before it was maximized, so that
+    /// unmaximizing a restored window returns it to them.
     width: f64,
     height: f64,
     x: i32,
@@ -63,6 +156,15 @@ struct WindowState {
     visible: bool,
     decorated: bool,
     fullscreen: bool,
+    /// `None` until set through [`WindowExt::set_always_on_top_tracked`], as Tauri can't read
+    /// it from the window.
+    always_on_top: Option<bool>,
+    /// The scale factor of the window when it was saved, the size is in logical pixels while
+    /// the position is in physical ones.
+    scale_factor: f64,
+    /// The monitor `x` and `y` are relative to, `None` in state files written before it was
+    /// tracked.
+    monitor: Option<MonitorState>,
 }
 
 impl Default for WindowState {
@@ -76,113 +178,402 @@ impl Default for WindowState {
             visible: true,
             decorated: true,
             fullscreen: Default::default(),
+            always_on_top: Default::default(),
+            scale_factor: 1.,
+            monitor: None,
         }
     }
 }
 
+impl WindowState {
+    /// The saved position in the current coordinates of `saved`, the monitor it was saved
+    /// relative to, now at `origin` with `scale_factor`.
+    ///
+    /// The monitor may have moved since, e.g. when displays were rearranged, or changed its
+    /// scale factor, which scales the window's offset from the monitor's top-left corner.
+    fn position_on(
+        &self,
+        saved: &MonitorState,
+        origin: PhysicalPosition<i32>,
+        scale_factor: f64,
+    ) -> PhysicalPosition<i32> {
+        let scale = scale_factor / self.scale_factor;
+        PhysicalPosition {
+            x: origin.x + ((self.x - saved.x) as f64 * scale).round() as i32,
+            y: origin.y + ((self.y - saved.y) as f64 * scale).round() as i32,
+        }
+    }
+
+    /// The saved logical size in physical pixels at `scale_factor`.
+    fn size_at(&self, scale_factor: f64) -> PhysicalSize<u32> {
+        LogicalSize {
+            width: self.width,
+            height: self.height,
+        }
+        .to_physical(scale_factor)
+    }
+}
+
 struct WindowStateCache(Arc<Mutex<HashMap<String, WindowState>>>);
+
+/// The options of the plugin that affect how windows are restored.
+struct Config {
+    allow_offscreen: bool,
+    /// Whether restoring a window shows it, if the state flags include
+    /// [`StateFlags::VISIBLE`] and it was visible when it was saved.
+    show_after_restore: bool,
+}
+
+/// Which windows the plugin saves and restores.
+#[derive(Default)]
+struct Tracking {
+    /// Only these windows are tracked if set, otherwise all windows not in the denylist.
+    allowlist: Option<HashSet<String>>,
+    denylist: HashSet<String>,
+}
+
+impl Tracking {
+    fn is_tracked(&self, label: &str) -> bool {
+        !self.denylist.contains(label)
+            && self
+                .allowlist
+                .as_ref()
+                .map_or(true, |allowlist| allowlist.contains(label))
+    }
+}
+
 pub trait AppHandleExt {
     /// Saves all open windows state to disk
     fn save_window_state(&self, flags: StateFlags) -> Result<()>;
+    /// Starts saving and restoring the state of the window with the given label, e.g. after
+    /// it was excluded with [`Builder::with_denylist`].
+    fn track_window(&self, label: &str);
+    /// Stops saving and restoring the state of the window with the given label and removes
+    /// its saved state on the next save.
+    fn untrack_window(&self, label: &str);
+    /// Removes the saved state of all windows, returning whether there was any.
+    ///
+    /// Windows that are open keep their current state but it isn't saved anymore until
+    /// the app is restarted or they are tracked again with [`AppHandleExt::track_window`].
+    fn clear_state(&self) -> Result<bool>;
+    /// Removes the saved state of the window with the given label, returning whether there
+    /// was any.
+    ///
+    /// See [`AppHandleExt::clear_state`] for what happens if the window is open.
+    fn clear_state_for(&self, label: &str) -> Result<bool>;
 }
 
 impl<R: Runtime> AppHandleExt for tauri::AppHandle<R> {
     fn save_window_state(&self, flags: StateFlags) -> Result<()> {
-        if let Some(app_dir) = self.path_resolver().app_config_dir() {
-            let state_path = app_dir.join(STATE_FILENAME);
-            let cache = self.state::<WindowStateCache>();
-            let mut state = cache.0.lock().unwrap();
-            for (label, s) in state.iter_mut() {
-                if let Some(window) = self.get_window(label) {
-                    window.update_state(s, flags)?;
-                }
+        let cache = self.state::<WindowStateCache>();
+        let labels: Vec<String> = cache.0.lock().unwrap().keys().cloned().collect();
+        for label in labels {
+            if let Some(window) = self.get_window(&label) {
+                window.update_cached_state(flags)?;
             }
+        }
+        let state = cache.0.lock().unwrap().clone();
+        self.state::<StateFile>().write(self, &state)
+    }
 
-            create_dir_all(&app_dir)
-                .map_err(Error::Io)
-                .and_then(|_| File::create(state_path).map_err(Into::into))
-                .and_then(|mut f| {
-                    f.write_all(&bincode::serialize(&*state).map_err(Error::Bincode)?)
-                        .map_err(Into::into)
-                })
-        } else {
-            Ok(())
+    fn track_window(&self, label: &str) {
+        {
+            let tracking = self.state::<Mutex<Tracking>>();
+            let mut tracking = tracking.lock().unwrap();
+            tracking.denylist.remove(label);
+            if let Some(allowlist) = &mut tracking.allowlist {
+                allowlist.insert(label.into());
+            }
         }
+
+        // the event handlers only update windows that have a state
+        let cache = self.state::<WindowStateCache>();
+        let mut c = cache.0.lock().unwrap();
+        c.entry(label.into()).or_insert_with(WindowState::default);
+    }
+
+    fn untrack_window(&self, label: &str) {
+        {
+            let tracking = self.state::<Mutex<Tracking>>();
+            let mut tracking = tracking.lock().unwrap();
+            tracking.denylist.insert(label.into());
+            if let Some(allowlist) = &mut tracking.allowlist {
+                allowlist.remove(label);
+            }
+        }
+
+        let cache = self.state::<WindowStateCache>();
+        cache.0.lock().unwrap().remove(label);
+    }
+
+    fn clear_state(&self) -> Result<bool> {
+        let cached = {
+            let cache = self.state::<WindowStateCache>();
+            let mut c = cache.0.lock().unwrap();
+            let cached = c.values().any(|state| *state != WindowState::default());
+            c.clear();
+            cached
+        };
+        let saved = self.state::<StateFile>().clear(self, None)?;
+        Ok(cached || saved)
+    }
+
+    fn clear_state_for(&self, label: &str) -> Result<bool> {
+        let cache = self.state::<WindowStateCache>();
+        let cached = cache
+            .0
+            .lock()
+            .unwrap()
+            .remove(label)
+            .map_or(false, |state| state != WindowState::default());
+        let saved = self.state::<StateFile>().clear(self, Some(label))?;
+        Ok(cached || saved)
+    }
+}
+
+/// Saves the state of a window once it stopped moving and resizing for a while.
+struct DebouncedSave {
+    delay: Duration,
+    deadline: Arc<Mutex<Option<Instant>>>,
+}
+
+impl DebouncedSave {
+    fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            deadline: Default::default(),
+        }
+    }
+
+    /// Pushes the save back by the delay, starting a timer if none is pending.
+    fn schedule<R: Runtime>(&self, window: &Window<R>, flags: StateFlags) {
+        let mut deadline = self.deadline.lock().unwrap();
+        let pending = deadline.is_some();
+        *deadline = Some(Instant::now() + self.delay);
+        if pending {
+            return;
+        }
+
+        let deadline = self.deadline.clone();
+        let window = window.clone();
+        thread::spawn(move || {
+            loop {
+                let wait = {
+                    let mut deadline = deadline.lock().unwrap();
+                    match deadline.and_then(|d| d.checked_duration_since(Instant::now())) {
+                        Some(wait) => wait,
+                        None => {
+                            *deadline = None;
+                            break;
+                        }
+                    }
+                };
+                thread::sleep(wait);
+            }
+
+            // minimized windows report bogus positions on some platforms, e.g. -32000 on Windows
+            if window.is_minimized().unwrap_or(false) {
+                return;
+            }
+            if let Ok(true) = window.update_cached_state(flags) {
+                let state = window.state::<WindowStateCache>().0.lock().unwrap().clone();
+                let app = window.app_handle();
+                let _ = app.state::<StateFile>().write(&app, &state);
+            }
+        });
     }
 }
 
 pub trait WindowExt {
     /// Restores this window state from disk
     fn restore_state(&self, flags: StateFlags) -> tauri::Result<()>;
+    /// Sets whether this window is always on top and remembers it for
+    /// [`StateFlags::ALWAYS_ON_TOP`].
+    fn set_always_on_top_tracked(&self, always_on_top: bool) -> tauri::Result<()>;
 }
 
 impl<R: Runtime> WindowExt for Window<R> {
     fn restore_state(&self, flags: StateFlags) -> tauri::Result<()> {
+        let label = self.label().to_string();
+        match self.restore_saved_state(flags) {
+            Ok(true) => {
+                let restored = Restored {
+                    label,
+                    position: self.outer_position()?,
+                    size: self.inner_size()?,
+                    flags: flags.bits(),
+                };
+                let _ = self.emit_all(RESTORED_EVENT, restored);
+                Ok(())
+            }
+            Ok(false) => {
+                let error = format!("No saved state for window with label: {}", label);
+                let _ = self.emit_all(ERROR_EVENT, StateError { label, error });
+                Ok(())
+            }
+            Err(e) => {
+                let error = e.to_string();
+                let _ = self.emit_all(ERROR_EVENT, StateError { label, error });
+                Err(e)
+            }
+        }
+    }
+
+    fn set_always_on_top_tracked(&self, always_on_top: bool) -> tauri::Result<()> {
+        self.set_always_on_top(always_on_top)?;
         let cache = self.state::<WindowStateCache>();
         let mut c = cache.0.lock().unwrap();
+        if let Some(state) = c.get_mut(self.label()) {
+            state.always_on_top = Some(always_on_top);
+        }
+        Ok(())
+    }
+}
+
+trait WindowExtInternal {
+    fn restore_saved_state(&self, flags: StateFlags) -> tauri::Result<bool>;
+    fn snapshot_state(&self, flags: StateFlags) -> tauri::Result<StateSnapshot>;
+    fn update_cached_state(&self, flags: StateFlags) -> tauri::Result<bool>;
+    fn center_on_primary_monitor(&self) -> tauri::Result<()>;
+}
+
+impl<R: Runtime> WindowExtInternal for Window<R> {
+    /// Restores the saved state of this window, returning whether there was one.
+    fn restore_saved_state(&self, flags: StateFlags) -> tauri::Result<bool> {
+        // the window is restored from a copy, the cache must not be locked while the window
+        // is queried or changed, as the event handlers lock it on the main thread
+        let cache = self.state::<WindowStateCache>();
+        let saved = cache.0.lock().unwrap().get(self.label()).cloned();
 
         let mut should_show = true;
+        let mut restored = false;
 
-        if let Some(state) = c.get(self.label()) {
+        if let Some(state) = &saved {
             // avoid restoring the default zeroed state
-            if *state == WindowState::default() {
-                return Ok(());
+            if state == &WindowState::default() {
+                return Ok(false);
             }
 
             if flags.contains(StateFlags::DECORATIONS) {
                 self.set_decorations(state.decorated)?;
             }
 
-            if flags.contains(StateFlags::SIZE) {
-                self.set_size(LogicalSize {
-                    width: state.width,
-                    height: state.height,
-                })?;
-            }
+            let monitors = self.available_monitors()?;
+            // where the window is restored to and on which monitor
+            let mut position = None;
+            let mut monitor = None;
 
             if flags.contains(StateFlags::POSITION) {
-                // restore position to saved value if saved monitor exists
-                // otherwise, let the OS decide where to place the window
-                for m in self.available_monitors()? {
-                    if m.contains((state.x, state.y).into()) {
-                        self.set_position(PhysicalPosition {
-                            x: state.x,
-                            y: state.y,
-                        })?;
+                match &state.monitor {
+                    Some(saved) => {
+                        // translate the position into the saved monitor's current coordinate
+                        // space, it may have moved since, e.g. when displays were rearranged,
+                        // or changed its scale factor
+                        if let Some(m) = monitors.iter().find(|m| saved.is(m)) {
+                            position =
+                                Some(state.position_on(saved, *m.position(), m.scale_factor()));
+                            monitor = Some(m.clone());
+                        }
+                    }
+                    None => {
+                        // restore position to saved value if saved monitor exists
+                        // otherwise, let the OS decide where to place the window
+                        if let Some(m) = monitors
+                            .iter()
+                            .find(|m| m.contains((state.x, state.y).into()))
+                        {
+                            position = Some(PhysicalPosition {
+                                x: state.x,
+                                y: state.y,
+                            });
+                            monitor = Some(m.clone());
+                        }
                     }
                 }
             }
 
+            // windows that were maximized the whole time have no size to return to
+            let restore_size =
+                flags.contains(StateFlags::SIZE) && state.width > 0. && state.height > 0.;
+            // the window may not be on the monitor it is restored to yet, so convert the
+            // logical size with that monitor's scale factor rather than its own
+            let size_on = |monitor: &Option<Monitor>| -> tauri::Result<PhysicalSize<u32>> {
+                if !restore_size {
+                    return self.outer_size();
+                }
+                let scale_factor = match monitor {
+                    Some(m) => m.scale_factor(),
+                    None => self.scale_factor()?,
+                };
+                Ok(state.size_at(scale_factor))
+            };
+
+            // center the window on the primary monitor if its saved monitor is gone or it
+            // would end up (mostly) off-screen
+            let center = match position {
+                Some(position) => {
+                    let size = size_on(&monitor)?;
+                    !self.state::<Config>().allow_offscreen
+                        && !monitors.iter().any(|m| m.shows_title_bar(position, size))
+                }
+                None => flags.contains(StateFlags::POSITION) && state.monitor.is_some(),
+            };
+            if center {
+                position = None;
+                monitor = self.primary_monitor()?;
+            }
+
+            if let Some(position) = position {
+                self.set_position(position)?;
+            }
+
+            if restore_size {
+                self.set_size(size_on(&monitor)?)?;
+            }
+
+            if center {
+                self.center_on_primary_monitor()?;
+            }
+
             if flags.contains(StateFlags::MAXIMIZED) && state.maximized {
                 self.maximize()?;
             }
 
+            if flags.contains(StateFlags::ALWAYS_ON_TOP) {
+                if let Some(always_on_top) = state.always_on_top {
+                    self.set_always_on_top(always_on_top)?;
+                }
+            }
+
+            // entering fullscreen last makes leaving it return to the restored geometry
             if flags.contains(StateFlags::FULLSCREEN) {
                 self.set_fullscreen(state.fullscreen)?;
             }
 
             should_show = state.visible;
+            restored = true;
         } else {
             let mut metadata = WindowState::default();
+            let is_maximized = self.is_maximized()?;
 
-            if flags.contains(StateFlags::SIZE) {
-                let scale_factor = self
-                    .current_monitor()?
-                    .map(|m| m.scale_factor())
-                    .unwrap_or(1.);
+            if flags.contains(StateFlags::SIZE) && !is_maximized {
+                let scale_factor = self.scale_factor()?;
                 let size = self.inner_size()?.to_logical(scale_factor);
                 metadata.width = size.width;
                 metadata.height = size.height;
+                metadata.scale_factor = scale_factor;
             }
 
-            if flags.contains(StateFlags::POSITION) {
+            if flags.contains(StateFlags::POSITION) && !is_maximized {
                 let pos = self.outer_position()?;
                 metadata.x = pos.x;
                 metadata.y = pos.y;
+                metadata.monitor = self.current_monitor()?.as_ref().map(Into::into);
+                metadata.scale_factor = self.scale_factor()?;
             }
 
             if flags.contains(StateFlags::MAXIMIZED) {
-                metadata.maximized = self.is_maximized()?;
+                metadata.maximized = is_maximized;
             }
 
             if flags.contains(StateFlags::VISIBLE) {
@@ -197,56 +588,73 @@ impl<R: Runtime> WindowExt for Window<R> {
                 metadata.fullscreen = self.is_fullscreen()?;
             }
 
-            c.insert(self.label().into(), metadata);
+            let tracking = self.state::<Mutex<Tracking>>();
+            if tracking.lock().unwrap().is_tracked(self.label()) {
+                cache
+                    .0
+                    .lock()
+                    .unwrap()
+                    .insert(self.label().into(), metadata);
+            }
         }
 
-        if flags.contains(StateFlags::VISIBLE) && should_show {
+        if flags.contains(StateFlags::VISIBLE)
+            && should_show
+            && self.state::<Config>().show_after_restore
+        {
             self.show()?;
             self.set_focus()?;
         }
 
-        Ok(())
+        Ok(restored)
     }
-}
 
-trait WindowExtInternal {
-    fn update_state(&self, state: &mut WindowState, flags: StateFlags) -> tauri::Result<()>;
-}
+    fn center_on_primary_monitor(&self) -> tauri::Result<()> {
+        let monitor = match self.primary_monitor()? {
+            Some(monitor) => monitor,
+            None => return self.center(),
+        };
+        let PhysicalPosition { x, y } = *monitor.position();
+        let PhysicalSize { width, height } = *monitor.size();
+        let size = self.outer_size()?;
+        self.set_position(PhysicalPosition {
+            x: x + (width as i32 - size.width as i32) / 2,
+            y: y + (height as i32 - size.height as i32) / 2,
+        })
+    }
 
-impl<R: Runtime> WindowExtInternal for Window<R> {
-    fn update_state(&self, state: &mut WindowState, flags: StateFlags) -> tauri::Result<()> {
-        let is_maximized = match flags.intersects(StateFlags::MAXIMIZED | StateFlags::SIZE) {
+    fn snapshot_state(&self, flags: StateFlags) -> tauri::Result<StateSnapshot> {
+        let mut snapshot = StateSnapshot::default();
+        let is_maximized = match flags
+            .intersects(StateFlags::MAXIMIZED | StateFlags::SIZE | StateFlags::POSITION)
+        {
             true => self.is_maximized()?,
             false => false,
         };
 
         if flags.contains(StateFlags::MAXIMIZED) {
-            state.maximized = is_maximized;
+            snapshot.maximized = Some(is_maximized);
         }
 
         if flags.contains(StateFlags::FULLSCREEN) {
-            state.fullscreen = self.is_fullscreen()?;
+            snapshot.fullscreen = Some(self.is_fullscreen()?);
         }
 
         if flags.contains(StateFlags::DECORATIONS) {
-            state.decorated = self.is_decorated()?;
+            snapshot.decorated = Some(self.is_decorated()?);
         }
 
         if flags.contains(StateFlags::VISIBLE) {
-            state.visible = self.is_visible()?;
+            snapshot.visible = Some(self.is_visible()?);
         }
 
         if flags.contains(StateFlags::SIZE) {
-            let scale_factor = self
-                .current_monitor()?
-                .map(|m| m.scale_factor())
-                .unwrap_or(1.);
+            let scale_factor = self.scale_factor()?;
             let size = self.inner_size()?.to_logical(scale_factor);
 
             // It doesn't make sense to save a self with 0 height or width
             if size.width > 0. && size.height > 0. && !is_maximized {
-                state.width = size.width;
-                state.height = size.height;
+                snapshot.size = Some((size, scale_factor));
             }
         }
 
@@ -255,21 +663,90 @@ impl<R: Runtime> WindowExtInternal for Window<R> {
             if let Ok(Some(monitor)) = self.current_monitor() {
                 // save only window positions that are inside the current monitor
                 if monitor.contains(position) && !is_maximized {
-                    state.x = position.x;
-                    state.y = position.y;
+                    snapshot.position = Some((position, (&monitor).into(), self.scale_factor()?));
                 }
             }
         }
 
-        Ok(())
+        Ok(snapshot)
+    }
+
+    /// Updates the cached state of this window, returning whether it has one.
+    ///
+    /// The window is queried before the cache is locked, the event handlers lock it on the
+    /// main thread, which the getters wait for.
+    fn update_cached_state(&self, flags: StateFlags) -> tauri::Result<bool> {
+        let cache = self.state::<WindowStateCache>();
+        if !cache.0.lock().unwrap().contains_key(self.label()) {
+            return Ok(false);
+        }
+        let snapshot = self.snapshot_state(flags)?;
+        match cache.0.lock().unwrap().get_mut(self.label()) {
+            Some(state) => {
+                snapshot.apply(state);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// The parts of a window's state selected by the [`StateFlags`], queried without holding
+/// the cache lock and applied to the cached state afterwards.
+#[derive(Default)]
+struct StateSnapshot {
+    maximized: Option<bool>,
+    fullscreen: Option<bool>,
+    decorated: Option<bool>,
+    visible: Option<bool>,
+    /// The logical size and the scale factor it was measured with.
+    size: Option<(LogicalSize<f64>, f64)>,
+    /// The position, the monitor it is on and that monitor's scale factor.
+    position: Option<(PhysicalPosition<i32>, MonitorState, f64)>,
+}
+
+impl StateSnapshot {
+    fn apply(self, state: &mut WindowState) {
+        if let Some(maximized) = self.maximized {
+            state.maximized = maximized;
+        }
+        if let Some(fullscreen) = self.fullscreen {
+            state.fullscreen = fullscreen;
+        }
+        if let Some(decorated) = self.decorated {
+            state.decorated = decorated;
+        }
+        if let Some(visible) = self.visible {
+            state.visible = visible;
+        }
+        if let Some((size, scale_factor)) = self.size {
+            state.width = size.width;
+            state.height = size.height;
+            state.scale_factor = scale_factor;
+        }
+        if let Some((position, monitor, scale_factor)) = self.position {
+            state.x = position.x;
+            state.y = position.y;
+            state.monitor = Some(monitor);
+            state.scale_factor = scale_factor;
+        }
     }
 }
 
 #[derive(Default)]
 pub struct Builder {
+    allowlist: Option<HashSet<String>>,
     denylist: HashSet<String>,
     skip_initial_state: HashSet<String>,
     state_flags: StateFlags,
+    save_debounce: Option<Duration>,
+    filename: Option<String>,
+    state_dir: Option<PathBuf>,
+    file_per_window: bool,
+    allow_offscreen: bool,
+    hide_while_restoring: bool,
+    skip_show: bool,
+    persistence: Persistence,
 }
 
 impl Builder {
@@ -286,72 +763,184 @@ impl Builder {
         self
     }
 
+    /// Sets a list of windows that should be tracked and managed by this plugin, instead of
+    /// all windows that aren't in the denylist.
+    ///
+    /// Use [`AppHandleExt::track_window`] to track more windows later on.
+    pub fn with_allowlist(mut self, allowlist: &[&str]) -> Self {
+        self.allowlist = Some(allowlist.iter().map(|l| l.to_string()).collect());
+        self
+    }
+
     /// Adds the given window label to a list of windows to skip initial state restore.
     pub fn skip_initial_state(mut self, label: &str) -> Self {
         self.skip_initial_state.insert(label.into());
         self
     }
 
+    /// Additionally saves a window's state once it hasn't been moved or resized for `delay`,
+    /// so it isn't lost if the app doesn't exit gracefully, e.g. when it crashes.
+    ///
+    /// Without this the state is only saved when windows are closed and the app exits.
+    pub fn debounced_save(mut self, delay: Duration) -> Self {
+        self.save_debounce = Some(delay);
+        self
+    }
+
+    /// Sets the name of the state file, [`STATE_FILENAME`] by default.
+    pub fn with_filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// Sets the directory the state file is stored in, the app config directory by default.
+    pub fn with_state_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.state_dir = Some(dir.into());
+        self
+    }
+
+    /// Stores the state of each window in its own file, `<label>.json` in a directory named
+    /// after the state file, so the saved state of a single window can be removed easily.
+    pub fn with_file_per_window(mut self) -> Self {
+        self.file_per_window = true;
+        self
+    }
+
+    /// Sets where the state of windows is kept, [`Persistence::File`] by default.
+    ///
+    /// With [`Persistence::Memory`] the state file options have no effect and saving the
+    /// state only updates the state kept in memory.
+    pub fn with_persistence(mut self, persistence: Persistence) -> Self {
+        self.persistence = persistence;
+        self
+    }
+
+    /// Restores windows to their saved position even if they would end up off-screen.
+    ///
+    /// By default windows whose title bar wouldn't be on any monitor are centered on the
+    /// primary monitor instead.
+    pub fn allow_offscreen(mut self) -> Self {
+        self.allow_offscreen = true;
+        self
+    }
+
+    /// Hides windows that are visible when they are created while their saved state is
+    /// restored, and shows them again afterwards, see [`Builder::show_after_restore`].
+    ///
+    /// The plugin only gets to windows once their webview is ready, so a window created
+    /// visible has already been shown with its initial position and size by then: this
+    /// trades the jump to the restored geometry for a brief flicker. Create windows with
+    /// `visible: false` to avoid both; with [`StateFlags::VISIBLE`] the plugin shows them
+    /// once restored if they were visible when saved.
+    pub fn hide_while_restoring(mut self, hide_while_restoring: bool) -> Self {
+        self.hide_while_restoring = hide_while_restoring;
+        self
+    }
+
+    /// Sets whether restoring a window shows it if it was visible when its state was saved,
+    /// which requires [`StateFlags::VISIBLE`]. Enabled by default.
+    ///
+    /// Disable it to show windows yourself once the app is ready.
+    pub fn show_after_restore(mut self, show: bool) -> Self {
+        self.skip_show = !show;
+        self
+    }
+
     pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
         let flags = self.state_flags;
+        let config = Config {
+            allow_offscreen: self.allow_offscreen,
+            show_after_restore: !self.skip_show,
+        };
+        let tracking = Tracking {
+            allowlist: self.allowlist,
+            denylist: self.denylist,
+        };
+        let state_file = StateFile::new(
+            self.state_dir,
+            self.filename.unwrap_or_else(|| STATE_FILENAME.into()),
+            self.file_per_window,
+            self.persistence,
+        );
         PluginBuilder::new("window-state")
             .invoke_handler(tauri::generate_handler![
                 cmd::save_window_state,
-                cmd::restore_state
+                cmd::restore_state,
+                cmd::clear_state,
+                cmd::clear_state_for
             ])
-            .setup(|app| {
-                let cache: Arc<Mutex<HashMap<String, WindowState>>> = if let Some(app_dir) =
-                    app.path_resolver().app_config_dir()
-                {
-                    let state_path = app_dir.join(STATE_FILENAME);
-                    if state_path.exists() {
-                        Arc::new(Mutex::new(
-                            tauri::api::file::read_binary(state_path)
-                                .map_err(Error::TauriApi)
-                                .and_then(|state| bincode::deserialize(&state).map_err(Into::into))
-                                .unwrap_or_default(),
-                        ))
-                    } else {
-                        Default::default()
-                    }
-                } else {
-                    Default::default()
-                };
+            .setup(move |app| {
+                let cache = Arc::new(Mutex::new(state_file.read(app)));
                 app.manage(WindowStateCache(cache));
+                app.manage(state_file);
+                app.manage(Mutex::new(tracking));
+                app.manage(config);
                 Ok(())
             })
             .on_webview_ready(move |window| {
-                if self.denylist.contains(window.label()) {
-                    return;
-                }
-
-                if !self.skip_initial_state.contains(window.label()) {
-                    let _ = window.restore_state(self.state_flags);
-                }
-
                 let cache = window.state::<WindowStateCache>();
                 let cache = cache.0.clone();
                 let label = window.label().to_string();
                 let window_clone = window.clone();
                 let flags = self.state_flags;
 
-                // insert a default state if this window should be tracked and
-                // the disk cache doesn't have a state for it
-                {
+                // windows that aren't tracked yet get the event handlers as well, they are
+                // no-ops until the window is tracked and has a state
+                let is_tracked = window
+                    .state::<Mutex<Tracking>>()
+                    .lock()
+                    .unwrap()
+                    .is_tracked(&label);
+                if is_tracked {
+                    if !self.skip_initial_state.contains(window.label()) {
+                        // hide windows that are already visible while restoring them, so
+                        // they don't jump from their initial geometry to the restored one
+                        let hide = self.hide_while_restoring
+                            && window.is_visible().unwrap_or(false)
+                            && cache
+                                .lock()
+                                .unwrap()
+                                .get(&label)
+                                .map_or(false, |state| *state != WindowState::default());
+                        if hide {
+                            let _ = window.hide();
+                        }
+                        let _ = window.restore_state(self.state_flags);
+                        if hide
+                            && !self.state_flags.contains(StateFlags::VISIBLE)
+                            && !self.skip_show
+                        {
+                            let _ = window.show();
+                        }
+                    }
+
+                    // insert a default state if this window should be tracked and
+                    // the disk cache doesn't have a state for it
                     cache
                         .lock()
                         .unwrap()
-                        .entry(label.clone())
+                        .entry(label)
                         .or_insert_with(WindowState::default);
                 }
 
-                window.on_window_event(move |e| {
-                    if let WindowEvent::CloseRequested { .. } = e {
-                        let mut c = cache.lock().unwrap();
-                        if let Some(state) = c.get_mut(&label) {
-                            let _ = window_clone.update_state(state, flags);
+                let debounced_save = self.save_debounce.map(DebouncedSave::new);
+
+                window.on_window_event(move |e| match e {
+                    WindowEvent::CloseRequested { .. } => {
+                        let _ = window_clone.update_cached_state(flags);
+                    }
+                    WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+                        // keep track of the geometry before the window gets maximized,
+                        // the snapshot leaves it alone while the window is maximized
+                        if !window_clone.is_minimized().unwrap_or(false) {
+                            let geometry = StateFlags::SIZE | StateFlags::POSITION;
+                            let _ = window_clone.update_cached_state(flags & geometry);
+                        }
+                        if let Some(debounced_save) = &debounced_save {
+                            debounced_save.schedule(&window_clone, flags);
                         }
                     }
+                    _ => {}
                 });
             })
             .on_event(move |app, event| {
@@ -363,8 +952,13 @@ impl Builder {
     }
 }
 
+/// How much of a window's title bar must be on a monitor for the window to count as
+/// visible, in physical pixels.
+const MIN_VISIBLE: i32 = 64;
+
 trait MonitorExt {
     fn contains(&self, position: PhysicalPosition<i32>) -> bool;
+    fn shows_title_bar(&self, position: PhysicalPosition<i32>, size: PhysicalSize<u32>) -> bool;
 }
 
 impl MonitorExt for Monitor {
@@ -377,4 +971,126 @@ impl MonitorExt for Monitor {
             && y < position.y as _
             && position.y < (y + height as i32)
     }
+
+    /// Whether at least [`MIN_VISIBLE`] by [`MIN_VISIBLE`] pixels at the top of a window
+    /// with the given position and size are on this monitor, so it can be dragged by them.
+    fn shows_title_bar(&self, position: PhysicalPosition<i32>, size: PhysicalSize<u32>) -> bool {
+        let PhysicalPosition { x, y } = *self.position();
+        let PhysicalSize { width, height } = *self.size();
+        let min_width = MIN_VISIBLE.min(size.width as i32);
+        let min_height = MIN_VISIBLE.min(size.height as i32);
+
+        let visible_width =
+            (position.x + size.width as i32).min(x + width as i32) - position.x.max(x);
+        let visible_height = (position.y + min_height).min(y + height as i32) - position.y.max(y);
+        visible_width >= min_width && visible_height >= min_height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(x: i32, y: i32) -> MonitorState {
+        MonitorState {
+            name: Some("DISPLAY1".into()),
+            x,
+            y,
+            width: 3840,
+            height: 2160,
+        }
+    }
+
+    /// The state saved for a window restored with `restored` onto `monitor` at
+    /// `scale_factor`, keeping its logical size.
+    fn saved_as(
+        state: &WindowState,
+        restored: PhysicalPosition<i32>,
+        monitor: MonitorState,
+        scale_factor: f64,
+    ) -> WindowState {
+        WindowState {
+            x: restored.x,
+            y: restored.y,
+            scale_factor,
+            monitor: Some(monitor),
+            ..state.clone()
+        }
+    }
+
+    #[test]
+    fn restore_across_scale_factors() {
+        // saved at 1x on a monitor at (-1920, 0), which is now at 2x and moved to (0, -400)
+        let at_1x = WindowState {
+            width: 800.,
+            height: 600.,
+            x: -1820,
+            y: 50,
+            scale_factor: 1.,
+            monitor: Some(monitor(-1920, 0)),
+            ..Default::default()
+        };
+        let position =
+            at_1x.position_on(&monitor(-1920, 0), PhysicalPosition { x: 0, y: -400 }, 2.);
+        assert_eq!(position, PhysicalPosition { x: 200, y: -300 });
+        assert_eq!(
+            at_1x.size_at(2.),
+            PhysicalSize {
+                width: 1600,
+                height: 1200
+            }
+        );
+
+        // saved again at 2x and restored after the monitor went back to 1x at (-1920, 0)
+        let at_2x = saved_as(&at_1x, position, monitor(0, -400), 2.);
+        let position =
+            at_2x.position_on(&monitor(0, -400), PhysicalPosition { x: -1920, y: 0 }, 1.);
+        assert_eq!(position, PhysicalPosition { x: -1820, y: 50 });
+        assert_eq!(
+            at_2x.size_at(1.),
+            PhysicalSize {
+                width: 800,
+                height: 600
+            }
+        );
+    }
+
+    #[test]
+    fn restore_from_2x_to_1x_and_back() {
+        let at_2x = WindowState {
+            width: 640.,
+            height: 480.,
+            x: 2560 + 400,
+            y: 300,
+            scale_factor: 2.,
+            monitor: Some(monitor(2560, 0)),
+            ..Default::default()
+        };
+        let position = at_2x.position_on(&monitor(2560, 0), PhysicalPosition { x: 2560, y: 0 }, 1.);
+        assert_eq!(position, PhysicalPosition { x: 2760, y: 150 });
+        assert_eq!(
+            at_2x.size_at(1.),
+            PhysicalSize {
+                width: 640,
+                height: 480
+            }
+        );
+
+        let at_1x = saved_as(&at_2x, position, monitor(2560, 0), 1.);
+        let position = at_1x.position_on(&monitor(2560, 0), PhysicalPosition { x: 2560, y: 0 }, 2.);
+        assert_eq!(
+            position,
+            PhysicalPosition {
+                x: at_2x.x,
+                y: at_2x.y
+            }
+        );
+        assert_eq!(
+            at_1x.size_at(2.),
+            PhysicalSize {
+                width: 1280,
+                height: 960
+            }
+        );
+    }
 }
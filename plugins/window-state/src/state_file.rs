@@ -0,0 +1,423 @@
+// Copyright 2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{Error, Persistence, Result, Saved, WindowState, SAVED_EVENT};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use std::{
+    collections::HashMap,
+    fs::{self, create_dir_all, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    process,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Where and how the window state is stored on disk.
+pub(crate) struct StateFile {
+    dir: Option<PathBuf>,
+    filename: String,
+    per_window: bool,
+    persistence: Persistence,
+    /// The state as this instance of the app last read or wrote it, to tell which windows
+    /// it changed since.
+    synced: Mutex<HashMap<String, WindowState>>,
+}
+
+impl StateFile {
+    pub(crate) fn new(
+        dir: Option<PathBuf>,
+        filename: String,
+        per_window: bool,
+        persistence: Persistence,
+    ) -> Self {
+        Self {
+            dir,
+            filename,
+            per_window,
+            persistence,
+            synced: Default::default(),
+        }
+    }
+
+    /// The state file, or the directory of per-window files, `None` if the state isn't
+    /// stored on disk.
+    pub(crate) fn path<R: Runtime>(&self, app: &AppHandle<R>) -> Option<PathBuf> {
+        if self.persistence == Persistence::Memory {
+            return None;
+        }
+        self.dir
+            .clone()
+            .or_else(|| app.path_resolver().app_config_dir())
+            .map(|dir| dir.join(&self.filename))
+    }
+
+    /// Reads the saved state of all windows, which is empty if nothing was saved yet.
+    ///
+    /// Files that can't be parsed are moved aside to `<name>.corrupt-<timestamp>` and ignored,
+    /// files that can't be read are skipped.
+    pub(crate) fn read<R: Runtime>(&self, app: &AppHandle<R>) -> HashMap<String, WindowState> {
+        let state = match self.path(app) {
+            Some(path) if path.exists() => self.read_path(path),
+            _ => Default::default(),
+        };
+        *self.synced.lock().unwrap() = state.clone();
+        state
+    }
+
+    fn read_path(&self, path: PathBuf) -> HashMap<String, WindowState> {
+        if self.per_window {
+            fs::read_dir(path)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok())
+                        .filter(|entry| entry.path().extension().map_or(false, |e| e == "json"))
+                        .flat_map(|entry| read_file_or_skip(entry.path()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
+            read_file_or_skip(path)
+        }
+    }
+
+    /// Writes the state of the windows that changed since it was last read or written.
+    ///
+    /// Other instances of the app may have saved their windows in the meantime, so the
+    /// state on disk is merged with this instance's changes while holding a lock on it.
+    pub(crate) fn write<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        state: &HashMap<String, WindowState>,
+    ) -> Result<()> {
+        let path = match self.path(app) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+        let _lock = lock(&path)?;
+
+        let mut synced = self.synced.lock().unwrap();
+        let changed = state
+            .iter()
+            .filter(|(label, s)| synced.get(*label) != Some(*s));
+        let removed = synced.keys().filter(|label| !state.contains_key(*label));
+
+        if self.per_window {
+            create_dir_all(&path)?;
+            for (label, s) in changed {
+                let windows = HashMap::from([(label, s)]);
+                write_file(path.join(file_name(label)), &windows)?;
+            }
+            for label in removed {
+                let _ = fs::remove_file(path.join(file_name(label)));
+            }
+        } else {
+            let mut windows = if path.exists() {
+                read_file(path.clone())?
+            } else {
+                Default::default()
+            };
+            for (label, s) in changed {
+                windows.insert(label.clone(), s.clone());
+            }
+            for label in removed {
+                windows.remove(label);
+            }
+            write_file(path.clone(), &windows)?;
+        }
+
+        *synced = state.clone();
+
+        let saved = Saved {
+            labels: state.keys().cloned().collect(),
+            path,
+        };
+        let _ = app.emit_all(SAVED_EVENT, saved);
+        Ok(())
+    }
+
+    /// Removes the saved state of the window with the given label, or of all windows,
+    /// returning whether there was any.
+    pub(crate) fn clear<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        label: Option<&str>,
+    ) -> Result<bool> {
+        let path = match self.path(app) {
+            Some(path) if path.exists() => path,
+            _ => return Ok(false),
+        };
+        let _lock = lock(&path)?;
+
+        let mut synced = self.synced.lock().unwrap();
+        match label {
+            Some(label) => {
+                synced.remove(label);
+                if self.per_window {
+                    let path = path.join(file_name(label));
+                    let exists = path.exists();
+                    if exists {
+                        fs::remove_file(path)?;
+                    }
+                    Ok(exists)
+                } else {
+                    let mut windows = read_file(path.clone())?;
+                    let exists = windows.remove(label).is_some();
+                    if exists {
+                        write_file(path, &windows)?;
+                    }
+                    Ok(exists)
+                }
+            }
+            None => {
+                synced.clear();
+                if self.per_window {
+                    fs::remove_dir_all(path)?;
+                } else {
+                    fs::remove_file(path)?;
+                }
+                Ok(true)
+            }
+        }
+    }
+}
+
+/// The version of the state file format.
+///
+/// Version 0 is the bincode encoded map of window states and version 1 the same map encoded
+/// as JSON, both without a version field.
+const VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct Contents<W> {
+    version: u32,
+    windows: W,
+}
+
+/// Reads the state at `path`, moving the file aside if its contents can't be parsed.
+///
+/// IO errors are returned instead, the file may be fine and only temporarily unreadable,
+/// e.g. while another process holds it open.
+fn read_file(path: PathBuf) -> Result<HashMap<String, WindowState>> {
+    let bytes = fs::read(&path)?;
+    match deserialize_state(&bytes) {
+        Ok(state) => Ok(state),
+        Err(e) => {
+            log::warn!("failed to parse window state from {}: {e}", path.display());
+            quarantine(&path);
+            Ok(Default::default())
+        }
+    }
+}
+
+/// Like [`read_file`], but skips files that can't be read.
+fn read_file_or_skip(path: PathBuf) -> HashMap<String, WindowState> {
+    read_file(path.clone()).unwrap_or_else(|e| {
+        log::warn!("failed to read window state from {}: {e}", path.display());
+        Default::default()
+    })
+}
+
+/// Moves a state file that can't be read aside, so it's neither read again nor lost.
+fn quarantine(path: &Path) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let _ = fs::rename(path, with_suffix(path, &format!(".corrupt-{timestamp}")));
+}
+
+/// Writes the state to a temporary file first and moves it over the old one once it's
+/// complete, so the old state survives if writing fails halfway.
+fn write_file<W: Serialize>(path: PathBuf, windows: &W) -> Result<()> {
+    let contents = Contents {
+        version: VERSION,
+        windows,
+    };
+    let temp_path = with_suffix(&path, &format!(".tmp-{}", process::id()));
+    let mut file = File::create(&temp_path)?;
+    file.write_all(&serde_json::to_vec(&contents).map_err(Error::SerdeJson)?)?;
+    file.sync_all()?;
+    fs::rename(temp_path, path).map_err(Into::into)
+}
+
+/// Takes an advisory lock on the state at `path` that is released when the file is dropped.
+fn lock(path: &Path) -> Result<File> {
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(with_suffix(path, ".lock"))?;
+    file.lock_exclusive()?;
+    Ok(file)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+/// The name of a window's file, escaping the characters window labels may contain that
+/// aren't allowed in file names.
+fn file_name(label: &str) -> String {
+    let label = label
+        .replace('%', "%25")
+        .replace('/', "%2F")
+        .replace(':', "%3A");
+    format!("{label}.json")
+}
+
+/// The bincode encoded state of files written before the state file was stored as JSON.
+#[derive(Deserialize)]
+struct LegacyWindowState {
+    width: f64,
+    height: f64,
+    x: i32,
+    y: i32,
+    maximized: bool,
+    visible: bool,
+    decorated: bool,
+    fullscreen: bool,
+}
+
+impl From<LegacyWindowState> for WindowState {
+    fn from(state: LegacyWindowState) -> Self {
+        Self {
+            width: state.width,
+            height: state.height,
+            x: state.x,
+            y: state.y,
+            maximized: state.maximized,
+            visible: state.visible,
+            decorated: state.decorated,
+            fullscreen: state.fullscreen,
+            always_on_top: None,
+            scale_factor: 1.,
+            monitor: None,
+        }
+    }
+}
+
+/// Parses a state file of any version, migrating it to the current one.
+fn deserialize_state(bytes: &[u8]) -> Result<HashMap<String, WindowState>> {
+    match serde_json::from_slice::<Contents<HashMap<String, WindowState>>>(bytes) {
+        // fields added since default to their values in `WindowState::default`
+        Ok(contents) => Ok(contents.windows),
+        Err(e) => serde_json::from_slice(bytes).or_else(|_| {
+            bincode::deserialize::<HashMap<String, LegacyWindowState>>(bytes)
+                .map(|state| state.into_iter().map(|(k, v)| (k, v.into())).collect())
+                .map_err(|_| e.into())
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory for a single test.
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "tauri-plugin-window-state-{name}-{}",
+            process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn quarantined(dir: &Path, name: &str) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .map_or(false, |n| n.starts_with(&format!("{name}.corrupt-")))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unreadable_files_are_moved_aside() {
+        for (name, bytes) in [
+            ("garbage", &b"\x00\xffnot a window state\x7f"[..]),
+            ("empty", &b""[..]),
+        ] {
+            let dir = test_dir(name);
+            let path = dir.join(".window-state");
+            fs::write(&path, bytes).unwrap();
+
+            assert!(read_file(path.clone()).unwrap().is_empty());
+            assert!(!path.exists());
+            let moved = quarantined(&dir, ".window-state");
+            assert_eq!(moved.len(), 1);
+            assert_eq!(fs::read(&moved[0]).unwrap(), bytes);
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn read_errors_leave_files_in_place() {
+        let dir = test_dir("read-error");
+        // reading a directory fails with an IO error, like a file locked by another process
+        let path = dir.join(".window-state");
+        fs::create_dir(&path).unwrap();
+
+        assert!(read_file(path.clone()).is_err());
+        assert!(path.exists());
+        assert!(quarantined(&dir, ".window-state").is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn older_versions_are_migrated() {
+        let dir = test_dir("legacy");
+
+        // version 1, JSON without a version field or the fields added since
+        let path = dir.join("v1");
+        fs::write(
+            &path,
+            br#"{"main":{"width":800.0,"height":600.0,"x":10,"y":20,"maximized":false,"visible":true,"decorated":true,"fullscreen":false}}"#,
+        )
+        .unwrap();
+        let state = read_file(path.clone()).unwrap();
+        let main = &state["main"];
+        assert_eq!(
+            (main.width, main.height, main.x, main.y),
+            (800.0, 600.0, 10, 20)
+        );
+        assert_eq!(main.always_on_top, None);
+        assert_eq!(main.scale_factor, 1.);
+        assert!(path.exists());
+
+        // version 0, bincode
+        let path = dir.join("v0");
+        let legacy = HashMap::from([(
+            "main".to_string(),
+            (800.0f64, 600.0f64, 10i32, 20i32, true, true, false, false),
+        )]);
+        fs::write(&path, bincode::serialize(&legacy).unwrap()).unwrap();
+        let state = read_file(path.clone()).unwrap();
+        let main = &state["main"];
+        assert_eq!(
+            (main.width, main.height, main.x, main.y),
+            (800.0, 600.0, 10, 20)
+        );
+        assert!(main.maximized && !main.decorated);
+        assert!(path.exists());
+
+        assert!(quarantined(&dir, "v0").is_empty());
+        assert!(quarantined(&dir, "v1").is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}